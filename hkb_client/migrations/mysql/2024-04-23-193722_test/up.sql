@@ -0,0 +1,5 @@
+CREATE TABLE test (
+  id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+  gest VARCHAR(255) NOT NULL,
+  mest VARCHAR(255) NOT NULL
+)
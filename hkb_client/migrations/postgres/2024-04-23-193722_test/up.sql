@@ -0,0 +1,5 @@
+CREATE TABLE test (
+  id BIGSERIAL PRIMARY KEY,
+  gest VARCHAR NOT NULL,
+  mest VARCHAR NOT NULL
+)
@@ -1,17 +1,33 @@
-use hkb_core::database::services;
-use hkb_core::database::services::reminders::CreateReminderData;
+use hkb_core::database::services::reminders::{
+    AsyncReminderRepository, ChecklistEdit, CreateReminderData, ReminderChangeData,
+    ReminderChangeKind, ReminderData, ReminderOperation, ReminderRepository,
+};
 use hkb_core::logger::{debug, error, info};
 use hkb_daemon_core::frame::Event as FrameEvent;
+use hkb_date::date::SimpleDate;
 use ratatui::prelude::{Frame, Rect};
+use std::future::Future;
+use tokio::sync::mpsc;
 
 use self::reminders_create::RemindersCreate;
 use self::reminders_list::RemindersList;
+use self::reminders_snooze::RemindersSnooze;
+use self::reminders_trash::RemindersTrash;
 
 mod reminders_create;
 mod reminders_list;
+mod reminders_snooze;
+mod reminders_trash;
+
+/// Bounds of the repositories the views can use from background tasks
+trait Repository: ReminderRepository + Clone + Send + Sync + 'static {}
+
+impl<R: ReminderRepository + Clone + Send + Sync + 'static> Repository for R {}
 
 trait RemindersView {
     fn init(&mut self);
+    /// Called when the reminders changed in the background
+    fn reload(&mut self) {}
     fn update(&mut self) -> Option<Message>;
     fn render(&mut self, frame: &mut Frame, area: Rect);
 }
@@ -19,13 +35,20 @@ trait RemindersView {
 enum View {
     List,
     Create,
+    Snooze(Box<ReminderData>),
+    Trash,
 }
 
-impl From<View> for Box<dyn RemindersView> {
-    fn from(val: View) -> Self {
-        match val {
-            View::List => Box::new(RemindersList::default()),
+impl View {
+    fn into_view<R: Repository>(
+        self,
+        repository: &AsyncReminderRepository<R>,
+    ) -> Box<dyn RemindersView> {
+        match self {
+            View::List => Box::new(RemindersList::new(repository.clone())),
             View::Create => Box::new(RemindersCreate::default()),
+            View::Snooze(reminder) => Box::new(RemindersSnooze::new(*reminder)),
+            View::Trash => Box::new(RemindersTrash::new(repository.clone())),
         }
     }
 }
@@ -34,52 +57,257 @@ enum Message {
     ChangeView(View),
     DeleteReminder(i64),
     CreateReminder(CreateReminderData),
+    SnoozeReminder(i64, SimpleDate),
+    CompleteReminder(i64),
+    RestoreReminder(i64),
+    PurgeReminder(i64),
+    EditChecklist(i64, ChecklistEdit),
+    Undo,
+    Redo,
+}
+
+/// How many changes can be taken back
+const HISTORY_LIMIT: usize = 100;
+
+/// Which history the changes of a batch go to
+#[derive(Clone, Copy)]
+enum History {
+    /// A new change, after which what was undone can no longer be redone
+    Action,
+    Undo,
+    Redo,
 }
 
-pub struct RemindersApp {
+/// A batch of changes to the reminders that finished in the background
+enum Outcome {
+    Applied(History, Vec<ReminderChangeData>),
+    /// Undoing or redoing failed, so the changes stay where they were
+    Failed(History, Vec<ReminderChangeData>),
+}
+
+pub struct RemindersApp<R: ReminderRepository> {
+    repository: AsyncReminderRepository<R>,
     current_view: Box<dyn RemindersView>,
+    outcomes_sender: mpsc::UnboundedSender<Outcome>,
+    outcomes: mpsc::UnboundedReceiver<Outcome>,
+    /// Batches of changes, the latest last
+    undo_stack: Vec<Vec<ReminderChangeData>>,
+    redo_stack: Vec<Vec<ReminderChangeData>>,
+}
+
+fn push_history(stack: &mut Vec<Vec<ReminderChangeData>>, changes: Vec<ReminderChangeData>) {
+    if changes.is_empty() {
+        return;
+    }
+
+    stack.push(changes);
+
+    if stack.len() > HISTORY_LIMIT {
+        stack.remove(0);
+    }
+}
+
+fn notify_daemon(change: ReminderChangeData) {
+    let event = match (change.kind, change.after) {
+        (ReminderChangeKind::Create, Some(reminder)) => FrameEvent::ReminderCreated(reminder),
+        (ReminderChangeKind::Update, Some(reminder)) => FrameEvent::ReminderUpdated(reminder),
+        _ => FrameEvent::ReminderDeleted(change.reminder_id),
+    };
+
+    crate::singleton::send_server_msg(event);
 }
 
-impl RemindersApp {
-    pub fn new() -> Self {
-        let mut current_view: Box<dyn RemindersView> = View::List.into();
+impl<R: ReminderRepository + Clone + Send + Sync + 'static> RemindersApp<R> {
+    pub fn new(repository: R) -> Self {
+        let repository = AsyncReminderRepository::new(repository);
+        let mut current_view = View::List.into_view(&repository);
         current_view.init();
 
-        Self { current_view }
+        let (outcomes_sender, outcomes) = mpsc::unbounded_channel();
+
+        Self {
+            repository,
+            current_view,
+            outcomes_sender,
+            outcomes,
+            undo_stack: vec![],
+            redo_stack: vec![],
+        }
+    }
+
+    fn change_view(&mut self, view: View) {
+        self.current_view = view.into_view(&self.repository);
+        self.current_view.init();
+    }
+
+    /// Runs a change on the database without holding up rendering
+    fn spawn_change(&self, change: impl Future<Output = Option<Outcome>> + Send + 'static) {
+        let outcomes = self.outcomes_sender.clone();
+
+        tokio::spawn(async move {
+            if let Some(outcome) = change.await {
+                outcomes.send(outcome).unwrap_or_default();
+            }
+        });
+    }
+
+    /// Applies the operations in one batch, so they are undone together
+    fn apply_operations(&self, operations: Vec<ReminderOperation>, description: String) {
+        let repository = self.repository.clone();
+
+        self.spawn_change(async move {
+            match repository.apply_reminder_batch(operations).await {
+                Ok(changes) => Some(Outcome::Applied(History::Action, changes)),
+                Err(e) => {
+                    error!(target: "CLIENT_REMINDERS", "Failed to {description}! {e}");
+
+                    None
+                }
+            }
+        });
+    }
+
+    /// Applies the inverse of the latest changes in `history`, from the last change to the first
+    fn revert_latest(&mut self, history: History) {
+        let stack = match history {
+            History::Undo => &mut self.undo_stack,
+            _ => &mut self.redo_stack,
+        };
+        let Some(changes) = stack.pop() else {
+            debug!(target: "CLIENT_REMINDERS", "Nothing to take back.");
+
+            return;
+        };
+        let operations = changes
+            .iter()
+            .rev()
+            .map(|change| change.inverse())
+            .collect();
+        let repository = self.repository.clone();
+
+        self.spawn_change(async move {
+            match repository.apply_reminder_batch(operations).await {
+                Ok(reverted_changes) => Some(Outcome::Applied(history, reverted_changes)),
+                Err(e) => {
+                    error!(target: "CLIENT_REMINDERS", "Failed to take back {} changes! {e}", changes.len());
+
+                    Some(Outcome::Failed(history, changes))
+                }
+            }
+        });
+    }
+
+    fn receive_outcomes(&mut self) {
+        while let Ok(outcome) = self.outcomes.try_recv() {
+            match outcome {
+                Outcome::Applied(history, changes) => {
+                    for change in changes.iter().cloned() {
+                        notify_daemon(change);
+                    }
+
+                    match history {
+                        History::Action => {
+                            self.redo_stack.clear();
+                            push_history(&mut self.undo_stack, changes);
+                        }
+                        History::Undo => push_history(&mut self.redo_stack, changes),
+                        History::Redo => push_history(&mut self.undo_stack, changes),
+                    }
+                }
+                Outcome::Failed(History::Redo, changes) => {
+                    push_history(&mut self.redo_stack, changes);
+                }
+                Outcome::Failed(_, changes) => push_history(&mut self.undo_stack, changes),
+            }
+
+            self.current_view.reload();
+        }
     }
 }
 
-impl RemindersApp {
+impl<R: ReminderRepository + Clone + Send + Sync + 'static> RemindersApp<R> {
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        self.receive_outcomes();
+
         if let Some(m) = self.current_view.update() {
             match m {
-                Message::ChangeView(view) => {
-                    self.current_view = view.into();
-                    self.current_view.init();
-                }
+                Message::ChangeView(view) => self.change_view(view),
                 Message::CreateReminder(reminder) => {
                     info!(target: "CLIENT_REMINDERS", "Creating a reminder.");
                     debug!(target: "CLIENT_REMINDERS", "Received a message to create a reminder with {reminder:?}");
 
-                    if let Ok(reminder) = services::reminders::create_reminder(reminder) {
-                        crate::singleton::send_server_msg(FrameEvent::ReminderCreated(reminder));
-                    }
+                    self.apply_operations(
+                        vec![ReminderOperation::Create(reminder)],
+                        "create a reminder".to_owned(),
+                    );
+                    self.change_view(View::List);
+                }
+                Message::SnoozeReminder(reminder_id, remind_at) => {
+                    info!(target: "CLIENT_REMINDERS", "Snoozing a reminder.");
+                    debug!(target: "CLIENT_REMINDERS", "Received a message to snooze a reminder with id {reminder_id} until {remind_at}");
 
-                    self.current_view = View::List.into();
-                    self.current_view.init();
+                    self.apply_operations(
+                        vec![ReminderOperation::Snooze(reminder_id, remind_at)],
+                        format!("snooze a reminder with id {reminder_id}"),
+                    );
+                    self.change_view(View::List);
+                }
+                Message::CompleteReminder(reminder_id) => {
+                    info!(target: "CLIENT_REMINDERS", "Completing a reminder.");
+                    debug!(target: "CLIENT_REMINDERS", "Received a message to complete a reminder with id {reminder_id}");
+
+                    // the view reloads once it is done, as the reminder is no longer pending
+                    self.apply_operations(
+                        vec![ReminderOperation::Complete(reminder_id)],
+                        format!("complete a reminder with id {reminder_id}"),
+                    );
                 }
                 Message::DeleteReminder(reminder_id) => {
                     info!(target: "CLIENT_REMINDERS", "Deleting a reminder.");
                     debug!(target: "CLIENT_REMINDERS", "Received a message to delete a reminder with id {reminder_id}");
 
-                    if services::reminders::delete_reminder(reminder_id).is_ok() {
-                        crate::singleton::send_server_msg(FrameEvent::ReminderDeleted(reminder_id));
+                    // the view reloads once it is done, as we just deleted a reminder
+                    self.apply_operations(
+                        vec![ReminderOperation::Delete(reminder_id)],
+                        format!("delete a reminder with id {reminder_id}"),
+                    );
+                }
+                Message::RestoreReminder(reminder_id) => {
+                    info!(target: "CLIENT_REMINDERS", "Restoring a reminder.");
+                    debug!(target: "CLIENT_REMINDERS", "Received a message to restore a reminder with id {reminder_id}");
 
-                        // reinitialize view, as we just deleted a reminder
-                        self.current_view.init();
-                    } else {
-                        error!(target: "CLIENT_REMINDERS", "Failed to delete a reminder with id {reminder_id}!");
-                    }
+                    self.apply_operations(
+                        vec![ReminderOperation::Restore(reminder_id)],
+                        format!("restore a reminder with id {reminder_id}"),
+                    );
+                }
+                Message::PurgeReminder(reminder_id) => {
+                    info!(target: "CLIENT_REMINDERS", "Purging a reminder.");
+                    debug!(target: "CLIENT_REMINDERS", "Received a message to purge a reminder with id {reminder_id}");
+
+                    self.apply_operations(
+                        vec![ReminderOperation::Purge(reminder_id)],
+                        format!("purge a reminder with id {reminder_id}"),
+                    );
+                }
+                Message::EditChecklist(reminder_id, edit) => {
+                    info!(target: "CLIENT_REMINDERS", "Editing the checklist of a reminder.");
+                    debug!(target: "CLIENT_REMINDERS", "Received a message to edit the checklist of a reminder with id {reminder_id}: {edit:?}");
+
+                    self.apply_operations(
+                        vec![ReminderOperation::EditChecklist(reminder_id, edit)],
+                        format!("edit the checklist of a reminder with id {reminder_id}"),
+                    );
+                }
+                Message::Undo => {
+                    info!(target: "CLIENT_REMINDERS", "Undoing the last change.");
+
+                    self.revert_latest(History::Undo);
+                }
+                Message::Redo => {
+                    info!(target: "CLIENT_REMINDERS", "Redoing the last undone change.");
+
+                    self.revert_latest(History::Redo);
                 }
             }
         };
@@ -87,3 +315,48 @@ impl RemindersApp {
         self.current_view.render(frame, area);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use hkb_core::database::services::reminders::{
+        AlertOffset, InMemoryReminderRepository, ReminderPriority, ReminderQuery, TrashFilter,
+    };
+
+    use super::*;
+
+    /// Waits for the change running in the background and takes it into the history
+    async fn finish_change<R: Repository>(app: &mut RemindersApp<R>) {
+        let outcome = app.outcomes.recv().await.unwrap();
+
+        app.outcomes_sender.send(outcome).unwrap_or_default();
+        app.receive_outcomes();
+    }
+
+    #[tokio::test]
+    async fn it_leaves_nothing_in_the_trash_when_a_creation_is_undone() {
+        let repository = InMemoryReminderRepository::new();
+        let mut app = RemindersApp::new(repository.clone());
+
+        app.apply_operations(
+            vec![ReminderOperation::Create(CreateReminderData {
+                note: "Dentist".to_owned(),
+                remind_at: SimpleDate::local(),
+                recurrence: None,
+                tags: vec![],
+                priority: ReminderPriority::Normal,
+                alerts: AlertOffset::defaults(),
+                checklist: vec![],
+            })],
+            "create a reminder".to_owned(),
+        );
+        finish_change(&mut app).await;
+
+        app.revert_latest(History::Undo);
+        finish_change(&mut app).await;
+
+        let query = ReminderQuery::new().trash(TrashFilter::Include);
+
+        assert_eq!(0, repository.count_reminders(&query).unwrap());
+        assert_eq!(1, app.redo_stack.len());
+    }
+}
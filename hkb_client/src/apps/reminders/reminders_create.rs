@@ -1,5 +1,5 @@
 use crossterm::event::KeyCode;
-use hkb_core::database::services::reminders::CreateReminderData;
+use hkb_core::database::services::reminders::{AlertOffset, CreateReminderData, ReminderPriority};
 use hkb_core::logger::{debug, info};
 use hkb_date::date::SimpleDate;
 use hkb_date::HumanDateParser;
@@ -19,8 +19,13 @@ pub struct RemindersCreate {
     focused_component: usize,
     message_input: InputState,
     reminder_date_input: InputState,
+    tags_input: InputState,
+    priority_input: InputState,
+    alerts_input: InputState,
     submit_button: ButtonState,
     parsed_date: Option<SimpleDate>,
+    parsed_priority: ReminderPriority,
+    parsed_alerts: Vec<AlertOffset>,
 }
 
 // Create rendering
@@ -28,11 +33,37 @@ impl RemindersCreate {
     fn render_inputs(&mut self, frame: &mut Frame, area: Rect) {
         let input_layout = Layout::new(
             Direction::Vertical,
-            [Constraint::Length(3), Constraint::Length(3)],
+            [
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+            ],
         )
         .split(area);
         Input::new("Title").render(frame, &mut self.message_input, input_layout[0]);
         Input::new("Reminder Date").render(frame, &mut self.reminder_date_input, input_layout[1]);
+        Input::new("Tags (comma separated)").render(frame, &mut self.tags_input, input_layout[2]);
+        Input::new("Priority (low, normal, high or critical)").render(
+            frame,
+            &mut self.priority_input,
+            input_layout[3],
+        );
+        Input::new("Alerts (e.g. 1 day before, at time)").render(
+            frame,
+            &mut self.alerts_input,
+            input_layout[4],
+        );
+    }
+
+    fn parse_tags(&self) -> Vec<String> {
+        self.tags_input
+            .buffer
+            .split(',')
+            .map(|tag| tag.trim().to_owned())
+            .filter(|tag| !tag.is_empty())
+            .collect()
     }
 
     fn render_error_messages(&mut self, frame: &mut Frame, area: Rect) {
@@ -77,11 +108,42 @@ impl RemindersCreate {
         self.render_create_button(frame, base_layout[base_layout.len() - 1]);
     }
 
+    fn parse_priority(&mut self) -> Result<(), String> {
+        let priority = self.priority_input.buffer.trim();
+
+        self.parsed_priority = if priority.is_empty() {
+            ReminderPriority::default()
+        } else {
+            priority.parse()?
+        };
+
+        Ok(())
+    }
+
+    fn parse_alerts(&mut self) -> Result<(), String> {
+        let alerts = self.alerts_input.buffer.trim();
+
+        self.parsed_alerts = if alerts.is_empty() {
+            AlertOffset::defaults()
+        } else {
+            alerts
+                .split(',')
+                .map(|alert| alert.parse())
+                .collect::<Result<Vec<AlertOffset>, String>>()?
+        };
+
+        Ok(())
+    }
+
     fn validate(&mut self) -> bool {
         if self.message_input.buffer.is_empty() {
             self.error_message = Some("Title Input is required!".to_owned());
         } else if self.reminder_date_input.buffer.is_empty() {
             self.error_message = Some("Remidner Date Input is required!".to_owned());
+        } else if let Err(e) = self.parse_priority() {
+            self.error_message = Some(e);
+        } else if let Err(e) = self.parse_alerts() {
+            self.error_message = Some(e);
         } else {
             let parser = HumanDateParser::new(SimpleDate::local());
 
@@ -117,6 +179,11 @@ impl RemindersView for RemindersCreate {
                 let data = CreateReminderData {
                     note: self.message_input.buffer.to_owned(),
                     remind_at: self.parsed_date.take().unwrap(),
+                    recurrence: None,
+                    tags: self.parse_tags(),
+                    priority: self.parsed_priority,
+                    alerts: std::mem::take(&mut self.parsed_alerts),
+                    checklist: vec![],
                 };
                 return Some(Message::CreateReminder(data));
             }
@@ -127,6 +194,9 @@ impl RemindersView for RemindersCreate {
         let mut focusables: Vec<&mut dyn Focusable> = vec![
             &mut self.message_input,
             &mut self.reminder_date_input,
+            &mut self.tags_input,
+            &mut self.priority_input,
+            &mut self.alerts_input,
             &mut self.submit_button,
         ];
 
@@ -160,9 +230,9 @@ impl RemindersView for RemindersCreate {
         let layout = Layout::new(
             Direction::Vertical,
             [
-                Constraint::Percentage(30),
+                Constraint::Percentage(15),
                 Constraint::Min(0),
-                Constraint::Percentage(30),
+                Constraint::Percentage(15),
             ],
         )
         .split(area);
@@ -1,19 +1,59 @@
 use crossterm::event::KeyCode;
 use hkb_core::database::services::reminders;
-use hkb_core::database::services::reminders::{ReminderData, ReminderQueryOptions};
+use hkb_core::database::services::reminders::{
+    AsyncReminderRepository, ChecklistEdit, ChecklistItemData, ReminderData, ReminderPriority,
+    ReminderQuery, ReminderQueryOptions, ReminderStatus,
+};
 use hkb_core::logger::info;
 use hkb_date::date::SimpleDate;
 use hkb_date::duration::HumanizedDuration;
 use ratatui::prelude::{Constraint, Direction, Frame, Layout, Rect};
-use ratatui::style::{Modifier, Style};
-use ratatui::widgets::{Block, Borders, List, ListState};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use tokio::sync::oneshot;
 
+use crate::components::{Input, InputState, StatefulComponent};
 use crate::utils::bounded_value::BoundedValue;
-use crate::{app_state, events};
+use crate::{app_state, events, focus::Focusable};
 
-use super::{Message, RemindersView};
+use super::{Message, RemindersView, Repository};
 
-pub struct RemindersList {
+const UPCOMING_PAGE_SIZE: i64 = 20;
+
+/// What the input on top of the lists is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prompt {
+    TagFilter,
+    Search,
+    ChecklistItem,
+}
+
+impl Prompt {
+    fn title(&self) -> &'static str {
+        match self {
+            Prompt::TagFilter => "Filter by tag (comma separated)",
+            Prompt::Search => "Search",
+            Prompt::ChecklistItem => "New checklist item",
+        }
+    }
+}
+
+/// Reminders loaded in the background
+struct LoadedReminders {
+    today: Vec<ReminderData>,
+    upcoming: Vec<ReminderData>,
+    upcoming_page: i64,
+    upcoming_total: i64,
+}
+
+fn page_count(total: i64) -> i64 {
+    std::cmp::max(1, (total + UPCOMING_PAGE_SIZE - 1) / UPCOMING_PAGE_SIZE)
+}
+
+pub struct RemindersList<R: Repository> {
+    repository: AsyncReminderRepository<R>,
+    loading: Option<oneshot::Receiver<LoadedReminders>>,
     selected: BoundedValue,
 
     today_reminders: Vec<ReminderData>,
@@ -21,11 +61,23 @@ pub struct RemindersList {
 
     upcoming_reminders: Vec<ReminderData>,
     upcoming_reminders_state: ListState,
+    upcoming_page: i64,
+    upcoming_total: i64,
+
+    tag_filter: Vec<String>,
+    search: String,
+    prompt: Option<(Prompt, InputState)>,
+
+    /// The reminder whose checklist is shown under it
+    expanded: Option<i64>,
+    checklist_selected: BoundedValue,
 }
 
-impl Default for RemindersList {
-    fn default() -> Self {
+impl<R: Repository> RemindersList<R> {
+    pub fn new(repository: AsyncReminderRepository<R>) -> Self {
         Self {
+            repository,
+            loading: None,
             selected: BoundedValue::new(0, 0, 0),
 
             today_reminders: vec![],
@@ -33,12 +85,21 @@ impl Default for RemindersList {
 
             upcoming_reminders: vec![],
             upcoming_reminders_state: ListState::default().with_selected(None),
+            upcoming_page: 0,
+            upcoming_total: 0,
+
+            tag_filter: vec![],
+            search: String::new(),
+            prompt: None,
+
+            expanded: None,
+            checklist_selected: BoundedValue::new(0, 0, 0),
         }
     }
 }
 
-impl RemindersList {
-    fn format_reminder(&self, reminder: &ReminderData) -> String {
+impl<R: Repository> RemindersList<R> {
+    fn format_reminder_details(&self, reminder: &ReminderData) -> String {
         let duration = {
             let duration = reminder.remind_at - SimpleDate::local();
 
@@ -49,33 +110,386 @@ impl RemindersList {
             }
         };
         let date = reminder.remind_at.format("%d-%m-%Y");
+        let snoozed = if reminder.snooze_count > 0 {
+            " [snoozed]"
+        } else {
+            ""
+        };
+
+        let tags = reminder
+            .tags
+            .iter()
+            .map(|tag| format!(" #{tag}"))
+            .collect::<Vec<String>>();
+
+        let priority = match reminder.priority {
+            ReminderPriority::Normal => String::new(),
+            priority => format!(" [{priority}]"),
+        };
+
+        let progress = reminder
+            .checklist_progress()
+            .map(|progress| format!(" [{progress}]"))
+            .unwrap_or_default();
+
+        format!(
+            " - {} ({}){}{}{}{}",
+            duration,
+            date,
+            priority,
+            progress,
+            snoozed,
+            tags.join("")
+        )
+    }
+
+    fn format_checklist_item(&self, item: &ChecklistItemData, selected: bool) -> Line<'static> {
+        let check = if item.done { "[x]" } else { "[ ]" };
+        let style = if selected {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else if item.done {
+            Style::default().add_modifier(Modifier::CROSSED_OUT)
+        } else {
+            Style::default()
+        };
+
+        Line::from(vec![
+            Span::raw("    "),
+            Span::styled(format!("{check} {}", item.title), style),
+        ])
+    }
+
+    fn reminder_style(&self, reminder: &ReminderData) -> Style {
+        match reminder.priority {
+            ReminderPriority::Low => Style::default().fg(Color::DarkGray),
+            ReminderPriority::Normal => Style::default(),
+            ReminderPriority::High => Style::default().fg(Color::Yellow),
+            ReminderPriority::Critical => {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            }
+        }
+    }
+
+    fn upcoming_pages(&self) -> i64 {
+        page_count(self.upcoming_total)
+    }
+
+    fn load_reminders(&mut self) {
+        let end_date = SimpleDate::local().end_of_day().unwrap();
+        let mut today_reminders_query = ReminderQuery::new().filter(vec![
+            ReminderQueryOptions::RemindAtBetween {
+                end_date,
+                start_date: SimpleDate::local().start_of_day().unwrap(),
+            },
+            ReminderQueryOptions::WithStatus {
+                status: ReminderStatus::Pending,
+            },
+        ]);
+        let mut upcoming_reminders_query = ReminderQuery::new().filter(vec![
+            ReminderQueryOptions::RemindAtGe { date: end_date },
+            ReminderQueryOptions::WithStatus {
+                status: ReminderStatus::Pending,
+            },
+        ]);
+
+        if !self.tag_filter.is_empty() {
+            let tag_filter = ReminderQueryOptions::WithTags {
+                tags: self.tag_filter.clone(),
+            };
+
+            today_reminders_query = today_reminders_query.filter(tag_filter.clone());
+            upcoming_reminders_query = upcoming_reminders_query.filter(tag_filter);
+        }
+
+        if !self.search.is_empty() {
+            let search = ReminderQueryOptions::Search {
+                text: self.search.clone(),
+            };
+
+            today_reminders_query = today_reminders_query.filter(search.clone());
+            upcoming_reminders_query = upcoming_reminders_query.filter(search);
+        }
+
+        let repository = self.repository.clone();
+        let upcoming_page = self.upcoming_page;
+        let (sender, receiver) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let upcoming_total = repository
+                .count_reminders(upcoming_reminders_query.clone())
+                .await
+                .unwrap_or_default();
+            // the last page might be gone after deleting or completing reminders
+            let upcoming_page = std::cmp::min(upcoming_page, page_count(upcoming_total) - 1);
+            let upcoming_reminders_query = upcoming_reminders_query
+                .limit(UPCOMING_PAGE_SIZE)
+                .offset(upcoming_page * UPCOMING_PAGE_SIZE);
+            let (today, upcoming) = tokio::join!(
+                repository.query_reminders(today_reminders_query),
+                repository.query_reminders(upcoming_reminders_query)
+            );
+
+            // nobody waits for the reminders when a newer load replaced this one
+            let _ = sender.send(LoadedReminders {
+                today: today.unwrap_or_default(),
+                upcoming: upcoming.unwrap_or_default(),
+                upcoming_page,
+                upcoming_total,
+            });
+        });
+
+        self.loading = Some(receiver);
+    }
+
+    fn receive_reminders(&mut self) {
+        let Some(loading) = self.loading.as_mut() else {
+            return;
+        };
+
+        let loaded = match loading.try_recv() {
+            Ok(loaded) => loaded,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.loading = None;
+
+                return;
+            }
+        };
+
+        self.loading = None;
+        self.today_reminders = loaded.today;
+        self.upcoming_reminders = loaded.upcoming;
+        self.upcoming_page = loaded.upcoming_page;
+        self.upcoming_total = loaded.upcoming_total;
+
+        self.selected.set_max(
+            (self.today_reminders.len() + self.upcoming_reminders.len()).saturating_sub(1),
+        );
+        self.selected.set_val(0);
+        self.update_selected_reminder();
+
+        match self
+            .expanded_reminder()
+            .map(|reminder| reminder.checklist.len())
+        {
+            Some(items) => self.checklist_selected.set_max(items.saturating_sub(1)),
+            None => self.expanded = None,
+        }
+    }
+
+    fn change_upcoming_page(&mut self, page: i64) {
+        if page < 0 || page >= self.upcoming_pages() || page == self.upcoming_page {
+            return;
+        }
+
+        self.upcoming_page = page;
+        self.load_reminders();
+    }
+
+    fn open_prompt(&mut self, prompt: Prompt) {
+        let mut input = InputState::default();
+        input.buffer = match prompt {
+            Prompt::TagFilter => self.tag_filter.join(", "),
+            Prompt::Search => self.search.clone(),
+            Prompt::ChecklistItem => String::new(),
+        };
+        input.focus();
+
+        self.prompt = Some((prompt, input));
+
+        app_state::set_editing(true);
+        app_state::disable_navigation_events();
+    }
+
+    fn close_prompt(&mut self) {
+        self.prompt = None;
+
+        app_state::set_editing(false);
+        app_state::enable_navigation_events();
+    }
+
+    fn set_tag_filter(&mut self, tags: Vec<String>) {
+        info!(target: "CLIENT_REMINDERS_LIST", "Filtering reminders by tags: {tags:?}");
+
+        self.tag_filter = tags;
+        self.upcoming_page = 0;
+        self.load_reminders();
+    }
+
+    fn set_search(&mut self, search: String) {
+        info!(target: "CLIENT_REMINDERS_LIST", "Searching reminders for: {search}");
+
+        self.search = search;
+        self.upcoming_page = 0;
+        self.load_reminders();
+    }
+
+    fn update_prompt(&mut self) -> Option<Message> {
+        if events::has_key_event!(KeyCode::Enter) {
+            let (prompt, input) = self.prompt.take()?;
+
+            self.close_prompt();
+
+            match prompt {
+                Prompt::TagFilter => self.set_tag_filter(
+                    input
+                        .buffer
+                        .split(',')
+                        .map(|tag| tag.trim().to_owned())
+                        .filter(|tag| !tag.is_empty())
+                        .collect(),
+                ),
+                Prompt::Search => self.set_search(input.buffer.trim().to_owned()),
+                Prompt::ChecklistItem => {
+                    let title = input.buffer.trim().to_owned();
+
+                    if !title.is_empty() {
+                        return Some(Message::EditChecklist(
+                            self.expanded?,
+                            ChecklistEdit::Add(title),
+                        ));
+                    }
+                }
+            }
+        } else if !app_state::is_editing() && events::has_key_event!(KeyCode::Char(c) if c == 'q') {
+            self.close_prompt();
+        }
+
+        None
+    }
+
+    fn selected_reminder(&self) -> Option<&ReminderData> {
+        if self.selected >= self.today_reminders.len() {
+            self.upcoming_reminders
+                .get(self.selected.get_val() - self.today_reminders.len())
+        } else {
+            self.today_reminders.get(self.selected.get_val())
+        }
+    }
+
+    fn expanded_reminder(&self) -> Option<&ReminderData> {
+        let id = self.expanded?;
+
+        self.today_reminders
+            .iter()
+            .chain(self.upcoming_reminders.iter())
+            .find(|reminder| reminder.id == id)
+    }
+
+    fn selected_checklist_item(&self) -> Option<&ChecklistItemData> {
+        self.expanded_reminder()?
+            .checklist
+            .get(self.checklist_selected.get_val())
+    }
+
+    /// Shows the checklist of the selected reminder, or hides it when it is shown already
+    fn toggle_checklist(&mut self) {
+        let Some(reminder) = self.selected_reminder() else {
+            return;
+        };
+
+        if self.expanded == Some(reminder.id) {
+            self.expanded = None;
+
+            return;
+        }
+
+        let items = reminder.checklist.len();
 
-        format!("{} - {} ({})", reminder.note, duration, date)
+        self.expanded = Some(reminder.id);
+        self.checklist_selected = BoundedValue::new(0, 0, items.saturating_sub(1));
     }
 
-    fn create_reminder_list<'a>(&self, reminders: &[ReminderData], title: &'a str) -> List<'a> {
+    /// Keys of the checklist that is shown, if any
+    fn update_checklist(&mut self) -> Option<Message> {
+        let reminder_id = self.expanded?;
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 'i') {
+            self.open_prompt(Prompt::ChecklistItem);
+
+            return None;
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 'J') {
+            self.checklist_selected += 1;
+        } else if events::has_key_event!(KeyCode::Char(c) if c == 'K') {
+            self.checklist_selected -= 1;
+        }
+
+        let item = self.selected_checklist_item()?.clone();
+        let position = self.checklist_selected.get_val();
+
+        if events::has_key_event!(KeyCode::Char(c) if c == ' ') {
+            return Some(Message::EditChecklist(
+                reminder_id,
+                ChecklistEdit::SetDone(item.id, !item.done),
+            ));
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 'X') {
+            return Some(Message::EditChecklist(
+                reminder_id,
+                ChecklistEdit::Remove(item.id),
+            ));
+        }
+
+        // the selection follows the item that is moved
+        if events::has_key_event!(KeyCode::Char(c) if c == '+') {
+            self.checklist_selected += 1;
+
+            return Some(Message::EditChecklist(
+                reminder_id,
+                ChecklistEdit::Move(item.id, position + 1),
+            ));
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == '-') && position > 0 {
+            self.checklist_selected -= 1;
+
+            return Some(Message::EditChecklist(
+                reminder_id,
+                ChecklistEdit::Move(item.id, position - 1),
+            ));
+        }
+
+        None
+    }
+
+    fn create_reminder_list<'a>(&self, reminders: &[ReminderData], title: String) -> List<'a> {
+        let search_terms = reminders::search_terms(&self.search);
         let notes = reminders
             .iter()
-            .map(|reminder| self.format_reminder(reminder))
-            .collect::<Vec<String>>();
+            .map(|reminder| {
+                let mut spans = highlight_matches(
+                    &reminder.note,
+                    &search_terms,
+                    Style::default().add_modifier(Modifier::REVERSED),
+                );
+                spans.push(Span::raw(self.format_reminder_details(reminder)));
+
+                let mut lines = vec![Line::from(spans)];
+
+                if self.expanded == Some(reminder.id) {
+                    lines.extend(reminder.checklist.iter().enumerate().map(|(index, item)| {
+                        self.format_checklist_item(item, index == self.checklist_selected.get_val())
+                    }));
+                }
+
+                ListItem::new(Text::from(lines)).style(self.reminder_style(reminder))
+            })
+            .collect::<Vec<ListItem>>();
 
         List::new(notes)
             .highlight_style(
                 Style::default()
-                    .bg(ratatui::style::Color::Black)
-                    .fg(ratatui::style::Color::White)
+                    .bg(Color::Black)
+                    .fg(Color::White)
                     .add_modifier(Modifier::ITALIC),
             )
             .block(Block::default().borders(Borders::ALL).title(title))
     }
 
     fn update_selected_reminder(&mut self) {
-        if events::has_key_event!(KeyCode::Char(c) if c == 'j') {
-            self.selected += 1;
-        } else if events::has_key_event!(KeyCode::Char(c) if c == 'k') {
-            self.selected -= 1;
-        }
-
         if self.selected >= self.today_reminders.len() {
             let upcoming_selected = self.selected.get_val() - self.today_reminders.len();
 
@@ -92,53 +506,107 @@ impl RemindersList {
     }
 }
 
-impl RemindersView for RemindersList {
+impl<R: Repository> RemindersView for RemindersList<R> {
     fn init(&mut self) {
         info!(target: "CLIENT_REMINDERS_LIST", "List reminders view initialized.");
         app_state::set_editing(false);
         app_state::enable_navigation_events();
 
-        let end_date = SimpleDate::local().end_of_day().unwrap();
-        let today_reminders_query_options = vec![ReminderQueryOptions::RemindAtBetween {
-            end_date,
-            start_date: SimpleDate::local().start_of_day().unwrap(),
-        }];
-        self.today_reminders =
-            reminders::fetch_reminders(Some(today_reminders_query_options)).unwrap_or_default();
-
-        let rest_of_reminders_query_options =
-            vec![ReminderQueryOptions::RemindAtGe { date: end_date }];
-        self.upcoming_reminders =
-            reminders::fetch_reminders(Some(rest_of_reminders_query_options)).unwrap_or_default();
+        self.load_reminders();
+    }
 
-        self.selected.set_max(
-            (self.today_reminders.len() + self.upcoming_reminders.len()).saturating_sub(1),
-        );
+    fn reload(&mut self) {
+        self.load_reminders();
     }
 
     fn update(&mut self) -> Option<Message> {
+        self.receive_reminders();
+
+        // while the prompt is open, keys belong to its input
+        if self.prompt.is_some() {
+            return self.update_prompt();
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 'c') {
+            self.toggle_checklist();
+        }
+
+        if let Some(message) = self.update_checklist() {
+            return Some(message);
+        }
+
+        // the checklist prompt might have been opened
+        if self.prompt.is_some() {
+            return None;
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 't') {
+            self.open_prompt(Prompt::TagFilter);
+
+            return None;
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == '/') {
+            self.open_prompt(Prompt::Search);
+
+            return None;
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 'T') && !self.tag_filter.is_empty() {
+            self.set_tag_filter(vec![]);
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 'n') {
+            self.change_upcoming_page(self.upcoming_page + 1);
+        } else if events::has_key_event!(KeyCode::Char(c) if c == 'p') {
+            self.change_upcoming_page(self.upcoming_page - 1);
+        }
+
         if events::has_key_event!(KeyCode::Char(c) if c == 'a' || c == 'A') {
             return Some(Message::ChangeView(super::View::Create));
         }
 
+        if events::has_key_event!(KeyCode::Char(c) if c == 'D') {
+            return Some(Message::ChangeView(super::View::Trash));
+        }
+
         if (events::has_key_event!(KeyCode::Backspace)
             || events::has_key_event!(KeyCode::Char(c) if c == 'd'))
             && events::is_pressed_at_least('d', 2)
-            && (!self.today_reminders.is_empty() || !self.upcoming_reminders.is_empty())
         {
-            events::reset_key_press();
-
-            let reminder = {
-                if self.selected >= self.today_reminders.len() {
-                    self.upcoming_reminders
-                        .get(self.selected.get_val() - self.today_reminders.len())
-                        .unwrap()
-                } else {
-                    self.today_reminders.get(self.selected.get_val()).unwrap()
-                }
-            };
+            if let Some(reminder) = self.selected_reminder() {
+                events::reset_key_press();
+
+                return Some(Message::DeleteReminder(reminder.id));
+            }
+        }
+
+        if events::has_ctrl_key_event('r') {
+            return Some(Message::Redo);
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 'u') {
+            return Some(Message::Undo);
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 'x') {
+            if let Some(reminder) = self.selected_reminder() {
+                return Some(Message::CompleteReminder(reminder.id));
+            }
+        }
 
-            return Some(Message::DeleteReminder(reminder.id));
+        if events::has_key_event!(KeyCode::Char(c) if c == 's') {
+            if let Some(reminder) = self.selected_reminder() {
+                return Some(Message::ChangeView(super::View::Snooze(Box::new(
+                    reminder.clone(),
+                ))));
+            }
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 'j') {
+            self.selected += 1;
+        } else if events::has_key_event!(KeyCode::Char(c) if c == 'k') {
+            self.selected -= 1;
         }
 
         self.update_selected_reminder();
@@ -147,29 +615,61 @@ impl RemindersView for RemindersList {
     }
 
     fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let filter_height = if self.prompt.is_some() { 3 } else { 1 };
         let layout = Layout::new(
             Direction::Vertical,
             [
-                Constraint::Length(1),
+                Constraint::Length(filter_height),
                 Constraint::Min(0),
                 Constraint::Length(1),
             ],
         )
         .split(area);
+
+        if let Some((prompt, input)) = self.prompt.as_mut() {
+            Input::new(prompt.title()).render(frame, input, layout[0]);
+        } else {
+            let mut filters = vec![];
+
+            if !self.tag_filter.is_empty() {
+                let tags: Vec<String> = self
+                    .tag_filter
+                    .iter()
+                    .map(|tag| format!("#{tag}"))
+                    .collect();
+
+                filters.push(format!("Filtered by: {}", tags.join(" ")));
+            }
+
+            if !self.search.is_empty() {
+                filters.push(format!("Searching for: {}", self.search));
+            }
+
+            frame.render_widget(Paragraph::new(filters.join(" | ")), layout[0]);
+        }
         let vertical_split_layout = Layout::new(
             Direction::Vertical,
             [Constraint::Percentage(30), Constraint::Percentage(70)],
         )
         .split(layout[1]);
 
-        let list = self.create_reminder_list(&self.today_reminders, "Today Reminders");
+        let list = self.create_reminder_list(&self.today_reminders, "Today Reminders".to_owned());
         frame.render_stateful_widget(
             list,
             vertical_split_layout[0],
             &mut self.today_reminders_state,
         );
 
-        let list = self.create_reminder_list(&self.upcoming_reminders, "Upcoming Reminders");
+        let upcoming_title = if self.upcoming_pages() > 1 {
+            format!(
+                "Upcoming Reminders (page {}/{})",
+                self.upcoming_page + 1,
+                self.upcoming_pages()
+            )
+        } else {
+            "Upcoming Reminders".to_owned()
+        };
+        let list = self.create_reminder_list(&self.upcoming_reminders, upcoming_title);
         frame.render_stateful_widget(
             list,
             vertical_split_layout[1],
@@ -177,3 +677,61 @@ impl RemindersView for RemindersList {
         );
     }
 }
+
+/// The length of the start of the word that matches the term, ignoring case
+fn matched_prefix_len(word: &str, term: &str) -> Option<usize> {
+    let mut lowered = String::new();
+
+    for (index, c) in word.char_indices() {
+        if lowered == term {
+            return Some(index);
+        }
+
+        if !term.starts_with(&lowered) {
+            return None;
+        }
+
+        lowered.extend(c.to_lowercase());
+    }
+
+    (lowered == term).then_some(word.len())
+}
+
+/// Splits the text into spans, styling the start of every word that matches one of the terms
+fn highlight_matches(text: &str, terms: &[String], style: Style) -> Vec<Span<'static>> {
+    let mut spans = vec![];
+    let mut highlighted_until = 0;
+    let mut word_start = None;
+
+    // a trailing separator closes the last word
+    for (index, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (word_start, c.is_alphanumeric()) {
+            (None, true) => word_start = Some(index),
+            (Some(start), false) => {
+                word_start = None;
+
+                let Some(len) = terms
+                    .iter()
+                    .filter_map(|term| matched_prefix_len(&text[start..index], term))
+                    .max()
+                else {
+                    continue;
+                };
+
+                if start > highlighted_until {
+                    spans.push(Span::raw(text[highlighted_until..start].to_owned()));
+                }
+
+                spans.push(Span::styled(text[start..start + len].to_owned(), style));
+                highlighted_until = start + len;
+            }
+            _ => {}
+        }
+    }
+
+    if highlighted_until < text.len() {
+        spans.push(Span::raw(text[highlighted_until..].to_owned()));
+    }
+
+    spans
+}
@@ -0,0 +1,125 @@
+use crossterm::event::KeyCode;
+use hkb_core::database::services::reminders::ReminderData;
+use hkb_core::logger::{debug, info};
+use hkb_date::date::SimpleDate;
+use hkb_date::HumanDateParser;
+use ratatui::prelude::{Constraint, Direction, Frame, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph};
+
+use crate::components::{Input, InputState, StatefulComponent};
+use crate::{app_state, events, focus::Focusable};
+
+use super::{Message, RemindersView};
+
+pub struct RemindersSnooze {
+    reminder: ReminderData,
+    error_message: Option<String>,
+    snooze_input: InputState,
+}
+
+impl RemindersSnooze {
+    pub fn new(reminder: ReminderData) -> Self {
+        Self {
+            reminder,
+            error_message: None,
+            snooze_input: InputState::default(),
+        }
+    }
+}
+
+impl RemindersSnooze {
+    fn parse_snooze_date(&mut self) -> Option<SimpleDate> {
+        let input = self.snooze_input.buffer.trim();
+
+        if input.is_empty() {
+            self.error_message = Some("Snooze for Input is required!".to_owned());
+
+            return None;
+        }
+
+        let parser = HumanDateParser::new(SimpleDate::local());
+        // "10 minutes" reads better than "in 10 minutes" when snoozing,
+        // so we allow both
+        let result = parser
+            .parse(input)
+            .or_else(|_| parser.parse(format!("in {input}")));
+
+        match result {
+            Ok(date) => {
+                self.error_message = None;
+
+                Some(date)
+            }
+            Err(e) => {
+                debug!(target: "CLIENT_REMINDERS_SNOOZE", "Failed to parse date. Err: {e:?}");
+                self.error_message = Some("Failed to parse date!".to_owned());
+
+                None
+            }
+        }
+    }
+}
+
+impl RemindersView for RemindersSnooze {
+    fn init(&mut self) {
+        info!(target: "CLIENT_REMINDERS_SNOOZE", "Snooze reminder view initialized.");
+
+        app_state::set_editing(true);
+        app_state::disable_navigation_events();
+    }
+
+    fn update(&mut self) -> Option<Message> {
+        if !app_state::is_editing() && events::has_key_event!(KeyCode::Char(c) if c == 'q') {
+            return Some(Message::ChangeView(super::View::List));
+        }
+
+        if events::has_key_event!(KeyCode::Enter) {
+            if let Some(date) = self.parse_snooze_date() {
+                return Some(Message::SnoozeReminder(self.reminder.id, date));
+            }
+        }
+
+        self.snooze_input.focus();
+
+        None
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::new(
+            Direction::Vertical,
+            [
+                Constraint::Percentage(35),
+                Constraint::Min(0),
+                Constraint::Percentage(35),
+            ],
+        )
+        .split(area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::symmetric(1, 1))
+            .title(format!("Snooze: {}", self.reminder.note));
+        let block_area = block.inner(layout[1]);
+        let form_layout = Layout::new(
+            Direction::Vertical,
+            [Constraint::Length(3), Constraint::Length(1)],
+        )
+        .split(block_area);
+
+        frame.render_widget(block, layout[1]);
+        Input::new("Snooze for (e.g. 10 minutes)").render(
+            frame,
+            &mut self.snooze_input,
+            form_layout[0],
+        );
+
+        if let Some(message) = self.error_message.as_ref() {
+            frame.render_widget(
+                Paragraph::new(message.clone())
+                    .centered()
+                    .style(Style::default().fg(Color::Red)),
+                form_layout[1],
+            );
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crossterm::event::KeyCode;
+use hkb_core::database::services::reminders::{
+    AsyncReminderRepository, ReminderData, ReminderQuery, ReminderSortField, SortDirection,
+    TrashFilter,
+};
+use hkb_core::logger::{error, info};
+use hkb_date::date::SimpleDate;
+use hkb_date::duration::HumanizedDuration;
+use ratatui::prelude::{Frame, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
+use tokio::sync::oneshot;
+
+use crate::utils::bounded_value::BoundedValue;
+use crate::{app_state, events};
+
+use super::{Message, RemindersView, Repository};
+
+/// Reminders that were deleted, the latest first.
+/// They can be restored or purged for good before the daemon purges them.
+pub struct RemindersTrash<R: Repository> {
+    repository: AsyncReminderRepository<R>,
+    loading: Option<oneshot::Receiver<Vec<ReminderData>>>,
+    selected: BoundedValue,
+    reminders: Vec<ReminderData>,
+    reminders_state: ListState,
+}
+
+impl<R: Repository> RemindersTrash<R> {
+    pub fn new(repository: AsyncReminderRepository<R>) -> Self {
+        Self {
+            repository,
+            loading: None,
+            selected: BoundedValue::new(0, 0, 0),
+            reminders: vec![],
+            reminders_state: ListState::default().with_selected(Some(0)),
+        }
+    }
+}
+
+impl<R: Repository> RemindersTrash<R> {
+    fn load_reminders(&mut self) {
+        let query = ReminderQuery::new()
+            .trash(TrashFilter::Only)
+            .sort_by(ReminderSortField::DeletedAt, SortDirection::Desc);
+        let repository = self.repository.clone();
+        let (sender, receiver) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let reminders = repository.query_reminders(query).await.unwrap_or_else(|e| {
+                error!(target: "CLIENT_REMINDERS_TRASH", "Failed to load the trash! {e}");
+
+                vec![]
+            });
+
+            // nobody waits for the reminders when a newer load replaced this one
+            let _ = sender.send(reminders);
+        });
+
+        self.loading = Some(receiver);
+    }
+
+    fn receive_reminders(&mut self) {
+        let Some(loading) = self.loading.as_mut() else {
+            return;
+        };
+
+        let reminders = match loading.try_recv() {
+            Ok(reminders) => reminders,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.loading = None;
+
+                return;
+            }
+        };
+
+        self.loading = None;
+        self.reminders = reminders;
+        self.selected
+            .set_max(self.reminders.len().saturating_sub(1));
+        self.reminders_state.select(Some(self.selected.get_val()));
+    }
+
+    fn selected_reminder(&self) -> Option<&ReminderData> {
+        self.reminders.get(self.selected.get_val())
+    }
+
+    fn format_reminder(&self, reminder: &ReminderData) -> String {
+        let deleted = reminder
+            .deleted_at
+            .map(|deleted_at| {
+                format!(
+                    " - deleted {} ago",
+                    (SimpleDate::local() - deleted_at).to_human_string()
+                )
+            })
+            .unwrap_or_default();
+
+        format!(
+            "{} ({}){deleted}",
+            reminder.note,
+            reminder.remind_at.format("%d-%m-%Y")
+        )
+    }
+}
+
+impl<R: Repository> RemindersView for RemindersTrash<R> {
+    fn init(&mut self) {
+        info!(target: "CLIENT_REMINDERS_TRASH", "Trash view initialized.");
+        app_state::set_editing(false);
+        app_state::enable_navigation_events();
+
+        self.load_reminders();
+    }
+
+    fn reload(&mut self) {
+        self.load_reminders();
+    }
+
+    fn update(&mut self) -> Option<Message> {
+        self.receive_reminders();
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 'q') {
+            return Some(Message::ChangeView(super::View::List));
+        }
+
+        if events::has_ctrl_key_event('r') {
+            return Some(Message::Redo);
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 'u') {
+            return Some(Message::Undo);
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 'r') {
+            if let Some(reminder) = self.selected_reminder() {
+                return Some(Message::RestoreReminder(reminder.id));
+            }
+        }
+
+        if (events::has_key_event!(KeyCode::Backspace)
+            || events::has_key_event!(KeyCode::Char(c) if c == 'd'))
+            && events::is_pressed_at_least('d', 2)
+        {
+            if let Some(reminder) = self.selected_reminder() {
+                events::reset_key_press();
+
+                return Some(Message::PurgeReminder(reminder.id));
+            }
+        }
+
+        if events::has_key_event!(KeyCode::Char(c) if c == 'j') {
+            self.selected += 1;
+        } else if events::has_key_event!(KeyCode::Char(c) if c == 'k') {
+            self.selected -= 1;
+        }
+
+        self.reminders_state.select(Some(self.selected.get_val()));
+
+        None
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let reminders = self
+            .reminders
+            .iter()
+            .map(|reminder| ListItem::new(self.format_reminder(reminder)))
+            .collect::<Vec<ListItem>>();
+        let list = List::new(reminders)
+            .highlight_style(
+                Style::default()
+                    .bg(Color::Black)
+                    .fg(Color::White)
+                    .add_modifier(Modifier::ITALIC),
+            )
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Trash (r: restore, dd: delete for good, q: back)"),
+            );
+
+        frame.render_stateful_widget(list, area, &mut self.reminders_state);
+    }
+}
@@ -0,0 +1,120 @@
+use hkb_core::database::services::backup::{self, BackupError, BackupFormat};
+use hkb_core::database::services::reminders::RestoreConflict;
+use std::path::PathBuf;
+use thiserror::Error as ThisError;
+
+pub const USAGE: &str = "Usage:
+    hkb_client                                  Opens the terminal interface
+    hkb_client export <path> [--format json|csv]
+    hkb_client import <path> [--format json|csv] [--on-conflict skip|overwrite|duplicate]
+
+The format defaults to the extension of the path.";
+
+#[derive(ThisError, Debug)]
+pub enum CliError {
+    #[error("Unknown command: {0}")]
+    UnknownCommand(String),
+    #[error("Missing {0}")]
+    MissingArgument(&'static str),
+    #[error("Unknown argument: {0}")]
+    UnknownArgument(String),
+    #[error("Unknown conflict strategy: {0}")]
+    UnknownConflict(String),
+    #[error(transparent)]
+    FailedToBackup(#[from] BackupError),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Export {
+        path: PathBuf,
+        format: BackupFormat,
+    },
+    Import {
+        path: PathBuf,
+        format: BackupFormat,
+        conflict: RestoreConflict,
+    },
+}
+
+fn parse_conflict(value: &str) -> Result<RestoreConflict, CliError> {
+    match value.to_lowercase().as_str() {
+        "skip" => Ok(RestoreConflict::Skip),
+        "overwrite" => Ok(RestoreConflict::Overwrite),
+        "duplicate" => Ok(RestoreConflict::Duplicate),
+        _ => Err(CliError::UnknownConflict(value.to_owned())),
+    }
+}
+
+/// Reads the command from the arguments, without one the terminal interface is opened
+pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Command>, CliError> {
+    let Some(command) = args.next() else {
+        return Ok(None);
+    };
+
+    if command != "export" && command != "import" {
+        return Err(CliError::UnknownCommand(command));
+    }
+
+    let path = PathBuf::from(args.next().ok_or(CliError::MissingArgument("path"))?);
+    let mut format = None;
+    let mut conflict = RestoreConflict::default();
+
+    while let Some(arg) = args.next() {
+        let mut value = |name| args.next().ok_or(CliError::MissingArgument(name));
+
+        match arg.as_str() {
+            "--format" => format = Some(value("format")?.parse()?),
+            "--on-conflict" if command == "import" => {
+                conflict = parse_conflict(&value("conflict strategy")?)?
+            }
+            _ => return Err(CliError::UnknownArgument(arg)),
+        }
+    }
+
+    let format = match format {
+        Some(format) => format,
+        None => BackupFormat::from_path(&path)?,
+    };
+
+    Ok(Some(if command == "export" {
+        Command::Export { path, format }
+    } else {
+        Command::Import {
+            path,
+            format,
+            conflict,
+        }
+    }))
+}
+
+pub fn run(command: Command) -> Result<(), CliError> {
+    match command {
+        Command::Export { path, format } => {
+            let backup = backup::export_to_file(&path, format)?;
+
+            println!(
+                "Exported {} reminders to {}",
+                backup.reminders.len(),
+                path.display()
+            );
+        }
+        Command::Import {
+            path,
+            format,
+            conflict,
+        } => {
+            let summary = backup::import_from_file(&path, format, conflict)?;
+
+            println!(
+                "Imported {} reminders from {}, {} overwritten, {} skipped",
+                summary.created,
+                path.display(),
+                summary.overwritten,
+                summary.skipped
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crossterm::event::{Event, KeyCode};
+use crossterm::event::{Event, KeyCode, KeyModifiers};
 use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
 use std::time::Instant;
 
@@ -161,6 +161,18 @@ pub fn consume(index: usize) -> Option<Event> {
     EventHandler::get_global_handler().consume(index)
 }
 
+/// Consumes the presses of `c` along with control,
+/// the key event macros only look at the key code
+pub fn has_ctrl_key_event(c: char) -> bool {
+    !consume_if(|event| match event {
+        Event::Key(key) => {
+            key.code == KeyCode::Char(c) && key.modifiers.contains(KeyModifiers::CONTROL)
+        }
+        _ => false,
+    })
+    .is_empty()
+}
+
 pub fn reset_key_press() {
     EventHandler::get_global_handler().reset_key_press()
 }
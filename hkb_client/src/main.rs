@@ -1,8 +1,12 @@
 use app_state::AppView;
 use components::{Component, Navigation};
 use crossterm::event::{self, Event, KeyCode};
-use diesel_migrations::{embed_migrations, EmbeddedMigrations};
-use hkb_core::database::init_database;
+use diesel_migrations::embed_migrations;
+use hkb_core::database::services::encryption::unlock_database;
+use hkb_core::database::services::reminders::DieselReminderRepository;
+use hkb_core::database::{
+    database_key, database_url, init_database, DatabaseMigrations, CORE_MIGRATIONS,
+};
 use hkb_core::logger::{debug, error, init as logger_init};
 use hkb_daemon_core::client::{Client, ClientError};
 use hkb_daemon_core::frame::Event as FrameEvent;
@@ -14,6 +18,7 @@ use thiserror::Error as ThisError;
 
 mod app_state;
 mod apps;
+mod cli;
 mod components;
 mod events;
 mod focus;
@@ -21,8 +26,11 @@ mod singleton;
 mod terminal;
 mod utils;
 
-pub const APP_MIGRATIONS: EmbeddedMigrations = embed_migrations!("./migrations");
-pub const CORE_MIGRATIONS: EmbeddedMigrations = embed_migrations!("../hkb_core/migrations");
+pub const APP_MIGRATIONS: DatabaseMigrations = DatabaseMigrations {
+    sqlite: embed_migrations!("./migrations/sqlite"),
+    mysql: embed_migrations!("./migrations/mysql"),
+    postgres: embed_migrations!("./migrations/postgres"),
+};
 
 #[derive(ThisError, Debug)]
 pub enum RendererError {
@@ -88,15 +96,17 @@ fn spawn_server_connection_thread(rx: tokio::sync::mpsc::Receiver<FrameEvent>) {
     tokio::spawn(async move { connect_to_server(rx).await });
 }
 
+fn init_app_database() {
+    init_database(&database_url(), vec![CORE_MIGRATIONS, APP_MIGRATIONS])
+        .expect("Failed to initialize database!");
+
+    let key = database_key().expect("Failed to read the database key!");
+    unlock_database(key.as_ref()).expect("Failed to unlock the database!");
+}
+
 fn bootstrap() {
     logger_init(None);
-
-    let database_file_path = dirs::data_local_dir().unwrap().join("hkb/db");
-    init_database(
-        database_file_path.to_str().unwrap(),
-        vec![CORE_MIGRATIONS, APP_MIGRATIONS],
-    )
-    .expect("Failed to initialize database!");
+    init_app_database();
 
     let (tx, rx) = tokio::sync::mpsc::channel::<FrameEvent>(16);
 
@@ -106,12 +116,32 @@ fn bootstrap() {
 
 #[tokio::main]
 async fn main() -> RenderResult {
+    match cli::parse(std::env::args().skip(1)) {
+        Ok(None) => {}
+        Ok(Some(command)) => {
+            logger_init(None);
+            init_app_database();
+
+            if let Err(e) = cli::run(command) {
+                error!(target: "CLIENT", "Command failed: {e:?}");
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    }
+
     bootstrap();
 
     let mut terminal = terminal::init()?;
     let mut should_quit = false;
     let mut main_app = apps::MainApp::new();
-    let mut reminders_app = apps::RemindersApp::new();
+    let mut reminders_app = apps::RemindersApp::new(DieselReminderRepository);
     let mut navigation =
         Navigation::new("HKB".to_string(), vec![AppView::Main, AppView::Reminders]);
 
@@ -0,0 +1,4 @@
+DROP TABLE reminder_alerts;
+DROP TABLE reminder_tags;
+DROP TABLE tags;
+DROP TABLE reminders;
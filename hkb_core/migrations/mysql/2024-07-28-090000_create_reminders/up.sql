@@ -0,0 +1,40 @@
+-- dates are UTC, timezone is the IANA zone of the system they were set on
+CREATE TABLE reminders (
+  id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+  note TEXT NOT NULL,
+  remind_at DATETIME NOT NULL,
+  created_at DATETIME NOT NULL,
+  recurrence TEXT,
+  occurrence INTEGER NOT NULL DEFAULT 1,
+  original_remind_at DATETIME,
+  snooze_count INTEGER NOT NULL DEFAULT 0,
+  completed_at DATETIME,
+  dismissed_at DATETIME,
+  -- 0 = low, 1 = normal, 2 = high, 3 = critical
+  priority INTEGER NOT NULL DEFAULT 1,
+  timezone VARCHAR(64)
+);
+
+CREATE TABLE tags (
+  id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+  name VARCHAR(255) NOT NULL UNIQUE
+);
+
+CREATE TABLE reminder_tags (
+  reminder_id BIGINT NOT NULL,
+  tag_id BIGINT NOT NULL,
+  PRIMARY KEY (reminder_id, tag_id),
+  FOREIGN KEY (reminder_id) REFERENCES reminders (id) ON DELETE CASCADE,
+  FOREIGN KEY (tag_id) REFERENCES tags (id) ON DELETE CASCADE
+);
+
+CREATE TABLE reminder_alerts (
+  id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+  reminder_id BIGINT NOT NULL,
+  -- minutes before remind_at, 0 is at the time of the reminder
+  offset_minutes INTEGER NOT NULL,
+  -- the remind_at the alert last fired for, so snoozed and recurring reminders alert again
+  fired_for DATETIME,
+  UNIQUE (reminder_id, offset_minutes),
+  FOREIGN KEY (reminder_id) REFERENCES reminders (id) ON DELETE CASCADE
+);
@@ -0,0 +1,12 @@
+-- every change made to a reminder, kept after the reminder is deleted
+CREATE TABLE reminder_changes (
+  id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+  reminder_id BIGINT NOT NULL,
+  -- create, update or delete
+  operation VARCHAR(16) NOT NULL,
+  -- the reminder as json before and after the change, missing before a create and after a delete
+  snapshot_before TEXT,
+  snapshot_after TEXT,
+  changed_at DATETIME NOT NULL,
+  INDEX reminder_changes_reminder_id (reminder_id)
+);
@@ -0,0 +1,4 @@
+-- reminders in the trash are kept until they are purged
+ALTER TABLE reminders ADD COLUMN deleted_at DATETIME;
+
+CREATE INDEX reminders_deleted_at ON reminders (deleted_at);
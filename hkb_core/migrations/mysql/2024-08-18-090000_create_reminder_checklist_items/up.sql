@@ -0,0 +1,9 @@
+CREATE TABLE reminder_checklist_items (
+  id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+  reminder_id BIGINT NOT NULL,
+  title TEXT NOT NULL,
+  -- items are shown from the lowest position to the highest
+  position INTEGER NOT NULL,
+  done BOOLEAN NOT NULL DEFAULT FALSE,
+  FOREIGN KEY (reminder_id) REFERENCES reminders (id) ON DELETE CASCADE
+);
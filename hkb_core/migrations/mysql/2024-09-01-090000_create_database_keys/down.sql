@@ -0,0 +1,6 @@
+ALTER TABLE reminder_changes MODIFY snapshot_after TEXT;
+ALTER TABLE reminder_changes MODIFY snapshot_before TEXT;
+ALTER TABLE reminder_checklist_items MODIFY title TEXT NOT NULL;
+ALTER TABLE reminders MODIFY note TEXT NOT NULL;
+
+DROP TABLE database_keys;
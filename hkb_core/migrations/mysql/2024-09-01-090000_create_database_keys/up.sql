@@ -0,0 +1,14 @@
+-- a database has a row only while it is encrypted, see `DatabaseCipher`
+CREATE TABLE database_keys (
+  id BIGINT NOT NULL PRIMARY KEY,
+  salt VARCHAR(64) NOT NULL,
+  iterations INTEGER NOT NULL,
+  -- tells a wrong key apart without decrypting a note
+  key_check VARCHAR(64) NOT NULL
+);
+
+-- encrypted values are written in hex, twice as long as the text they hold
+ALTER TABLE reminders MODIFY note MEDIUMTEXT NOT NULL;
+ALTER TABLE reminder_checklist_items MODIFY title MEDIUMTEXT NOT NULL;
+ALTER TABLE reminder_changes MODIFY snapshot_before MEDIUMTEXT;
+ALTER TABLE reminder_changes MODIFY snapshot_after MEDIUMTEXT;
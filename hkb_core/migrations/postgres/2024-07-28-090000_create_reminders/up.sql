@@ -0,0 +1,40 @@
+-- dates are UTC, timezone is the IANA zone of the system they were set on
+CREATE TABLE reminders (
+  id BIGSERIAL PRIMARY KEY,
+  note TEXT NOT NULL,
+  remind_at TIMESTAMP NOT NULL,
+  created_at TIMESTAMP NOT NULL,
+  recurrence TEXT,
+  occurrence INTEGER NOT NULL DEFAULT 1,
+  original_remind_at TIMESTAMP,
+  snooze_count INTEGER NOT NULL DEFAULT 0,
+  completed_at TIMESTAMP,
+  dismissed_at TIMESTAMP,
+  -- 0 = low, 1 = normal, 2 = high, 3 = critical
+  priority INTEGER NOT NULL DEFAULT 1,
+  timezone TEXT
+);
+
+-- full-text index over reminder notes, searches use the same expression
+CREATE INDEX reminders_search ON reminders USING GIN (to_tsvector('simple', note));
+
+CREATE TABLE tags (
+  id BIGSERIAL PRIMARY KEY,
+  name TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE reminder_tags (
+  reminder_id BIGINT NOT NULL REFERENCES reminders (id) ON DELETE CASCADE,
+  tag_id BIGINT NOT NULL REFERENCES tags (id) ON DELETE CASCADE,
+  PRIMARY KEY (reminder_id, tag_id)
+);
+
+CREATE TABLE reminder_alerts (
+  id BIGSERIAL PRIMARY KEY,
+  reminder_id BIGINT NOT NULL REFERENCES reminders (id) ON DELETE CASCADE,
+  -- minutes before remind_at, 0 is at the time of the reminder
+  offset_minutes INTEGER NOT NULL,
+  -- the remind_at the alert last fired for, so snoozed and recurring reminders alert again
+  fired_for TIMESTAMP,
+  UNIQUE (reminder_id, offset_minutes)
+);
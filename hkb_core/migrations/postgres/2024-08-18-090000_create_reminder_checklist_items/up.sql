@@ -0,0 +1,10 @@
+CREATE TABLE reminder_checklist_items (
+  id BIGSERIAL PRIMARY KEY,
+  reminder_id BIGINT NOT NULL REFERENCES reminders (id) ON DELETE CASCADE,
+  title TEXT NOT NULL,
+  -- items are shown from the lowest position to the highest
+  position INTEGER NOT NULL,
+  done BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX reminder_checklist_items_reminder_id ON reminder_checklist_items (reminder_id);
@@ -0,0 +1,8 @@
+-- a database has a row only while it is encrypted, see `DatabaseCipher`
+CREATE TABLE database_keys (
+  id BIGINT PRIMARY KEY,
+  salt TEXT NOT NULL,
+  iterations INTEGER NOT NULL,
+  -- tells a wrong key apart without decrypting a note
+  key_check TEXT NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE reminders ADD COLUMN recurrence TEXT;
+ALTER TABLE reminders ADD COLUMN occurrence INTEGER NOT NULL DEFAULT 1;
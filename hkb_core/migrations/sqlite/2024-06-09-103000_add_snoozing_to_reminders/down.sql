@@ -0,0 +1,2 @@
+ALTER TABLE reminders DROP COLUMN snooze_count;
+ALTER TABLE reminders DROP COLUMN original_remind_at;
@@ -0,0 +1,2 @@
+ALTER TABLE reminders ADD COLUMN original_remind_at TIMESTAMP;
+ALTER TABLE reminders ADD COLUMN snooze_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE reminders DROP COLUMN dismissed_at;
+ALTER TABLE reminders DROP COLUMN completed_at;
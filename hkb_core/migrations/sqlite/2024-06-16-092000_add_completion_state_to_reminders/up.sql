@@ -0,0 +1,2 @@
+ALTER TABLE reminders ADD COLUMN completed_at TIMESTAMP;
+ALTER TABLE reminders ADD COLUMN dismissed_at TIMESTAMP;
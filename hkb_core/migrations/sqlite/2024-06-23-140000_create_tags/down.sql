@@ -0,0 +1,2 @@
+DROP TABLE reminder_tags;
+DROP TABLE tags;
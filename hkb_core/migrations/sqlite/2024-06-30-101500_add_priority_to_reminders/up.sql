@@ -0,0 +1,3 @@
+-- priorities are stored as numbers so reminders can be ordered by them
+-- 0 = low, 1 = normal, 2 = high, 3 = critical
+ALTER TABLE reminders ADD COLUMN priority INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,20 @@
+CREATE TABLE reminder_alerts (
+  id INTEGER PRIMARY KEY,
+  reminder_id INTEGER NOT NULL REFERENCES reminders(id) ON DELETE CASCADE,
+  -- minutes before remind_at, 0 is at the time of the reminder
+  offset_minutes INTEGER NOT NULL,
+  -- the remind_at the alert last fired for, so snoozed and recurring reminders alert again
+  fired_for TIMESTAMP,
+  UNIQUE (reminder_id, offset_minutes)
+);
+
+-- keep the warnings pending reminders used to get from the daemon
+INSERT INTO reminder_alerts (reminder_id, offset_minutes)
+SELECT reminders.id, offsets.offset_minutes
+FROM reminders, (
+  SELECT 30 AS offset_minutes
+  UNION ALL SELECT 15
+  UNION ALL SELECT 5
+  UNION ALL SELECT 1
+) AS offsets
+WHERE reminders.completed_at IS NULL AND reminders.dismissed_at IS NULL;
@@ -0,0 +1,4 @@
+DROP TRIGGER reminders_search_after_update;
+DROP TRIGGER reminders_search_after_delete;
+DROP TRIGGER reminders_search_after_insert;
+DROP TABLE reminders_search;
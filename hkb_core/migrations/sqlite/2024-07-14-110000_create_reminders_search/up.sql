@@ -0,0 +1,21 @@
+-- full-text index over reminder notes, the rowid of an entry is the id of its reminder
+CREATE VIRTUAL TABLE reminders_search USING fts5(
+  note,
+  content = 'reminders',
+  content_rowid = 'id'
+);
+
+INSERT INTO reminders_search (reminders_search) VALUES ('rebuild');
+
+CREATE TRIGGER reminders_search_after_insert AFTER INSERT ON reminders BEGIN
+  INSERT INTO reminders_search (rowid, note) VALUES (new.id, new.note);
+END;
+
+CREATE TRIGGER reminders_search_after_delete AFTER DELETE ON reminders BEGIN
+  INSERT INTO reminders_search (reminders_search, rowid, note) VALUES ('delete', old.id, old.note);
+END;
+
+CREATE TRIGGER reminders_search_after_update AFTER UPDATE OF note ON reminders BEGIN
+  INSERT INTO reminders_search (reminders_search, rowid, note) VALUES ('delete', old.id, old.note);
+  INSERT INTO reminders_search (rowid, note) VALUES (new.id, new.note);
+END;
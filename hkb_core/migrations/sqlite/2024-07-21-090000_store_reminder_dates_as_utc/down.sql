@@ -0,0 +1,10 @@
+ALTER TABLE reminders DROP COLUMN timezone;
+
+UPDATE reminder_alerts SET fired_for = strftime('%Y-%m-%dT%H:%M:%SZ', fired_for, 'localtime');
+
+UPDATE reminders SET
+  remind_at = strftime('%Y-%m-%dT%H:%M:%SZ', remind_at, 'localtime'),
+  created_at = strftime('%Y-%m-%dT%H:%M:%SZ', created_at, 'localtime'),
+  original_remind_at = strftime('%Y-%m-%dT%H:%M:%SZ', original_remind_at, 'localtime'),
+  completed_at = strftime('%Y-%m-%dT%H:%M:%SZ', completed_at, 'localtime'),
+  dismissed_at = strftime('%Y-%m-%dT%H:%M:%SZ', dismissed_at, 'localtime');
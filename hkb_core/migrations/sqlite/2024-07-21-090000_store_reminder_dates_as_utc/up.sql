@@ -0,0 +1,15 @@
+-- dates used to be RFC3339 strings of the local wall clock time marked as UTC,
+-- they become UTC timestamps converted with the timezone of the system running the migration,
+-- the Z is dropped since sqlite leaves dates with an explicit timezone alone
+-- and values that are not dates are kept, so reading them reports the error
+UPDATE reminders SET
+  remind_at = coalesce(datetime(rtrim(remind_at, 'Z'), 'utc'), remind_at),
+  created_at = coalesce(datetime(rtrim(created_at, 'Z'), 'utc'), created_at),
+  original_remind_at = coalesce(datetime(rtrim(original_remind_at, 'Z'), 'utc'), original_remind_at),
+  completed_at = coalesce(datetime(rtrim(completed_at, 'Z'), 'utc'), completed_at),
+  dismissed_at = coalesce(datetime(rtrim(dismissed_at, 'Z'), 'utc'), dismissed_at);
+
+UPDATE reminder_alerts SET fired_for = coalesce(datetime(rtrim(fired_for, 'Z'), 'utc'), fired_for);
+
+-- the IANA timezone the dates were set in, unknown for the existing reminders
+ALTER TABLE reminders ADD COLUMN timezone TEXT;
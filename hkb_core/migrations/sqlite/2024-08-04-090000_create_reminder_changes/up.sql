@@ -0,0 +1,13 @@
+-- every change made to a reminder, kept after the reminder is deleted
+CREATE TABLE reminder_changes (
+  id INTEGER PRIMARY KEY,
+  reminder_id INTEGER NOT NULL,
+  -- create, update or delete
+  operation TEXT NOT NULL,
+  -- the reminder as json before and after the change, missing before a create and after a delete
+  snapshot_before TEXT,
+  snapshot_after TEXT,
+  changed_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX reminder_changes_reminder_id ON reminder_changes (reminder_id);
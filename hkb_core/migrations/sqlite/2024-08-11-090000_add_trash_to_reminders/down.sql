@@ -0,0 +1,3 @@
+DROP INDEX reminders_deleted_at;
+
+ALTER TABLE reminders DROP COLUMN deleted_at;
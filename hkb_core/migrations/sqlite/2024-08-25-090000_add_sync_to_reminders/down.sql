@@ -0,0 +1,11 @@
+DROP TABLE sync_devices;
+
+DROP INDEX reminder_changes_reminder_uuid;
+
+ALTER TABLE reminder_changes DROP COLUMN device_id;
+ALTER TABLE reminder_changes DROP COLUMN clock;
+ALTER TABLE reminder_changes DROP COLUMN reminder_uuid;
+
+DROP INDEX reminders_uuid;
+
+ALTER TABLE reminders DROP COLUMN uuid;
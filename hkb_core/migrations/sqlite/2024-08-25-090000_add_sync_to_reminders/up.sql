@@ -0,0 +1,24 @@
+-- reminders are matched across devices by their uuid, their ids are local to every database
+ALTER TABLE reminders ADD COLUMN uuid TEXT NOT NULL DEFAULT '';
+
+UPDATE reminders SET uuid = lower(
+  hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+  || substr('89ab', 1 + abs(random()) % 4, 1) || substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6))
+);
+
+CREATE UNIQUE INDEX reminders_uuid ON reminders (uuid);
+
+-- changes are ordered across devices by their lamport clock and then by their device,
+-- changes made before they were kept have no uuid and are not synced
+ALTER TABLE reminder_changes ADD COLUMN reminder_uuid TEXT;
+ALTER TABLE reminder_changes ADD COLUMN clock BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE reminder_changes ADD COLUMN device_id TEXT;
+
+CREATE INDEX reminder_changes_reminder_uuid ON reminder_changes (reminder_uuid);
+
+-- this device and the devices it synced with, along with the last of their changes it took
+CREATE TABLE sync_devices (
+  device_id TEXT PRIMARY KEY NOT NULL,
+  is_local BOOLEAN NOT NULL DEFAULT FALSE,
+  last_change_id BIGINT NOT NULL DEFAULT 0
+);
@@ -0,0 +1,185 @@
+use diesel::dsl::sql;
+use diesel::r2d2::{self, ManageConnection, R2D2Connection};
+use diesel::sql_types::BigInt;
+use diesel::{Connection, ConnectionError, ConnectionResult, QueryResult, RunQueryDsl};
+use std::fmt::Display;
+
+#[cfg(feature = "mysql-database")]
+use diesel::MysqlConnection;
+#[cfg(feature = "postgres-database")]
+use diesel::PgConnection;
+#[cfg(feature = "sqlite-database")]
+use diesel::SqliteConnection;
+
+/// A connection to one of the backends enabled by the features of the crate,
+/// which one is picked at runtime by the scheme of the database url
+#[derive(diesel::MultiConnection)]
+pub enum DatabaseConnection {
+    #[cfg(feature = "sqlite-database")]
+    Sqlite(SqliteConnection),
+    #[cfg(feature = "mysql-database")]
+    Mysql(MysqlConnection),
+    #[cfg(feature = "postgres-database")]
+    Postgres(PgConnection),
+}
+
+pub type DatabaseBackend = <DatabaseConnection as Connection>::Backend;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseKind {
+    Sqlite,
+    Mysql,
+    Postgres,
+}
+
+impl DatabaseKind {
+    /// `mysql://` and `postgres://` urls pick their backends,
+    /// anything else is the path of a sqlite database, with or without `sqlite://`
+    pub fn from_url(url: &str) -> Self {
+        let scheme = url
+            .split_once("://")
+            .map(|(scheme, _)| scheme.to_lowercase());
+
+        match scheme.as_deref() {
+            Some("mysql") => DatabaseKind::Mysql,
+            Some("postgres") | Some("postgresql") => DatabaseKind::Postgres,
+            _ => DatabaseKind::Sqlite,
+        }
+    }
+
+    /// Whether the backend was compiled in
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            DatabaseKind::Sqlite => cfg!(feature = "sqlite-database"),
+            DatabaseKind::Mysql => cfg!(feature = "mysql-database"),
+            DatabaseKind::Postgres => cfg!(feature = "postgres-database"),
+        }
+    }
+}
+
+impl Display for DatabaseKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatabaseKind::Sqlite => write!(f, "SQLite"),
+            DatabaseKind::Mysql => write!(f, "MySQL"),
+            DatabaseKind::Postgres => write!(f, "PostgreSQL"),
+        }
+    }
+}
+
+impl DatabaseConnection {
+    /// Connects to the backend picked by the scheme of the url
+    pub fn connect_to(url: &str) -> ConnectionResult<Self> {
+        match DatabaseKind::from_url(url) {
+            #[cfg(feature = "sqlite-database")]
+            DatabaseKind::Sqlite => {
+                let path = url.strip_prefix("sqlite://").unwrap_or(url);
+
+                Ok(Self::Sqlite(SqliteConnection::establish(path)?))
+            }
+            #[cfg(feature = "mysql-database")]
+            DatabaseKind::Mysql => Ok(Self::Mysql(MysqlConnection::establish(url)?)),
+            #[cfg(feature = "postgres-database")]
+            DatabaseKind::Postgres => Ok(Self::Postgres(PgConnection::establish(url)?)),
+            #[allow(unreachable_patterns)]
+            kind => Err(ConnectionError::BadConnection(format!(
+                "{kind} databases are not enabled in this build"
+            ))),
+        }
+    }
+
+    pub fn kind(&self) -> DatabaseKind {
+        match self {
+            #[cfg(feature = "sqlite-database")]
+            Self::Sqlite(_) => DatabaseKind::Sqlite,
+            #[cfg(feature = "mysql-database")]
+            Self::Mysql(_) => DatabaseKind::Mysql,
+            #[cfg(feature = "postgres-database")]
+            Self::Postgres(_) => DatabaseKind::Postgres,
+        }
+    }
+
+    /// The id of the row this connection inserted last, for backends without `RETURNING`
+    pub(crate) fn last_insert_id(&mut self) -> QueryResult<i64> {
+        let function = match self.kind() {
+            DatabaseKind::Sqlite => "last_insert_rowid()",
+            DatabaseKind::Mysql => "CAST(LAST_INSERT_ID() AS SIGNED)",
+            DatabaseKind::Postgres => "lastval()",
+        };
+
+        diesel::select(sql::<BigInt>(function)).get_result(self)
+    }
+
+    /// Moves the id sequence of a table past the ids that were inserted by hand.
+    /// SQLite and MySQL keep track of those by themselves.
+    pub(crate) fn sync_id_sequence(&mut self, table: &str) -> QueryResult<()> {
+        if self.kind() == DatabaseKind::Postgres {
+            diesel::sql_query(format!(
+                "SELECT setval(pg_get_serial_sequence('{table}', 'id'), \
+                 COALESCE(MAX(id), 0) + 1, false) FROM {table}"
+            ))
+            .execute(self)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Opens the connections of the pool with the backend picked by the url
+#[derive(Debug)]
+pub struct DatabaseConnectionManager {
+    url: String,
+}
+
+impl DatabaseConnectionManager {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+impl ManageConnection for DatabaseConnectionManager {
+    type Connection = DatabaseConnection;
+    type Error = r2d2::Error;
+
+    fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        DatabaseConnection::connect_to(&self.url).map_err(r2d2::Error::ConnectionError)
+    }
+
+    fn is_valid(&self, connection: &mut Self::Connection) -> Result<(), Self::Error> {
+        connection.ping().map_err(r2d2::Error::QueryError)
+    }
+
+    fn has_broken(&self, connection: &mut Self::Connection) -> bool {
+        std::thread::panicking() || connection.is_broken()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_picks_the_backend_by_the_scheme_of_the_url() {
+        assert_eq!(DatabaseKind::Sqlite, DatabaseKind::from_url(":memory:"));
+        assert_eq!(
+            DatabaseKind::Sqlite,
+            DatabaseKind::from_url("/home/user/.local/share/hkb/db")
+        );
+        assert_eq!(
+            DatabaseKind::Sqlite,
+            DatabaseKind::from_url("sqlite:///tmp/db")
+        );
+        assert_eq!(
+            DatabaseKind::Mysql,
+            DatabaseKind::from_url("mysql://hkb@localhost/hkb")
+        );
+        assert_eq!(
+            DatabaseKind::Postgres,
+            DatabaseKind::from_url("postgres://hkb@localhost/hkb")
+        );
+        assert_eq!(
+            DatabaseKind::Postgres,
+            DatabaseKind::from_url("PostgreSQL://hkb@localhost/hkb")
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::cell::RefCell;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{DatabaseError, DatabaseResult};
+
+/// Values written by a cipher start with it, values without it were written in plain text
+const ENCRYPTED_PREFIX: &str = "hkb-encrypted:v2:";
+const NONCE_LENGTH: usize = 24;
+const SALT_LENGTH: usize = 16;
+const KEY_LENGTH: usize = 32;
+const KEY_CHECK_MESSAGE: &[u8] = b"hkb database key check";
+
+/// Rounds of PBKDF2 for newly encrypted databases, the count is stored next to the salt
+pub const DEFAULT_KDF_ITERATIONS: u32 = 200_000;
+
+/// The secret an encrypted database is opened with
+#[derive(Clone, PartialEq, Eq)]
+pub struct DatabaseKey {
+    secret: Vec<u8>,
+    kdf_iterations: u32,
+}
+
+impl DatabaseKey {
+    pub fn from_passphrase(passphrase: &str) -> Self {
+        Self::from_bytes(passphrase.as_bytes().to_vec())
+    }
+
+    /// Every byte of the file is part of the key, e.g. of one made with `head -c 32 /dev/urandom`
+    pub fn from_file(path: impl AsRef<Path>) -> DatabaseResult<Self> {
+        std::fs::read(path)
+            .map(Self::from_bytes)
+            .map_err(DatabaseError::FailedToReadKeyFile)
+    }
+
+    fn from_bytes(secret: Vec<u8>) -> Self {
+        Self {
+            secret,
+            kdf_iterations: DEFAULT_KDF_ITERATIONS,
+        }
+    }
+
+    /// Rounds of PBKDF2 a database encrypted with this key for the first time is derived with,
+    /// databases that are already encrypted keep the count they were encrypted with
+    pub fn with_kdf_iterations(mut self, kdf_iterations: u32) -> Self {
+        // stored in an integer column
+        self.kdf_iterations = kdf_iterations.clamp(1, i32::MAX as u32);
+
+        self
+    }
+
+    pub fn kdf_iterations(&self) -> u32 {
+        self.kdf_iterations
+    }
+}
+
+// the secret stays out of the logs
+impl std::fmt::Debug for DatabaseKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DatabaseKey(..)")
+    }
+}
+
+/// The passphrase in `HKB_DATABASE_KEY` or the key file `HKB_DATABASE_KEYFILE` points to,
+/// `None` when neither is set and the database is not encrypted
+pub fn database_key() -> DatabaseResult<Option<DatabaseKey>> {
+    if let Some(passphrase) = std::env::var("HKB_DATABASE_KEY")
+        .ok()
+        .filter(|passphrase| !passphrase.is_empty())
+    {
+        return Ok(Some(DatabaseKey::from_passphrase(&passphrase)));
+    }
+
+    std::env::var("HKB_DATABASE_KEYFILE")
+        .ok()
+        .filter(|path| !path.is_empty())
+        .map(DatabaseKey::from_file)
+        .transpose()
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+
+    getrandom::getrandom(&mut bytes).expect("the system has no source of randomness");
+
+    bytes
+}
+
+/// A salt for a database that is encrypted for the first time
+pub(crate) fn new_salt() -> String {
+    hex::encode(random_bytes::<SALT_LENGTH>())
+}
+
+/// The keys a [`DatabaseKey`] derives for the salt of a database with PBKDF2-HMAC-SHA256.
+/// Every value is sealed with XChaCha20-Poly1305 under a random nonce of its own,
+/// the nonce is long enough to be picked at random.
+pub(crate) struct DatabaseCipher {
+    aead: XChaCha20Poly1305,
+    key_check: String,
+}
+
+impl DatabaseCipher {
+    pub(crate) fn derive(key: &DatabaseKey, salt: &str, iterations: u32) -> DatabaseResult<Self> {
+        let salt = hex::decode(salt).map_err(|_| DatabaseError::InvalidCiphertext)?;
+        let mut keys = [0u8; KEY_LENGTH * 2];
+
+        pbkdf2::pbkdf2_hmac::<Sha256>(&key.secret, &salt, iterations.max(1), &mut keys);
+
+        let (encryption_key, check_key) = keys.split_at(KEY_LENGTH);
+        let mut key_check = <Hmac<Sha256> as Mac>::new_from_slice(check_key)
+            .expect("HMAC takes keys of any length");
+        key_check.update(KEY_CHECK_MESSAGE);
+
+        Ok(Self {
+            aead: XChaCha20Poly1305::new_from_slice(encryption_key)
+                .expect("the derived key has the length of a ChaCha20 key"),
+            key_check: hex::encode(key_check.finalize().into_bytes()),
+        })
+    }
+
+    /// Stored next to the salt, tells a wrong key apart without decrypting anything
+    pub(crate) fn key_check(&self) -> String {
+        self.key_check.clone()
+    }
+
+    pub(crate) fn seal(&self, text: &str) -> String {
+        let nonce = random_bytes::<NONCE_LENGTH>();
+        let ciphertext = self
+            .aead
+            .encrypt(XNonce::from_slice(&nonce), text.as_bytes())
+            .expect("values are far shorter than the limit of the cipher");
+
+        format!(
+            "{ENCRYPTED_PREFIX}{}{}",
+            hex::encode(nonce),
+            hex::encode(ciphertext)
+        )
+    }
+
+    /// Values that were not sealed are refused, so plain text written around the app
+    /// or left behind by an unfinished reseal does not pass for encrypted data
+    pub(crate) fn open(&self, value: &str) -> DatabaseResult<String> {
+        let encoded = value
+            .strip_prefix(ENCRYPTED_PREFIX)
+            .ok_or(DatabaseError::InvalidCiphertext)?;
+        let bytes = hex::decode(encoded).map_err(|_| DatabaseError::InvalidCiphertext)?;
+
+        if bytes.len() < NONCE_LENGTH {
+            return Err(DatabaseError::InvalidCiphertext);
+        }
+
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LENGTH);
+        let text = self
+            .aead
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| DatabaseError::InvalidCiphertext)?;
+
+        String::from_utf8(text).map_err(|_| DatabaseError::InvalidCiphertext)
+    }
+}
+
+thread_local! {
+    /// The cipher of the database the connection of this thread belongs to, see `within_database`
+    static CIPHER: RefCell<Option<Arc<DatabaseCipher>>> = const { RefCell::new(None) };
+}
+
+/// Puts the previous cipher back once the callback is done, even when it panics
+struct CipherGuard(Option<Arc<DatabaseCipher>>);
+
+impl Drop for CipherGuard {
+    fn drop(&mut self) {
+        CIPHER.with(|cipher| *cipher.borrow_mut() = self.0.take());
+    }
+}
+
+/// Runs the callback with the values of this thread encrypted by the cipher
+pub(crate) fn with_cipher<T>(
+    cipher: Option<Arc<DatabaseCipher>>,
+    callback: impl FnOnce() -> T,
+) -> T {
+    let _guard = CipherGuard(CIPHER.with(|current| current.replace(cipher)));
+
+    callback()
+}
+
+pub(crate) fn is_encrypted() -> bool {
+    CIPHER.with(|cipher| cipher.borrow().is_some())
+}
+
+pub(crate) fn current_cipher() -> Option<Arc<DatabaseCipher>> {
+    CIPHER.with(|cipher| cipher.borrow().clone())
+}
+
+/// Whether the value was written by a cipher
+pub(crate) fn is_sealed(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+/// Encrypts a value before it is written, when the database is encrypted
+pub(crate) fn encrypt(text: String) -> String {
+    CIPHER.with(|cipher| match cipher.borrow().as_ref() {
+        Some(cipher) => cipher.seal(&text),
+        None => text,
+    })
+}
+
+/// Decrypts a value that was read. Without a cipher plain text is returned as it is,
+/// with one every value has to be encrypted.
+pub(crate) fn decrypt(value: String) -> DatabaseResult<String> {
+    CIPHER.with(|cipher| match cipher.borrow().as_ref() {
+        Some(cipher) => cipher.open(&value),
+        None if is_sealed(&value) => Err(DatabaseError::MissingDatabaseKey),
+        None => Ok(value),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher(passphrase: &str) -> DatabaseCipher {
+        DatabaseCipher::derive(
+            &DatabaseKey::from_passphrase(passphrase),
+            "000102030405060708090a0b0c0d0e0f",
+            1_000,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn it_derives_keys_with_the_default_iterations() {
+        let key = DatabaseKey::from_passphrase("correct horse battery staple");
+        let salt = "000102030405060708090a0b0c0d0e0f";
+        let cipher = DatabaseCipher::derive(&key, salt, key.kdf_iterations()).unwrap();
+
+        assert_eq!(DEFAULT_KDF_ITERATIONS, key.kdf_iterations());
+        assert_ne!(
+            cipher.key_check(),
+            DatabaseCipher::derive(&key, salt, 1_000)
+                .unwrap()
+                .key_check()
+        );
+        assert_eq!(
+            "Pick up the test results",
+            cipher
+                .open(&cipher.seal("Pick up the test results"))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_opens_what_it_sealed() {
+        let cipher = cipher("correct horse battery staple");
+        let sealed = cipher.seal("Pick up the test results");
+
+        assert!(sealed.starts_with(ENCRYPTED_PREFIX));
+        assert!(!sealed.contains("results"));
+        assert_ne!(sealed, cipher.seal("Pick up the test results"));
+        assert_eq!("Pick up the test results", cipher.open(&sealed).unwrap());
+    }
+
+    #[test]
+    fn it_refuses_plain_text_once_there_is_a_cipher() {
+        let cipher = Arc::new(cipher("correct horse battery staple"));
+
+        assert!(matches!(
+            cipher.open("Written in plain text"),
+            Err(DatabaseError::InvalidCiphertext)
+        ));
+        assert!(matches!(
+            with_cipher(Some(cipher), || decrypt("Written in plain text".to_owned())),
+            Err(DatabaseError::InvalidCiphertext)
+        ));
+        assert_eq!(
+            "Written in plain text",
+            decrypt("Written in plain text".to_owned()).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_refuses_values_of_another_key_or_changed_values() {
+        let sealed = cipher("correct horse battery staple").seal("Pick up the test results");
+        let index = ENCRYPTED_PREFIX.len() + NONCE_LENGTH * 2 + 4;
+        let flipped = if &sealed[index..=index] == "0" {
+            "1"
+        } else {
+            "0"
+        };
+        let mut changed = sealed.clone();
+        changed.replace_range(index..=index, flipped);
+
+        assert_ne!(
+            cipher("correct horse battery staple").key_check(),
+            cipher("wrong horse").key_check()
+        );
+        assert!(matches!(
+            cipher("wrong horse").open(&sealed),
+            Err(DatabaseError::InvalidCiphertext)
+        ));
+        assert!(matches!(
+            cipher("correct horse battery staple").open(&changed),
+            Err(DatabaseError::InvalidCiphertext)
+        ));
+    }
+
+    #[test]
+    fn it_encrypts_values_only_within_a_cipher() {
+        let cipher = Arc::new(cipher("correct horse battery staple"));
+        let sealed = with_cipher(Some(cipher.clone()), || {
+            assert!(is_encrypted());
+
+            encrypt("Pick up the test results".to_owned())
+        });
+
+        assert!(!is_encrypted());
+        assert_eq!("Plain", encrypt("Plain".to_owned()));
+        assert!(matches!(
+            decrypt(sealed.clone()),
+            Err(DatabaseError::MissingDatabaseKey)
+        ));
+        assert_eq!(
+            "Pick up the test results",
+            with_cipher(Some(cipher), || decrypt(sealed)).unwrap()
+        );
+    }
+}
@@ -1,14 +1,26 @@
-use cfg_if::cfg_if;
-use diesel::{result::Error as DieselResultError, Connection, ConnectionError};
-use diesel_migrations::{EmbeddedMigrations, MigrationHarness};
+use diesel::r2d2::{Pool, PoolError};
+use diesel::{result::Error as DieselResultError, ConnectionError};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use hkb_date::date::DateError;
 use log::{debug, error};
 use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error as ThisError;
 
+mod connection;
+pub mod encryption;
 pub(crate) mod models;
 mod schema;
 pub mod services;
 
+pub use connection::{
+    DatabaseBackend, DatabaseConnection, DatabaseConnectionManager, DatabaseKind,
+};
+pub use encryption::{database_key, DatabaseKey};
+
+use encryption::DatabaseCipher;
+
 #[derive(ThisError, Debug)]
 pub enum DatabaseError {
     #[error("Database not initialized!")]
@@ -19,34 +31,206 @@ pub enum DatabaseError {
     FailedToEstablishConnection(#[from] ConnectionError),
     #[error(transparent)]
     FailedToFetchResult(#[from] DieselResultError),
+    #[error("Failed to get a connection from the pool")]
+    FailedToGetConnection(#[from] PoolError),
+    #[error("Database task did not finish")]
+    FailedToFinishTask(#[from] tokio::task::JoinError),
+    #[error("Invalid date: {0}")]
+    InvalidDate(#[from] DateError),
+    #[error("{0} databases are not enabled in this build")]
+    UnsupportedDatabase(DatabaseKind),
+    #[error("Invalid reminder snapshot in the journal")]
+    InvalidSnapshot(#[from] serde_json::Error),
+    #[error("Invalid recurrence of a reminder")]
+    InvalidRecurrence(#[source] serde_json::Error),
+    #[error("Invalid change in the journal: {0}")]
+    InvalidChange(String),
+    #[error("The database is encrypted, a key is needed to open it")]
+    MissingDatabaseKey,
+    #[error("The key does not open this database")]
+    WrongDatabaseKey,
+    #[error("Encrypted data in the database is damaged")]
+    InvalidCiphertext,
+    #[error("Failed to read the key file")]
+    FailedToReadKeyFile(#[source] std::io::Error),
 }
 
-cfg_if! {
-     if #[cfg(feature = "mysql-database" )] {
-        use diesel::MysqlConnection;
+pub type DatabasePool = Pool<DatabaseConnectionManager>;
 
-        type DatabaseConnection = MysqlConnection;
-    } else if #[cfg(feature = "sqlite-database" )] {
-        use diesel::SqliteConnection;
+/// Migrations written for every backend, only the ones of the backend picked by the url are run
+pub struct DatabaseMigrations {
+    pub sqlite: EmbeddedMigrations,
+    pub mysql: EmbeddedMigrations,
+    pub postgres: EmbeddedMigrations,
+}
 
-        type DatabaseConnection = SqliteConnection;
+impl DatabaseMigrations {
+    fn for_kind(self, kind: DatabaseKind) -> EmbeddedMigrations {
+        match kind {
+            DatabaseKind::Sqlite => self.sqlite,
+            DatabaseKind::Mysql => self.mysql,
+            DatabaseKind::Postgres => self.postgres,
+        }
     }
 }
 
-static GLOBAL_CONNECTION: Mutex<Option<DatabaseConnection>> = parking_lot::const_mutex(None);
+/// The tables of the reminders and everything that belongs to them
+pub const CORE_MIGRATIONS: DatabaseMigrations = DatabaseMigrations {
+    sqlite: embed_migrations!("./migrations/sqlite"),
+    mysql: embed_migrations!("./migrations/mysql"),
+    postgres: embed_migrations!("./migrations/postgres"),
+};
+
+/// The database the tests run on, `HKB_TEST_DATABASE_URL` points them at another backend
+#[cfg(test)]
+pub(crate) fn test_database_url() -> String {
+    std::env::var("HKB_TEST_DATABASE_URL").unwrap_or_else(|_| ":memory:".to_owned())
+}
+
+/// Empties every table and forgets the cipher, so a test does not see what the tests of other modules left behind
+#[cfg(test)]
+pub(crate) fn clear_test_database() {
+    use diesel::RunQueryDsl;
+
+    // tables that point at others go first
+    let tables = [
+        "reminder_tags",
+        "reminder_alerts",
+        "reminder_checklist_items",
+        "reminder_changes",
+        "reminders",
+        "tags",
+        "sync_devices",
+        "database_keys",
+    ];
+
+    set_global_cipher(None);
+    within_database(|conn| {
+        for table in tables {
+            diesel::sql_query(format!("DELETE FROM {table}")).execute(conn)?;
+        }
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+static GLOBAL_POOL: Mutex<Option<DatabasePool>> = parking_lot::const_mutex(None);
+/// Set once an encrypted database is unlocked, see `services::encryption::unlock_database`
+static GLOBAL_CIPHER: Mutex<Option<Arc<DatabaseCipher>>> = parking_lot::const_mutex(None);
 
 pub type DatabaseResult<T> = Result<T, DatabaseError>;
 
-pub fn init_database(url: &str, migrations: Vec<EmbeddedMigrations>) -> Result<(), DatabaseError> {
-    let mut connection = {
-        cfg_if! {
-            if #[cfg(feature = "mysql-database")] {
-                MysqlConnection::establish(url)
-            } else if #[cfg(feature = "sqlite-database")] {
-                SqliteConnection::establish(url)
-            }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DatabaseConfig {
+    /// Most connections open at the same time, in memory databases always use one
+    pub pool_size: u32,
+    /// How long a connection waits for another one to release its lock on the database
+    pub busy_timeout: Duration,
+    /// How long a caller waits for a free connection in the pool
+    pub connection_timeout: Duration,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            pool_size: 4,
+            busy_timeout: Duration::from_secs(5),
+            connection_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SqliteConnectionOptions {
+    busy_timeout: Duration,
+}
+
+impl diesel::r2d2::CustomizeConnection<DatabaseConnection, diesel::r2d2::Error>
+    for SqliteConnectionOptions
+{
+    fn on_acquire(&self, connection: &mut DatabaseConnection) -> Result<(), diesel::r2d2::Error> {
+        use diesel::connection::SimpleConnection;
+
+        if connection.kind() != DatabaseKind::Sqlite {
+            return Ok(());
         }
-    }?;
+
+        // the busy timeout goes first, so switching the journal mode can wait for other connections.
+        // Foreign keys are off unless they are turned on for every connection, without them
+        // the tags, alerts and checklists of a removed reminder would not be removed with it.
+        connection
+            .batch_execute(&format!(
+                "PRAGMA busy_timeout = {}; PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL; \
+                 PRAGMA foreign_keys = ON;",
+                self.busy_timeout.as_millis()
+            ))
+            .map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
+fn is_in_memory(url: &str) -> bool {
+    DatabaseKind::from_url(url) == DatabaseKind::Sqlite
+        && (url.ends_with(":memory:") || url.contains("mode=memory"))
+}
+
+/// Opens a pool of connections to the database, without running any migrations.
+/// The backend is picked by the scheme of the url, see `DatabaseKind::from_url`.
+pub fn connect(url: &str, config: DatabaseConfig) -> DatabaseResult<DatabasePool> {
+    let kind = DatabaseKind::from_url(url);
+
+    if !kind.is_enabled() {
+        error!(target: "CORE_DATABASE", "{kind} databases are not enabled in this build");
+
+        return Err(DatabaseError::UnsupportedDatabase(kind));
+    }
+
+    let mut builder = Pool::builder()
+        .max_size(config.pool_size.max(1))
+        .connection_timeout(config.connection_timeout)
+        .connection_customizer(Box::new(SqliteConnectionOptions {
+            busy_timeout: config.busy_timeout,
+        }));
+
+    // every connection to an in memory database has a database of its own,
+    // so the only connection is kept for as long as the pool lives
+    if is_in_memory(url) {
+        builder = builder.max_size(1).max_lifetime(None).idle_timeout(None);
+    }
+
+    let pool = builder
+        .build(DatabaseConnectionManager::new(url))
+        .map_err(|e| {
+            error!(target: "CORE_DATABASE", "Failed to open the connection pool: {e}");
+
+            DatabaseError::FailedToGetConnection(e)
+        })?;
+
+    Ok(pool)
+}
+
+/// `HKB_DATABASE_URL` when it is set, e.g. `postgres://hkb@localhost/hkb`,
+/// otherwise the sqlite database in the local data directory
+pub fn database_url() -> String {
+    std::env::var("HKB_DATABASE_URL").unwrap_or_else(|_| {
+        let database_file_path = dirs::data_local_dir().unwrap().join("hkb/db");
+
+        database_file_path.to_string_lossy().into_owned()
+    })
+}
+
+pub fn init_database(url: &str, migrations: Vec<DatabaseMigrations>) -> Result<(), DatabaseError> {
+    init_database_with_config(url, migrations, DatabaseConfig::default())
+}
+
+pub fn init_database_with_config(
+    url: &str,
+    migrations: Vec<DatabaseMigrations>,
+    config: DatabaseConfig,
+) -> Result<(), DatabaseError> {
+    let pool = connect(url, config)?;
+    let mut connection = pool.get()?;
+    let kind = connection.kind();
 
     debug!(target: "CORE_DATABASE", "Running migrations");
     // TODO: maybe we can use iter.enumurate() for this?
@@ -55,7 +239,7 @@ pub fn init_database(url: &str, migrations: Vec<EmbeddedMigrations>) -> Result<(
     for migration in migrations {
         debug!(target: "CORE_DATABASE", "Starting migration {i}.");
 
-        if let Err(e) = connection.run_pending_migrations(migration) {
+        if let Err(e) = connection.run_pending_migrations(migration.for_kind(kind)) {
             error!(target: "CORE_DATABASE", "Failed to run migration: {e}");
 
             return Err(DatabaseError::FailedToRunMigrations);
@@ -66,22 +250,139 @@ pub fn init_database(url: &str, migrations: Vec<EmbeddedMigrations>) -> Result<(
         i += 1;
     }
 
-    let mut conn = GLOBAL_CONNECTION.lock();
-    *conn = Some(connection);
+    drop(connection);
+
+    let mut global_pool = GLOBAL_POOL.lock();
+    *global_pool = Some(pool);
+    // the new database is unlocked on its own
+    set_global_cipher(None);
 
     Ok(())
 }
 
+pub(crate) fn set_global_cipher(cipher: Option<Arc<DatabaseCipher>>) {
+    *GLOBAL_CIPHER.lock() = cipher;
+}
+
+/// Runs the callback with a connection from the pool, callers on other threads get connections of their own.
+/// Values the callback reads and writes are encrypted with the key the database was unlocked with.
 pub fn within_database<T, F: FnOnce(&mut DatabaseConnection) -> DatabaseResult<T>>(
     callback: F,
 ) -> DatabaseResult<T> {
-    let mut conn = GLOBAL_CONNECTION.lock();
+    // the lock is only held to clone the pool, so callbacks do not wait on each other
+    let pool = GLOBAL_POOL.lock().clone();
+    let cipher = GLOBAL_CIPHER.lock().clone();
+
+    if let Some(pool) = pool {
+        let mut connection = pool.get()?;
 
-    if let Some(connection) = (*conn).as_mut() {
         debug!("Found connection. Executing database callback.");
 
-        callback(connection)
+        encryption::with_cipher(cipher, || callback(&mut connection))
     } else {
         Err(DatabaseError::DatabaseNotInitialized)
     }
 }
+
+/// Runs blocking database work on the blocking thread pool of tokio,
+/// so async tasks keep running while it waits on the disk
+pub async fn run_blocking<T, F>(callback: F) -> DatabaseResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> DatabaseResult<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(callback).await?
+}
+
+/// The same as `within_database`, for callers inside async tasks
+pub async fn within_database_async<T, F>(callback: F) -> DatabaseResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut DatabaseConnection) -> DatabaseResult<T> + Send + 'static,
+{
+    run_blocking(move || within_database(callback)).await
+}
+
+#[cfg(all(test, feature = "sqlite-database"))]
+mod tests {
+    use diesel::sql_types::{BigInt, Text};
+    use diesel::{sql_query, QueryableByName, RunQueryDsl};
+    use std::thread;
+
+    use super::*;
+
+    #[derive(QueryableByName)]
+    struct JournalMode {
+        #[diesel(sql_type = Text)]
+        journal_mode: String,
+    }
+
+    #[derive(QueryableByName)]
+    struct Count {
+        #[diesel(sql_type = BigInt)]
+        count: i64,
+    }
+
+    #[test]
+    fn it_shares_a_file_database_between_connections() {
+        let directory = std::env::temp_dir().join(format!("hkb-pool-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let url = directory.join("db");
+        let pool = connect(url.to_str().unwrap(), DatabaseConfig::default()).unwrap();
+
+        sql_query("CREATE TABLE entries (id INTEGER PRIMARY KEY)")
+            .execute(&mut pool.get().unwrap())
+            .unwrap();
+
+        let writers: Vec<_> = (0..8)
+            .map(|_| {
+                let pool = pool.clone();
+
+                thread::spawn(move || {
+                    for _ in 0..10 {
+                        sql_query("INSERT INTO entries DEFAULT VALUES")
+                            .execute(&mut pool.get().unwrap())
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let mode = sql_query("PRAGMA journal_mode")
+            .get_result::<JournalMode>(&mut pool.get().unwrap())
+            .unwrap();
+        let entries = sql_query("SELECT COUNT(*) AS count FROM entries")
+            .get_result::<Count>(&mut pool.get().unwrap())
+            .unwrap();
+
+        assert_eq!("wal", mode.journal_mode);
+        assert_eq!(4, pool.state().connections);
+        assert_eq!(80, entries.count);
+
+        drop(pool);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn it_keeps_a_single_connection_to_in_memory_databases() {
+        let config = DatabaseConfig {
+            pool_size: 8,
+            ..DatabaseConfig::default()
+        };
+        let pool = connect(":memory:", config).unwrap();
+
+        sql_query("CREATE TABLE entries (id INTEGER PRIMARY KEY)")
+            .execute(&mut pool.get().unwrap())
+            .unwrap();
+
+        assert_eq!(1, pool.max_size());
+        assert!(sql_query("SELECT * FROM entries")
+            .execute(&mut pool.get().unwrap())
+            .is_ok());
+    }
+}
@@ -0,0 +1,20 @@
+use crate::database::schema::reminder_alerts;
+use chrono::NaiveDateTime;
+use diesel::prelude::{Insertable, Queryable, Selectable};
+
+#[derive(Debug, Queryable, Selectable)]
+#[diesel(table_name = reminder_alerts)]
+#[diesel(check_for_backend(crate::database::DatabaseBackend))]
+pub(crate) struct ReminderAlert {
+    pub id: i64,
+    pub reminder_id: i64,
+    pub offset_minutes: i32,
+    pub fired_for: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = reminder_alerts)]
+pub(crate) struct CreateReminderAlert {
+    pub reminder_id: i64,
+    pub offset_minutes: i32,
+}
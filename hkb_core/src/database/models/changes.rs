@@ -0,0 +1,33 @@
+use crate::database::schema::reminder_changes;
+use chrono::NaiveDateTime;
+use diesel::prelude::{Insertable, Queryable, Selectable};
+
+/// Snapshots are reminders serialized to json.
+/// The clock and the device order the changes of a reminder across devices.
+#[derive(Debug, Queryable, Selectable)]
+#[diesel(table_name = reminder_changes)]
+#[diesel(check_for_backend(crate::database::DatabaseBackend))]
+pub(crate) struct ReminderChange {
+    pub id: i64,
+    pub reminder_id: i64,
+    pub operation: String,
+    pub snapshot_before: Option<String>,
+    pub snapshot_after: Option<String>,
+    pub changed_at: NaiveDateTime,
+    pub reminder_uuid: Option<String>,
+    pub clock: i64,
+    pub device_id: Option<String>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = reminder_changes)]
+pub(crate) struct CreateReminderChange {
+    pub reminder_id: i64,
+    pub operation: String,
+    pub snapshot_before: Option<String>,
+    pub snapshot_after: Option<String>,
+    pub changed_at: NaiveDateTime,
+    pub reminder_uuid: Option<String>,
+    pub clock: i64,
+    pub device_id: Option<String>,
+}
@@ -0,0 +1,30 @@
+use crate::database::schema::reminder_checklist_items;
+use diesel::prelude::{AsChangeset, Insertable, Queryable, Selectable};
+
+#[derive(Debug, Queryable, Selectable)]
+#[diesel(table_name = reminder_checklist_items)]
+#[diesel(check_for_backend(crate::database::DatabaseBackend))]
+pub(crate) struct ReminderChecklistItem {
+    pub id: i64,
+    pub reminder_id: i64,
+    pub title: String,
+    pub done: bool,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = reminder_checklist_items)]
+pub(crate) struct CreateReminderChecklistItem {
+    pub id: Option<i64>,
+    pub reminder_id: i64,
+    pub title: String,
+    pub position: i32,
+    pub done: bool,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = reminder_checklist_items)]
+pub(crate) struct UpdateReminderChecklistItem {
+    pub title: String,
+    pub position: i32,
+    pub done: bool,
+}
@@ -0,0 +1,13 @@
+use crate::database::schema::database_keys;
+use diesel::prelude::{Insertable, Queryable, Selectable};
+
+/// The salt a key derives the cipher of the database with, the only row has the id 1
+#[derive(Debug, Queryable, Selectable, Insertable)]
+#[diesel(table_name = database_keys)]
+#[diesel(check_for_backend(crate::database::DatabaseBackend))]
+pub(crate) struct DatabaseKeyCheck {
+    pub id: i64,
+    pub salt: String,
+    pub iterations: i32,
+    pub key_check: String,
+}
@@ -1 +1,7 @@
+pub mod alerts;
+pub mod changes;
+pub mod checklists;
+pub mod encryption;
 pub mod reminders;
+pub mod sync;
+pub mod tags;
@@ -1,37 +1,85 @@
 use crate::database::schema::reminders;
+use chrono::NaiveDateTime;
 use diesel::{
     prelude::{Insertable, Queryable, Selectable},
     query_builder::AsChangeset,
 };
 
+/// Dates are UTC instants, `timezone` is the IANA zone of the system they were set on
+/// and is missing for reminders written before it was kept.
+/// The uuid is the same on every device the reminder is synced to, the id is not.
 #[derive(Debug, Queryable, Selectable)]
 #[diesel(table_name = reminders)]
-#[cfg_attr(
-    feature = "mysql-database",
-    diesel(check_for_backend(diesel::mysql::Mysql))
-)]
-#[cfg_attr(
-    feature = "sqlite-database",
-    diesel(check_for_backend(diesel::sqlite::Sqlite))
-)]
+#[diesel(check_for_backend(crate::database::DatabaseBackend))]
 pub(crate) struct Reminder {
     pub id: i64,
+    pub uuid: String,
     pub note: String,
-    pub remind_at: String,
-    pub created_at: String,
+    pub remind_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub recurrence: Option<String>,
+    pub occurrence: i32,
+    pub original_remind_at: Option<NaiveDateTime>,
+    pub snooze_count: i32,
+    pub completed_at: Option<NaiveDateTime>,
+    pub dismissed_at: Option<NaiveDateTime>,
+    pub priority: i32,
+    pub timezone: Option<String>,
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
-#[derive(Debug, AsChangeset)]
+#[derive(Debug, Default, PartialEq, AsChangeset)]
 #[diesel(table_name = reminders)]
 pub(crate) struct UpdateReminder {
     pub note: Option<String>,
-    pub remind_at: Option<String>,
+    pub remind_at: Option<NaiveDateTime>,
+    pub recurrence: Option<Option<String>>,
+    pub occurrence: Option<i32>,
+    pub original_remind_at: Option<Option<NaiveDateTime>>,
+    pub snooze_count: Option<i32>,
+    pub completed_at: Option<Option<NaiveDateTime>>,
+    pub dismissed_at: Option<Option<NaiveDateTime>>,
+    pub priority: Option<i32>,
+    pub timezone: Option<Option<String>>,
+    pub deleted_at: Option<Option<NaiveDateTime>>,
+}
+
+impl UpdateReminder {
+    pub fn has_changes(&self) -> bool {
+        *self != Self::default()
+    }
 }
 
 #[derive(Insertable)]
 #[diesel(table_name = reminders)]
 pub(crate) struct CreateReminder {
+    pub uuid: String,
+    pub note: String,
+    pub remind_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub recurrence: Option<String>,
+    pub occurrence: i32,
+    pub completed_at: Option<NaiveDateTime>,
+    pub priority: i32,
+    pub timezone: Option<String>,
+}
+
+/// A reminder written with all of its state, without an id it gets a new one
+#[derive(Insertable)]
+#[diesel(table_name = reminders)]
+pub(crate) struct RestoreReminder {
+    pub id: Option<i64>,
+    pub uuid: String,
     pub note: String,
-    pub remind_at: String,
-    pub created_at: String,
+    pub remind_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub recurrence: Option<String>,
+    pub occurrence: i32,
+    pub original_remind_at: Option<NaiveDateTime>,
+    pub snooze_count: i32,
+    pub completed_at: Option<NaiveDateTime>,
+    pub dismissed_at: Option<NaiveDateTime>,
+    pub priority: i32,
+    pub timezone: Option<String>,
+    pub deleted_at: Option<NaiveDateTime>,
 }
@@ -0,0 +1,13 @@
+use crate::database::schema::sync_devices;
+use diesel::prelude::{Insertable, Queryable, Selectable};
+
+/// `last_change_id` is the id of the last change taken from the device,
+/// in the journal of that device
+#[derive(Debug, Queryable, Selectable, Insertable)]
+#[diesel(table_name = sync_devices)]
+#[diesel(check_for_backend(crate::database::DatabaseBackend))]
+pub(crate) struct SyncDevice {
+    pub device_id: String,
+    pub is_local: bool,
+    pub last_change_id: i64,
+}
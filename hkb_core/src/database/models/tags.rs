@@ -0,0 +1,15 @@
+use crate::database::schema::{reminder_tags, tags};
+use diesel::prelude::Insertable;
+
+#[derive(Insertable)]
+#[diesel(table_name = tags)]
+pub(crate) struct CreateTag {
+    pub name: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = reminder_tags)]
+pub(crate) struct CreateReminderTag {
+    pub reminder_id: i64,
+    pub tag_id: i64,
+}
@@ -2,7 +2,97 @@ diesel::table! {
     reminders (id) {
         id -> Int8,
         note -> Varchar,
-        remind_at -> Date,
-        created_at -> Date,
+        remind_at -> Timestamp,
+        created_at -> Timestamp,
+        recurrence -> Nullable<Text>,
+        occurrence -> Integer,
+        original_remind_at -> Nullable<Timestamp>,
+        snooze_count -> Integer,
+        completed_at -> Nullable<Timestamp>,
+        dismissed_at -> Nullable<Timestamp>,
+        priority -> Integer,
+        timezone -> Nullable<Text>,
+        deleted_at -> Nullable<Timestamp>,
+        uuid -> Text,
     }
 }
+
+diesel::table! {
+    tags (id) {
+        id -> Int8,
+        name -> Varchar,
+    }
+}
+
+diesel::table! {
+    reminder_tags (reminder_id, tag_id) {
+        reminder_id -> Int8,
+        tag_id -> Int8,
+    }
+}
+
+diesel::table! {
+    reminder_alerts (id) {
+        id -> Int8,
+        reminder_id -> Int8,
+        offset_minutes -> Integer,
+        fired_for -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    reminder_changes (id) {
+        id -> Int8,
+        reminder_id -> Int8,
+        operation -> Text,
+        snapshot_before -> Nullable<Text>,
+        snapshot_after -> Nullable<Text>,
+        changed_at -> Timestamp,
+        reminder_uuid -> Nullable<Text>,
+        clock -> Int8,
+        device_id -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    reminder_checklist_items (id) {
+        id -> Int8,
+        reminder_id -> Int8,
+        title -> Text,
+        position -> Integer,
+        done -> Bool,
+    }
+}
+
+diesel::table! {
+    sync_devices (device_id) {
+        device_id -> Text,
+        is_local -> Bool,
+        last_change_id -> Int8,
+    }
+}
+
+diesel::table! {
+    database_keys (id) {
+        id -> Int8,
+        salt -> Text,
+        iterations -> Integer,
+        key_check -> Text,
+    }
+}
+
+diesel::joinable!(reminder_tags -> reminders (reminder_id));
+diesel::joinable!(reminder_tags -> tags (tag_id));
+diesel::joinable!(reminder_alerts -> reminders (reminder_id));
+diesel::joinable!(reminder_checklist_items -> reminders (reminder_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    reminders,
+    tags,
+    reminder_tags,
+    reminder_alerts,
+    reminder_changes,
+    reminder_checklist_items,
+    sync_devices,
+    database_keys
+);
@@ -0,0 +1,596 @@
+use hkb_date::date::SimpleDate;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use thiserror::Error as ThisError;
+
+use super::reminders::{
+    self, AlertOffset, ReminderData, ReminderQuery, RestoreConflict, RestoreSummary, TrashFilter,
+};
+use crate::database::{self, encryption, DatabaseError};
+
+/// Bumped whenever the layout of a backup changes, older backups can still be read
+pub const BACKUP_VERSION: u32 = 1;
+
+const BACKUP_FILE_PREFIX: &str = "hkb-backup-";
+const BACKUP_FILE_DATE_FORMAT: &str = "%Y%m%dT%H%M%S";
+const CSV_VERSION_PREFIX: &str = "# hkb backup version ";
+
+#[derive(ThisError, Debug)]
+pub enum BackupError {
+    #[error("Failed to access the backup file")]
+    FailedToAccessFile(#[from] std::io::Error),
+    #[error("Failed to read JSON backup")]
+    InvalidJson(#[from] serde_json::Error),
+    #[error("Failed to read CSV backup")]
+    InvalidCsv(#[from] csv::Error),
+    #[error("Invalid value in column {column} of CSV record {record}")]
+    InvalidCsvValue { record: usize, column: &'static str },
+    #[error("Backup version {0} is newer than what is supported")]
+    UnsupportedVersion(u32),
+    #[error("Unknown backup format: {0}")]
+    UnknownFormat(String),
+    #[error(transparent)]
+    FailedToAccessDatabase(#[from] DatabaseError),
+}
+
+pub type BackupResult<T> = Result<T, BackupError>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BackupFormat {
+    /// Keeps everything in the database
+    #[default]
+    Json,
+    /// Only has reminders, one per row
+    Csv,
+}
+
+impl BackupFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            BackupFormat::Json => "json",
+            BackupFormat::Csv => "csv",
+        }
+    }
+
+    pub fn from_path(path: impl AsRef<Path>) -> BackupResult<Self> {
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+
+        extension.parse()
+    }
+}
+
+impl Display for BackupFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+impl FromStr for BackupFormat {
+    type Err = BackupError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(BackupFormat::Json),
+            "csv" => Ok(BackupFormat::Csv),
+            _ => Err(BackupError::UnknownFormat(s.to_owned())),
+        }
+    }
+}
+
+/// Everything in the database.
+/// New tables get their own field, defaulting to empty, so older backups can still be read.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Backup {
+    pub version: u32,
+    pub created_at: SimpleDate,
+    #[serde(default)]
+    pub reminders: Vec<ReminderData>,
+}
+
+#[derive(Deserialize)]
+struct BackupVersion {
+    version: u32,
+}
+
+/// A reminder as a CSV row, lists and recurrences are kept as JSON
+#[derive(Serialize, Deserialize)]
+struct CsvReminder {
+    id: i64,
+    note: String,
+    remind_at: String,
+    created_at: String,
+    recurrence: String,
+    occurrence: u32,
+    original_remind_at: String,
+    snooze_count: u32,
+    completed_at: String,
+    dismissed_at: String,
+    priority: String,
+    tags: String,
+    alerts: String,
+    #[serde(default)]
+    deleted_at: String,
+    #[serde(default)]
+    checklist: String,
+    #[serde(default)]
+    uuid: String,
+    #[serde(default)]
+    timezone: String,
+}
+
+impl CsvReminder {
+    fn from_reminder(reminder: &ReminderData) -> BackupResult<Self> {
+        let format_date = |date: Option<SimpleDate>| date.map(|date| date.to_string());
+        let alerts: Vec<u32> = reminder.alerts.iter().map(|alert| alert.minutes).collect();
+
+        Ok(Self {
+            id: reminder.id,
+            note: reminder.note.clone(),
+            remind_at: reminder.remind_at.to_string(),
+            created_at: reminder.created_at.to_string(),
+            recurrence: match &reminder.recurrence {
+                Some(recurrence) => serde_json::to_string(recurrence)?,
+                None => String::new(),
+            },
+            occurrence: reminder.occurrence,
+            original_remind_at: format_date(reminder.original_remind_at).unwrap_or_default(),
+            snooze_count: reminder.snooze_count,
+            completed_at: format_date(reminder.completed_at).unwrap_or_default(),
+            dismissed_at: format_date(reminder.dismissed_at).unwrap_or_default(),
+            priority: reminder.priority.to_string(),
+            tags: serde_json::to_string(&reminder.tags)?,
+            alerts: serde_json::to_string(&alerts)?,
+            deleted_at: format_date(reminder.deleted_at).unwrap_or_default(),
+            checklist: serde_json::to_string(&reminder.checklist)?,
+            uuid: reminder.uuid.clone(),
+            timezone: reminder.timezone.clone().unwrap_or_default(),
+        })
+    }
+
+    fn into_reminder(self, record: usize) -> BackupResult<ReminderData> {
+        let invalid = |column| BackupError::InvalidCsvValue { record, column };
+        let parse_date =
+            |date: &str, column| SimpleDate::parse_from_rfc3339(date).map_err(|_| invalid(column));
+        let parse_optional_date = |date: &str, column| match date {
+            "" => Ok(None),
+            date => parse_date(date, column).map(Some),
+        };
+        let alerts: Vec<u32> = serde_json::from_str(&self.alerts).map_err(|_| invalid("alerts"))?;
+
+        Ok(ReminderData {
+            id: self.id,
+            // reminders of backups made before uuids existed get new ones when they are restored
+            uuid: self.uuid,
+            remind_at: parse_date(&self.remind_at, "remind_at")?,
+            created_at: parse_date(&self.created_at, "created_at")?,
+            recurrence: match self.recurrence.as_str() {
+                "" => None,
+                recurrence => {
+                    Some(serde_json::from_str(recurrence).map_err(|_| invalid("recurrence"))?)
+                }
+            },
+            occurrence: self.occurrence,
+            original_remind_at: parse_optional_date(
+                &self.original_remind_at,
+                "original_remind_at",
+            )?,
+            snooze_count: self.snooze_count,
+            completed_at: parse_optional_date(&self.completed_at, "completed_at")?,
+            dismissed_at: parse_optional_date(&self.dismissed_at, "dismissed_at")?,
+            priority: self.priority.parse().map_err(|_| invalid("priority"))?,
+            tags: serde_json::from_str(&self.tags).map_err(|_| invalid("tags"))?,
+            alerts: alerts
+                .into_iter()
+                .map(AlertOffset::minutes_before)
+                .collect(),
+            deleted_at: parse_optional_date(&self.deleted_at, "deleted_at")?,
+            // backups made before checklists existed do not have the column
+            checklist: match self.checklist.as_str() {
+                "" => vec![],
+                checklist => serde_json::from_str(checklist).map_err(|_| invalid("checklist"))?,
+            },
+            note: self.note,
+            timezone: Some(self.timezone).filter(|timezone| !timezone.is_empty()),
+        })
+    }
+}
+
+impl Backup {
+    /// Takes everything that is in the database
+    pub fn create() -> BackupResult<Self> {
+        Ok(Self {
+            version: BACKUP_VERSION,
+            created_at: SimpleDate::local(),
+            reminders: reminders::query_reminders(
+                &ReminderQuery::new().trash(TrashFilter::Include),
+            )?,
+        })
+    }
+
+    /// Backups of an encrypted database are sealed as a whole with its key
+    pub fn write(&self, format: BackupFormat, mut writer: impl Write) -> BackupResult<()> {
+        let mut content = vec![];
+
+        match format {
+            BackupFormat::Json => serde_json::to_writer_pretty(&mut content, self)?,
+            BackupFormat::Csv => {
+                writeln!(content, "{CSV_VERSION_PREFIX}{}", self.version)?;
+
+                let mut csv_writer = csv::Writer::from_writer(&mut content);
+
+                for reminder in self.reminders.iter() {
+                    csv_writer.serialize(CsvReminder::from_reminder(reminder)?)?;
+                }
+
+                csv_writer.flush()?;
+            }
+        }
+
+        let content = String::from_utf8(content).expect("backups are written as UTF-8");
+        let content = database::within_database(|_| Ok(encryption::encrypt(content)))?;
+        writer.write_all(content.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Sealed backups can only be read while the database has the key they were written with
+    pub fn read(format: BackupFormat, mut reader: impl Read) -> BackupResult<Self> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+
+        if encryption::is_sealed(&content) {
+            content = database::within_database(|_| encryption::decrypt(content))?;
+        }
+
+        match format {
+            BackupFormat::Json => {
+                let BackupVersion { version } = serde_json::from_str(&content)?;
+
+                if version > BACKUP_VERSION {
+                    return Err(BackupError::UnsupportedVersion(version));
+                }
+
+                Ok(serde_json::from_str(&content)?)
+            }
+            BackupFormat::Csv => {
+                let (version, rows) = match content.strip_prefix(CSV_VERSION_PREFIX) {
+                    Some(content) => {
+                        let (version, rows) = content.split_once('\n').unwrap_or((content, ""));
+                        let version = version
+                            .trim()
+                            .parse()
+                            .map_err(|_| BackupError::UnknownFormat(version.to_owned()))?;
+
+                        (version, rows)
+                    }
+                    // a file edited by hand might lose its version line
+                    None => (BACKUP_VERSION, content.as_str()),
+                };
+
+                if version > BACKUP_VERSION {
+                    return Err(BackupError::UnsupportedVersion(version));
+                }
+
+                let reminders = csv::Reader::from_reader(rows.as_bytes())
+                    .deserialize::<CsvReminder>()
+                    .enumerate()
+                    .map(|(index, record)| record?.into_reminder(index + 1))
+                    .collect::<BackupResult<Vec<ReminderData>>>()?;
+
+                Ok(Self {
+                    version,
+                    created_at: SimpleDate::local(),
+                    reminders,
+                })
+            }
+        }
+    }
+
+    /// Writes the backup into the database, `conflict` decides what happens to reminders that are already there
+    pub fn restore(self, conflict: RestoreConflict) -> BackupResult<RestoreSummary> {
+        Ok(reminders::restore_reminders(self.reminders, conflict)?)
+    }
+}
+
+pub fn export_to_file(path: impl AsRef<Path>, format: BackupFormat) -> BackupResult<Backup> {
+    debug!(target: "CORE_BACKUP_SERVICE", "Exporting database to: {}", path.as_ref().display());
+
+    let backup = Backup::create()?;
+    backup.write(format, File::create(path)?)?;
+
+    debug!(target: "CORE_BACKUP_SERVICE", "Exported {} reminders", backup.reminders.len());
+
+    Ok(backup)
+}
+
+pub fn import_from_file(
+    path: impl AsRef<Path>,
+    format: BackupFormat,
+    conflict: RestoreConflict,
+) -> BackupResult<RestoreSummary> {
+    debug!(target: "CORE_BACKUP_SERVICE", "Importing backup from: {}", path.as_ref().display());
+
+    let backup = Backup::read(format, BufReader::new(File::open(path)?))?;
+    let summary = backup.restore(conflict)?;
+
+    debug!(target: "CORE_BACKUP_SERVICE", "Backup imported: {summary:?}");
+
+    Ok(summary)
+}
+
+/// Backups in the directory, from the oldest to the newest
+pub fn list_backups(directory: impl AsRef<Path>) -> BackupResult<Vec<(SimpleDate, PathBuf)>> {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut backups = vec![];
+
+    for entry in entries {
+        let path = entry?.path();
+        let date = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.strip_prefix(BACKUP_FILE_PREFIX))
+            .filter(|_| BackupFormat::from_path(&path).is_ok())
+            .and_then(|date| SimpleDate::parse_from_str(date, BACKUP_FILE_DATE_FORMAT).ok());
+
+        if let Some(date) = date {
+            backups.push((date, path));
+        }
+    }
+
+    backups.sort();
+
+    Ok(backups)
+}
+
+/// Writes a backup of the database into the directory and removes all but the newest `keep` backups
+pub fn rotate_backups(
+    directory: impl AsRef<Path>,
+    format: BackupFormat,
+    keep: usize,
+) -> BackupResult<PathBuf> {
+    let directory = directory.as_ref();
+    fs::create_dir_all(directory)?;
+
+    let file_name = format!(
+        "{BACKUP_FILE_PREFIX}{}.{}",
+        SimpleDate::local().format(BACKUP_FILE_DATE_FORMAT),
+        format.extension()
+    );
+    let path = directory.join(file_name);
+
+    export_to_file(&path, format)?;
+
+    let backups = list_backups(directory)?;
+    let outdated = backups.len().saturating_sub(std::cmp::max(1, keep));
+
+    for (_, backup) in backups.into_iter().take(outdated) {
+        debug!(target: "CORE_BACKUP_SERVICE", "Removing old backup: {}", backup.display());
+
+        fs::remove_file(backup)?;
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::{
+        clear_test_database, init_database, test_database_url, DatabaseKey, CORE_MIGRATIONS,
+    };
+    use ctor::ctor;
+    use hkb_date::recurrence::{Frequency, Recurrence};
+    use serial_test::serial;
+
+    use super::*;
+    use crate::database::services::encryption::unlock_database;
+    use crate::database::services::reminders::{
+        complete_reminder, create_reminder, fetch_reminders, ChecklistItemData, CreateReminderData,
+        ReminderPriority,
+    };
+
+    #[test]
+    #[ctor]
+    fn init() {
+        init_database(&test_database_url(), vec![CORE_MIGRATIONS]).unwrap();
+    }
+
+    fn create_reminders() -> Vec<ReminderData> {
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        create_reminder(CreateReminderData {
+            note: "Water the plants, \"all\" of them\nand the garden".to_owned(),
+            remind_at: date,
+            recurrence: Some(Recurrence::new(Frequency::Weekly).every(2)),
+            tags: vec!["home".to_owned(), "garden, back".to_owned()],
+            priority: ReminderPriority::High,
+            alerts: AlertOffset::defaults(),
+            checklist: vec![
+                ChecklistItemData::new("tomatoes"),
+                ChecklistItemData::new("roses, \"red\" ones"),
+            ],
+        })
+        .unwrap();
+        let completed = create_reminder(CreateReminderData {
+            note: "Call mom".to_owned(),
+            remind_at: date,
+            recurrence: None,
+            tags: vec![],
+            priority: ReminderPriority::Normal,
+            alerts: vec![],
+            checklist: vec![],
+        })
+        .unwrap();
+
+        complete_reminder(completed.id).unwrap();
+
+        fetch_reminders(None).unwrap()
+    }
+
+    fn round_trip(format: BackupFormat) {
+        clear_test_database();
+
+        let reminders = create_reminders();
+        let mut output = vec![];
+        Backup::create()
+            .unwrap()
+            .write(format, &mut output)
+            .unwrap();
+
+        clear_test_database();
+
+        let backup = Backup::read(format, output.as_slice()).unwrap();
+        let summary = backup.restore(RestoreConflict::Skip).unwrap();
+
+        assert_eq!(reminders.len(), summary.created);
+        assert_eq!(reminders, fetch_reminders(None).unwrap());
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_restore_a_json_backup() {
+        round_trip(BackupFormat::Json);
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_restore_a_csv_backup() {
+        round_trip(BackupFormat::Csv);
+    }
+
+    #[test]
+    #[serial]
+    fn it_handles_conflicts_when_restoring() {
+        clear_test_database();
+
+        let reminders = create_reminders();
+        let backup = Backup::create().unwrap();
+        let mut changed_backup = backup.clone();
+        changed_backup.reminders[0].note = "Changed".to_owned();
+
+        let summary = changed_backup
+            .clone()
+            .restore(RestoreConflict::Skip)
+            .unwrap();
+
+        assert_eq!(reminders.len(), summary.skipped);
+        assert_eq!(reminders, fetch_reminders(None).unwrap());
+
+        let summary = changed_backup.restore(RestoreConflict::Overwrite).unwrap();
+
+        assert_eq!(reminders.len(), summary.overwritten);
+        assert_eq!("Changed", fetch_reminders(None).unwrap()[0].note);
+
+        let summary = backup.restore(RestoreConflict::Duplicate).unwrap();
+
+        assert_eq!(reminders.len(), summary.created);
+        assert_eq!(reminders.len() * 2, fetch_reminders(None).unwrap().len());
+    }
+
+    #[test]
+    #[serial]
+    fn it_seals_backups_of_an_encrypted_database() {
+        clear_test_database();
+
+        let reminders = create_reminders();
+        let key = DatabaseKey::from_passphrase("correct horse").with_kdf_iterations(1_000);
+        unlock_database(Some(&key)).unwrap();
+
+        for format in [BackupFormat::Json, BackupFormat::Csv] {
+            let mut output = vec![];
+            Backup::create()
+                .unwrap()
+                .write(format, &mut output)
+                .unwrap();
+            let content = String::from_utf8(output).unwrap();
+
+            assert!(encryption::is_sealed(&content));
+            assert!(!content.contains("Water the plants"));
+            assert_eq!(
+                reminders,
+                Backup::read(format, content.as_bytes()).unwrap().reminders
+            );
+        }
+
+        let mut output = vec![];
+        Backup::create()
+            .unwrap()
+            .write(BackupFormat::Json, &mut output)
+            .unwrap();
+        crate::database::set_global_cipher(None);
+
+        assert!(matches!(
+            Backup::read(BackupFormat::Json, output.as_slice()),
+            Err(BackupError::FailedToAccessDatabase(
+                DatabaseError::MissingDatabaseKey
+            ))
+        ));
+
+        clear_test_database();
+    }
+
+    #[test]
+    #[serial]
+    fn it_rejects_newer_backups() {
+        let json = format!(
+            "{{\"version\": {}, \"created_at\": null}}",
+            BACKUP_VERSION + 1
+        );
+        let csv = format!("{CSV_VERSION_PREFIX}{}\n", BACKUP_VERSION + 1);
+
+        assert!(matches!(
+            Backup::read(BackupFormat::Json, json.as_bytes()),
+            Err(BackupError::UnsupportedVersion(_))
+        ));
+        assert!(matches!(
+            Backup::read(BackupFormat::Csv, csv.as_bytes()),
+            Err(BackupError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn it_keeps_only_the_newest_backups() {
+        clear_test_database();
+
+        let directory = std::env::temp_dir().join(format!("hkb-backups-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+
+        for date in ["20240401T080000", "20240402T080000", "20240403T080000"] {
+            File::create(directory.join(format!("{BACKUP_FILE_PREFIX}{date}.json"))).unwrap();
+        }
+
+        // files that are not backups are left alone
+        File::create(directory.join("notes.json")).unwrap();
+
+        let path = rotate_backups(&directory, BackupFormat::Csv, 2).unwrap();
+        let backups: Vec<PathBuf> = list_backups(&directory)
+            .unwrap()
+            .into_iter()
+            .map(|(_, path)| path)
+            .collect();
+
+        assert_eq!(
+            vec![
+                directory.join(format!("{BACKUP_FILE_PREFIX}20240403T080000.json")),
+                path
+            ],
+            backups
+        );
+        assert!(directory.join("notes.json").exists());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
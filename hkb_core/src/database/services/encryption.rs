@@ -0,0 +1,489 @@
+use diesel::connection::SimpleConnection;
+use diesel::{
+    Connection, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl, SelectableHelper,
+};
+use log::debug;
+use std::sync::Arc;
+
+use crate::database::{
+    self,
+    encryption::{self, DatabaseCipher, DatabaseKey},
+    models::encryption::DatabaseKeyCheck,
+    schema::database_keys::{self, dsl as database_keys_dsl},
+    schema::reminder_changes::dsl as reminder_changes_dsl,
+    schema::reminder_checklist_items::dsl as checklist_items_dsl,
+    schema::reminders::dsl as reminders_dsl,
+    DatabaseConnection, DatabaseError, DatabaseKind, DatabaseResult,
+};
+
+const DATABASE_KEY_ID: i64 = 1;
+
+fn find_key_check(conn: &mut DatabaseConnection) -> DatabaseResult<Option<DatabaseKeyCheck>> {
+    Ok(database_keys_dsl::database_keys
+        .find(DATABASE_KEY_ID)
+        .select(DatabaseKeyCheck::as_select())
+        .first(conn)
+        .optional()?)
+}
+
+/// Stores a new salt for the key, the cipher it derives is returned
+fn store_key(conn: &mut DatabaseConnection, key: &DatabaseKey) -> DatabaseResult<DatabaseCipher> {
+    let salt = encryption::new_salt();
+    let iterations = key.kdf_iterations();
+    let cipher = DatabaseCipher::derive(key, &salt, iterations)?;
+
+    diesel::insert_into(database_keys::table)
+        .values(&DatabaseKeyCheck {
+            id: DATABASE_KEY_ID,
+            salt,
+            iterations: iterations as i32,
+            key_check: cipher.key_check(),
+        })
+        .execute(conn)?;
+
+    Ok(cipher)
+}
+
+fn reseal(
+    value: String,
+    from: Option<&DatabaseCipher>,
+    to: Option<&DatabaseCipher>,
+) -> DatabaseResult<String> {
+    let text = match from {
+        Some(cipher) => cipher.open(&value)?,
+        None => value,
+    };
+
+    Ok(match to {
+        Some(cipher) => cipher.seal(&text),
+        None => text,
+    })
+}
+
+fn reseal_optional(
+    value: Option<String>,
+    from: Option<&DatabaseCipher>,
+    to: Option<&DatabaseCipher>,
+) -> DatabaseResult<Option<String>> {
+    value.map(|value| reseal(value, from, to)).transpose()
+}
+
+/// Writes every encrypted value again, with the cipher of the new key or in plain text.
+/// Covers the notes, the checklists and the snapshots of the journal,
+/// the old values are only gone from the disk once the database is compacted.
+fn reseal_database(
+    conn: &mut DatabaseConnection,
+    from: Option<&DatabaseCipher>,
+    to: Option<&DatabaseCipher>,
+) -> DatabaseResult<()> {
+    let notes: Vec<(i64, String)> = reminders_dsl::reminders
+        .select((reminders_dsl::id, reminders_dsl::note))
+        .load(conn)?;
+
+    for (id, note) in notes {
+        diesel::update(reminders_dsl::reminders.find(id))
+            .set(reminders_dsl::note.eq(reseal(note, from, to)?))
+            .execute(conn)?;
+    }
+
+    let titles: Vec<(i64, String)> = checklist_items_dsl::reminder_checklist_items
+        .select((checklist_items_dsl::id, checklist_items_dsl::title))
+        .load(conn)?;
+
+    for (id, title) in titles {
+        diesel::update(checklist_items_dsl::reminder_checklist_items.find(id))
+            .set(checklist_items_dsl::title.eq(reseal(title, from, to)?))
+            .execute(conn)?;
+    }
+
+    let snapshots: Vec<(i64, Option<String>, Option<String>)> =
+        reminder_changes_dsl::reminder_changes
+            .select((
+                reminder_changes_dsl::id,
+                reminder_changes_dsl::snapshot_before,
+                reminder_changes_dsl::snapshot_after,
+            ))
+            .load(conn)?;
+
+    for (id, before, after) in snapshots {
+        diesel::update(reminder_changes_dsl::reminder_changes.find(id))
+            .set((
+                reminder_changes_dsl::snapshot_before.eq(reseal_optional(before, from, to)?),
+                reminder_changes_dsl::snapshot_after.eq(reseal_optional(after, from, to)?),
+            ))
+            .execute(conn)?;
+    }
+
+    // the index of the notes keeps the words of the old ones until it is rebuilt
+    if conn.kind() == DatabaseKind::Sqlite {
+        conn.batch_execute("INSERT INTO reminders_search (reminders_search) VALUES ('rebuild');")?;
+    }
+
+    Ok(())
+}
+
+/// Rewrites the database without the pages and the log entries the old values are left in,
+/// has to run outside of a transaction
+fn compact_database(conn: &mut DatabaseConnection) -> DatabaseResult<()> {
+    debug!(target: "CORE_ENCRYPTION_SERVICE", "Compacting the database");
+
+    match conn.kind() {
+        // the log is emptied again after the vacuum, which writes the whole database to it
+        DatabaseKind::Sqlite => conn.batch_execute(
+            "PRAGMA wal_checkpoint(TRUNCATE); VACUUM; PRAGMA wal_checkpoint(TRUNCATE);",
+        )?,
+        DatabaseKind::Postgres => conn
+            .batch_execute("VACUUM FULL reminders, reminder_checklist_items, reminder_changes;")?,
+        DatabaseKind::Mysql => conn.batch_execute(
+            "OPTIMIZE TABLE reminders, reminder_checklist_items, reminder_changes;",
+        )?,
+    }
+
+    Ok(())
+}
+
+/// The cipher of the key, and whether the database was encrypted with it just now
+fn unlock_database_in(
+    conn: &mut DatabaseConnection,
+    key: Option<&DatabaseKey>,
+) -> DatabaseResult<(Option<DatabaseCipher>, bool)> {
+    match (find_key_check(conn)?, key) {
+        (None, None) => Ok((None, false)),
+        (Some(_), None) => Err(DatabaseError::MissingDatabaseKey),
+        (Some(key_check), Some(key)) => {
+            let cipher =
+                DatabaseCipher::derive(key, &key_check.salt, key_check.iterations.max(1) as u32)?;
+
+            if cipher.key_check() != key_check.key_check {
+                return Err(DatabaseError::WrongDatabaseKey);
+            }
+
+            Ok((Some(cipher), false))
+        }
+        (None, Some(key)) => {
+            debug!(target: "CORE_ENCRYPTION_SERVICE", "Encrypting the database for the first time");
+
+            let cipher = store_key(conn, key)?;
+            reseal_database(conn, None, Some(&cipher))?;
+
+            Ok((Some(cipher), true))
+        }
+    }
+}
+
+/// Opens the database with the key, see [`database::database_key`].
+/// A database without a key is encrypted with it the first time,
+/// an encrypted one fails with `WrongDatabaseKey` for any other key
+/// and with `MissingDatabaseKey` without one.
+pub fn unlock_database(key: Option<&DatabaseKey>) -> DatabaseResult<()> {
+    let cipher = database::within_database(|conn| {
+        debug!(target: "CORE_ENCRYPTION_SERVICE", "Unlocking the database");
+
+        let (cipher, resealed) = conn.transaction(|conn| unlock_database_in(conn, key))?;
+
+        if resealed {
+            compact_database(conn)?;
+        }
+
+        Ok(cipher)
+    })?;
+
+    debug!(target: "CORE_ENCRYPTION_SERVICE", "Database unlocked, encrypted: {}", cipher.is_some());
+
+    database::set_global_cipher(cipher.map(Arc::new));
+
+    Ok(())
+}
+
+/// Whether the database was unlocked with a key
+pub fn is_database_encrypted() -> DatabaseResult<bool> {
+    database::within_database(|_| Ok(encryption::is_encrypted()))
+}
+
+fn rotate_database_key_in(
+    conn: &mut DatabaseConnection,
+    new_key: Option<&DatabaseKey>,
+) -> DatabaseResult<Option<DatabaseCipher>> {
+    let cipher = encryption::current_cipher();
+
+    if find_key_check(conn)?.is_some() && cipher.is_none() {
+        return Err(DatabaseError::MissingDatabaseKey);
+    }
+
+    diesel::delete(database_keys_dsl::database_keys).execute(conn)?;
+
+    let new_cipher = new_key.map(|key| store_key(conn, key)).transpose()?;
+    reseal_database(conn, cipher.as_deref(), new_cipher.as_ref())?;
+
+    Ok(new_cipher)
+}
+
+/// Encrypts everything again with a new key, the database has to be unlocked first.
+/// Without a new key the database is written in plain text again.
+pub fn rotate_database_key(new_key: Option<&DatabaseKey>) -> DatabaseResult<()> {
+    let cipher = database::within_database(|conn| {
+        debug!(target: "CORE_ENCRYPTION_SERVICE", "Rotating the key of the database");
+
+        let cipher = conn.transaction(|conn| rotate_database_key_in(conn, new_key))?;
+        compact_database(conn)?;
+
+        Ok(cipher)
+    })?;
+
+    debug!(target: "CORE_ENCRYPTION_SERVICE", "Key rotated, encrypted: {}", cipher.is_some());
+
+    database::set_global_cipher(cipher.map(Arc::new));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::{
+        clear_test_database, init_database, test_database_url, within_database, CORE_MIGRATIONS,
+    };
+    use ctor::ctor;
+    use hkb_date::date::SimpleDate;
+    use serial_test::serial;
+
+    use super::*;
+    use crate::database::services::reminders::{
+        count_reminders, create_reminder, fetch_reminder, fetch_reminder_changes, search_reminders,
+        ChecklistItemData, CreateReminderData, ReminderPriority, ReminderQuery,
+        ReminderQueryOptions,
+    };
+
+    #[test]
+    #[ctor]
+    fn init() {
+        init_database(&test_database_url(), vec![CORE_MIGRATIONS]).unwrap();
+    }
+
+    /// Deriving with the default iterations is tested with the cipher, these tests unlock a lot
+    fn test_key(passphrase: &str) -> DatabaseKey {
+        DatabaseKey::from_passphrase(passphrase).with_kdf_iterations(1_000)
+    }
+
+    fn create_a_noted_reminder(note: &str) -> i64 {
+        create_reminder(CreateReminderData {
+            note: note.to_owned(),
+            remind_at: SimpleDate::local(),
+            recurrence: None,
+            tags: vec![],
+            priority: ReminderPriority::Normal,
+            alerts: vec![],
+            checklist: vec![ChecklistItemData::new("Bring the referral".to_owned())],
+        })
+        .unwrap()
+        .id
+    }
+
+    /// What the database holds, without decrypting anything
+    fn stored_values(id: i64) -> Vec<String> {
+        within_database(|conn| {
+            let note: String = reminders_dsl::reminders
+                .find(id)
+                .select(reminders_dsl::note)
+                .first(conn)?;
+            let titles: Vec<String> = checklist_items_dsl::reminder_checklist_items
+                .filter(checklist_items_dsl::reminder_id.eq(id))
+                .select(checklist_items_dsl::title)
+                .load(conn)?;
+            let snapshots: Vec<Option<String>> = reminder_changes_dsl::reminder_changes
+                .filter(reminder_changes_dsl::reminder_id.eq(id))
+                .select(reminder_changes_dsl::snapshot_after)
+                .load(conn)?;
+
+            Ok([
+                vec![note],
+                titles,
+                snapshots.into_iter().flatten().collect(),
+            ]
+            .concat())
+        })
+        .unwrap()
+    }
+
+    fn is_readable(values: &[String]) -> bool {
+        values
+            .iter()
+            .all(|value| value.contains("Dentist") || value.contains("referral"))
+    }
+
+    #[test]
+    #[serial]
+    fn it_encrypts_the_database_the_first_time_it_is_unlocked_with_a_key() {
+        clear_test_database();
+
+        let key = test_key("correct horse battery staple");
+        let before = create_a_noted_reminder("Dentist at 9");
+
+        unlock_database(Some(&key)).unwrap();
+
+        let after = create_a_noted_reminder("Dentist at 10");
+
+        assert!(!is_readable(&stored_values(before)));
+        assert!(!is_readable(&stored_values(after)));
+        assert_eq!("Dentist at 9", fetch_reminder(before).unwrap().note);
+        assert_eq!(
+            "Bring the referral",
+            fetch_reminder(after).unwrap().checklist[0].title
+        );
+        assert_eq!(
+            "Dentist at 10",
+            fetch_reminder_changes(after).unwrap()[0]
+                .after
+                .as_ref()
+                .unwrap()
+                .note
+        );
+
+        rotate_database_key(None).unwrap();
+
+        assert!(is_readable(&stored_values(before)));
+        assert!(is_readable(&stored_values(after)));
+    }
+
+    #[test]
+    #[serial]
+    fn it_refuses_to_open_an_encrypted_database_without_the_key() {
+        clear_test_database();
+
+        let key = test_key("correct horse battery staple");
+        let id = create_a_noted_reminder("Dentist at 9");
+
+        unlock_database(Some(&key)).unwrap();
+        // the next run of the app
+        database::set_global_cipher(None);
+
+        assert!(matches!(
+            fetch_reminder(id),
+            Err(DatabaseError::MissingDatabaseKey)
+        ));
+        assert!(matches!(
+            unlock_database(None),
+            Err(DatabaseError::MissingDatabaseKey)
+        ));
+        assert!(matches!(
+            unlock_database(Some(&test_key("wrong horse"))),
+            Err(DatabaseError::WrongDatabaseKey)
+        ));
+        assert!(matches!(
+            rotate_database_key(None),
+            Err(DatabaseError::MissingDatabaseKey)
+        ));
+
+        unlock_database(Some(&key)).unwrap();
+
+        assert_eq!("Dentist at 9", fetch_reminder(id).unwrap().note);
+
+        rotate_database_key(None).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn it_rotates_the_key_of_the_database() {
+        clear_test_database();
+
+        let old_key = test_key("correct horse battery staple");
+        let new_key = test_key("tr0ub4dor&3");
+        let id = create_a_noted_reminder("Dentist at 9");
+
+        unlock_database(Some(&old_key)).unwrap();
+        let old_values = stored_values(id);
+        rotate_database_key(Some(&new_key)).unwrap();
+        database::set_global_cipher(None);
+
+        assert!(matches!(
+            unlock_database(Some(&old_key)),
+            Err(DatabaseError::WrongDatabaseKey)
+        ));
+
+        unlock_database(Some(&new_key)).unwrap();
+
+        assert!(!is_readable(&stored_values(id)));
+        assert_ne!(old_values, stored_values(id));
+        assert_eq!("Dentist at 9", fetch_reminder(id).unwrap().note);
+
+        rotate_database_key(None).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn it_searches_encrypted_notes() {
+        clear_test_database();
+
+        unlock_database(Some(&test_key("correct horse"))).unwrap();
+
+        let dentist = create_a_noted_reminder("Dentist at 9");
+        create_a_noted_reminder("Call mom");
+        let query = ReminderQuery::from(vec![ReminderQueryOptions::NoteContains {
+            text: "dentist".to_owned(),
+        }]);
+
+        assert_eq!(
+            vec![dentist],
+            search_reminders("dent", &ReminderQuery::new())
+                .unwrap()
+                .into_iter()
+                .map(|reminder| reminder.id)
+                .collect::<Vec<i64>>()
+        );
+        assert_eq!(1, count_reminders(&query).unwrap());
+        assert_eq!(2, count_reminders(&ReminderQuery::new()).unwrap());
+
+        rotate_database_key(None).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn it_leaves_no_plain_text_in_the_database_file() {
+        // the other tests may run on a server
+        if DatabaseKind::from_url(&test_database_url()) != DatabaseKind::Sqlite {
+            return;
+        }
+
+        let directory = std::env::temp_dir().join(format!("hkb-encryption-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        init_database(
+            directory.join("db").to_str().unwrap(),
+            vec![CORE_MIGRATIONS],
+        )
+        .unwrap();
+
+        create_a_noted_reminder("Dentist at 9");
+        unlock_database(Some(&test_key("correct horse battery staple"))).unwrap();
+
+        // the search index keeps its words in lower case
+        let contents: Vec<u8> = ["db", "db-wal"]
+            .iter()
+            .filter_map(|file| std::fs::read(directory.join(file)).ok())
+            .flatten()
+            .map(|byte| byte.to_ascii_lowercase())
+            .collect();
+        let contains = |text: &[u8]| contents.windows(text.len()).any(|window| window == text);
+
+        assert!(!contains(b"dentist"));
+        assert!(!contains(b"referral"));
+
+        init_database(&test_database_url(), vec![CORE_MIGRATIONS]).unwrap();
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn it_reads_a_key_from_a_file() {
+        let path = std::env::temp_dir().join(format!("hkb-key-{}", std::process::id()));
+        std::fs::write(&path, [7u8; 32]).unwrap();
+
+        assert_eq!(
+            DatabaseKey::from_passphrase("\u{7}".repeat(32).as_str()),
+            DatabaseKey::from_file(&path).unwrap()
+        );
+        assert!(matches!(
+            DatabaseKey::from_file(path.with_extension("missing")),
+            Err(DatabaseError::FailedToReadKeyFile(_))
+        ));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1 +1,4 @@
+pub mod backup;
+pub mod encryption;
 pub mod reminders;
+pub mod sync;
@@ -1,127 +1,518 @@
 pub use crate::dtos::reminders::*;
-use diesel::{
-    sql_types::Date as SqlDateType, ExpressionMethods, IntoSql, QueryDsl, RunQueryDsl,
-    SelectableHelper,
-};
+use chrono::{NaiveDateTime, SubsecRound};
+use diesel::result::Error as DieselResultError;
+use diesel::{Connection, ExpressionMethods, QueryDsl, QueryResult, RunQueryDsl, SelectableHelper};
 use hkb_date::date::SimpleDate;
-use log::debug;
+use hkb_date::duration::Duration;
+use hkb_date::recurrence::Recurrence;
+use log::{debug, error};
+use std::collections::HashMap;
+
+mod async_repository;
+mod checklist;
+mod journal;
+mod query;
+mod repository;
+mod sync;
+
+pub use async_repository::*;
+pub use checklist::*;
+pub use journal::*;
+pub use query::*;
+pub use repository::*;
+pub use sync::*;
+
+use checklist::{
+    apply_checklist_edit, edit_checklist_in, load_checklists, normalize_checklist, set_checklist,
+    unchecked,
+};
+use journal::record_change;
+use sync::{available_uuid, new_uuid};
+
+// Alerts are still notified this long after the reminder is due,
+// e.g. when the daemon was not running at the time
+const DUE_ALERT_GRACE_PERIOD: Duration = Duration::Hour(1);
 
 use crate::database::{
-    self,
-    models::reminders::{CreateReminder, Reminder, UpdateReminder},
+    self, encryption,
+    models::alerts::{CreateReminderAlert, ReminderAlert},
+    models::reminders::{CreateReminder, Reminder, RestoreReminder, UpdateReminder},
+    models::tags::{CreateReminderTag, CreateTag},
+    schema::reminder_alerts::{self, dsl as reminder_alerts_dsl},
+    schema::reminder_tags::{self, dsl as reminder_tags_dsl},
     schema::reminders::{self, dsl as reminders_dsl},
-    DatabaseResult,
+    schema::tags::{self, dsl as tags_dsl},
+    DatabaseConnection, DatabaseError, DatabaseResult,
 };
 
-impl From<Reminder> for ReminderData {
-    fn from(val: Reminder) -> Self {
-        ReminderData {
-            id: val.id,
-            note: val.note,
-            remind_at: SimpleDate::parse_from_rfc3339(val.remind_at).unwrap(),
-            created_at: SimpleDate::parse_from_rfc3339(val.created_at).unwrap(),
+/// IANA name of the system timezone, kept next to the UTC dates of a reminder
+fn current_timezone() -> Option<String> {
+    match iana_time_zone::get_timezone() {
+        Ok(timezone) => Some(timezone),
+        Err(e) => {
+            error!(target: "CORE_REMINDERS_SERVICE", "Failed to get the system timezone: {e}");
+
+            None
         }
     }
 }
 
-impl From<ReminderData> for Reminder {
-    fn from(val: ReminderData) -> Self {
-        Reminder {
-            id: val.id,
-            note: val.note,
-            remind_at: val.remind_at.to_string(),
-            created_at: val.created_at.to_string(),
+// dates are stored to the second, the way they are shown and parsed
+fn to_instant(date: SimpleDate) -> DatabaseResult<NaiveDateTime> {
+    Ok(date.to_utc_instant()?.trunc_subsecs(0))
+}
+
+fn from_instant(instant: NaiveDateTime) -> DatabaseResult<SimpleDate> {
+    Ok(SimpleDate::from_utc_instant(instant)?)
+}
+
+/// The instant to compare stored dates with,
+/// dates too far out to convert are compared as they are
+fn instant_bound(date: &SimpleDate) -> NaiveDateTime {
+    date.to_utc_instant()
+        .unwrap_or_else(|_| date.to_chrono_date())
+}
+
+fn serialize_recurrence(recurrence: &Recurrence) -> String {
+    // serializing a plain data struct cannot fail
+    serde_json::to_string(recurrence).unwrap()
+}
+
+fn deserialize_recurrence(recurrence: &str) -> DatabaseResult<Recurrence> {
+    serde_json::from_str(recurrence).map_err(|e| {
+        error!(target: "CORE_REMINDERS_SERVICE", "Failed to deserialize recurrence {recurrence}: {e}");
+
+        DatabaseError::InvalidRecurrence(e)
+    })
+}
+
+impl From<ReminderPriority> for i32 {
+    fn from(val: ReminderPriority) -> Self {
+        match val {
+            ReminderPriority::Low => 0,
+            ReminderPriority::Normal => 1,
+            ReminderPriority::High => 2,
+            ReminderPriority::Critical => 3,
         }
     }
 }
 
-impl From<CreateReminderData> for CreateReminder {
-    fn from(val: CreateReminderData) -> Self {
-        CreateReminder {
-            note: val.note,
-            remind_at: val.remind_at.to_string(),
-            created_at: SimpleDate::local().to_string(),
+impl From<i32> for ReminderPriority {
+    fn from(val: i32) -> Self {
+        match val {
+            i32::MIN..=0 => ReminderPriority::Low,
+            1 => ReminderPriority::Normal,
+            2 => ReminderPriority::High,
+            _ => ReminderPriority::Critical,
         }
     }
 }
 
-impl From<UpdateReminderData> for UpdateReminder {
-    fn from(val: UpdateReminderData) -> Self {
+impl TryFrom<Reminder> for ReminderData {
+    type Error = DatabaseError;
+
+    fn try_from(val: Reminder) -> Result<Self, Self::Error> {
+        Ok(ReminderData {
+            id: val.id,
+            uuid: val.uuid,
+            note: encryption::decrypt(val.note)?,
+            remind_at: from_instant(val.remind_at)?,
+            created_at: from_instant(val.created_at)?,
+            recurrence: val
+                .recurrence
+                .as_deref()
+                .map(deserialize_recurrence)
+                .transpose()?,
+            occurrence: val.occurrence as u32,
+            original_remind_at: val.original_remind_at.map(from_instant).transpose()?,
+            snooze_count: val.snooze_count as u32,
+            completed_at: val.completed_at.map(from_instant).transpose()?,
+            dismissed_at: val.dismissed_at.map(from_instant).transpose()?,
+            // tags, alerts and checklists live in their own tables and are loaded separately
+            tags: vec![],
+            alerts: vec![],
+            priority: val.priority.into(),
+            deleted_at: val.deleted_at.map(from_instant).transpose()?,
+            checklist: vec![],
+            timezone: val.timezone,
+        })
+    }
+}
+
+impl TryFrom<ReminderData> for Reminder {
+    type Error = DatabaseError;
+
+    fn try_from(val: ReminderData) -> Result<Self, Self::Error> {
+        Ok(Reminder {
+            id: val.id,
+            uuid: val.uuid,
+            note: encryption::encrypt(val.note),
+            remind_at: to_instant(val.remind_at)?,
+            created_at: to_instant(val.created_at)?,
+            recurrence: val.recurrence.as_ref().map(serialize_recurrence),
+            occurrence: val.occurrence as i32,
+            original_remind_at: val.original_remind_at.map(to_instant).transpose()?,
+            snooze_count: val.snooze_count as i32,
+            completed_at: val.completed_at.map(to_instant).transpose()?,
+            dismissed_at: val.dismissed_at.map(to_instant).transpose()?,
+            priority: val.priority.into(),
+            timezone: val.timezone,
+            deleted_at: val.deleted_at.map(to_instant).transpose()?,
+        })
+    }
+}
+
+impl TryFrom<ReminderData> for RestoreReminder {
+    type Error = DatabaseError;
+
+    fn try_from(val: ReminderData) -> Result<Self, Self::Error> {
+        let reminder: Reminder = val.try_into()?;
+
+        Ok(RestoreReminder {
+            id: Some(reminder.id),
+            uuid: reminder.uuid,
+            note: reminder.note,
+            remind_at: reminder.remind_at,
+            created_at: reminder.created_at,
+            recurrence: reminder.recurrence,
+            occurrence: reminder.occurrence,
+            original_remind_at: reminder.original_remind_at,
+            snooze_count: reminder.snooze_count,
+            completed_at: reminder.completed_at,
+            dismissed_at: reminder.dismissed_at,
+            priority: reminder.priority,
+            timezone: reminder.timezone,
+            deleted_at: reminder.deleted_at,
+        })
+    }
+}
+
+/// Overwrites every column of an existing reminder, its creation date is kept
+impl From<RestoreReminder> for UpdateReminder {
+    fn from(val: RestoreReminder) -> Self {
         UpdateReminder {
-            note: val.note,
-            remind_at: val.remind_at.map(|date| date.to_string()),
+            note: Some(val.note),
+            remind_at: Some(val.remind_at),
+            recurrence: Some(val.recurrence),
+            occurrence: Some(val.occurrence),
+            original_remind_at: Some(val.original_remind_at),
+            snooze_count: Some(val.snooze_count),
+            completed_at: Some(val.completed_at),
+            dismissed_at: Some(val.dismissed_at),
+            priority: Some(val.priority),
+            timezone: Some(val.timezone),
+            deleted_at: Some(val.deleted_at),
         }
     }
 }
 
-#[derive(Debug)]
-pub enum ReminderQueryOptions<'a> {
-    RemindAtGe {
-        date: SimpleDate,
-    },
-    RemindAtLe {
-        date: SimpleDate,
-    },
-    RemindAtBetween {
-        end_date: SimpleDate,
-        start_date: SimpleDate,
-    },
-    WithIds {
-        ids: &'a Vec<i64>,
-    },
-    WithoutIds {
-        ids: &'a Vec<i64>,
-    },
+impl TryFrom<CreateReminderData> for CreateReminder {
+    type Error = DatabaseError;
+
+    fn try_from(val: CreateReminderData) -> Result<Self, Self::Error> {
+        Ok(CreateReminder {
+            uuid: new_uuid(),
+            note: encryption::encrypt(val.note),
+            remind_at: to_instant(val.remind_at)?,
+            created_at: to_instant(SimpleDate::local())?,
+            recurrence: val.recurrence.as_ref().map(serialize_recurrence),
+            occurrence: 1,
+            completed_at: None,
+            priority: val.priority.into(),
+            timezone: current_timezone(),
+        })
+    }
 }
 
-pub fn fetch_reminders(
-    options: Option<Vec<ReminderQueryOptions>>,
-) -> DatabaseResult<Vec<ReminderData>> {
-    database::within_database(|conn| {
-        debug!(target: "CORE_REMINDERS_SERVICE", "Fetching reminders with options: {options:?}");
+impl TryFrom<UpdateReminderData> for UpdateReminder {
+    type Error = DatabaseError;
+
+    fn try_from(val: UpdateReminderData) -> Result<Self, Self::Error> {
+        Ok(UpdateReminder {
+            note: val.note.map(encryption::encrypt),
+            remind_at: val.remind_at.map(to_instant).transpose()?,
+            recurrence: val
+                .recurrence
+                .map(|recurrence| recurrence.as_ref().map(serialize_recurrence)),
+            priority: val.priority.map(|priority| priority.into()),
+            ..Default::default()
+        })
+    }
+}
 
-        let mut query = reminders_dsl::reminders
-            .select(Reminder::as_select())
-            .order_by(reminders_dsl::id.asc())
-            .into_boxed();
-
-        if let Some(options) = options {
-            for option in options {
-                match option {
-                    ReminderQueryOptions::RemindAtBetween {
-                        end_date,
-                        start_date,
-                    } => {
-                        query = query.filter(reminders_dsl::remind_at.between(
-                            start_date.to_string().into_sql::<SqlDateType>(),
-                            end_date.to_string().into_sql::<SqlDateType>(),
-                        ));
-                    }
-                    ReminderQueryOptions::RemindAtGe { date } => {
-                        query = query.filter(
-                            reminders_dsl::remind_at.ge(date.to_string().into_sql::<SqlDateType>()),
-                        );
-                    }
-                    ReminderQueryOptions::RemindAtLe { date } => {
-                        query = query.filter(
-                            reminders_dsl::remind_at.le(date.to_string().into_sql::<SqlDateType>()),
-                        );
-                    }
-                    ReminderQueryOptions::WithIds { ids } => {
-                        query = query.filter(reminders_dsl::id.eq_any(ids));
-                    }
-                    ReminderQueryOptions::WithoutIds { ids } => {
-                        query = query.filter(diesel::dsl::not(reminders_dsl::id.eq_any(ids)));
-                    }
-                }
-            }
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized_tags: Vec<String> = Vec::with_capacity(tags.len());
+
+    for tag in tags.iter().map(|tag| tag.trim()) {
+        if !tag.is_empty() && !normalized_tags.iter().any(|t| t == tag) {
+            normalized_tags.push(tag.to_owned());
         }
+    }
+
+    normalized_tags
+}
+
+fn load_tags(conn: &mut DatabaseConnection, reminders: &mut [ReminderData]) -> QueryResult<()> {
+    let ids: Vec<i64> = reminders.iter().map(|reminder| reminder.id).collect();
+    let reminder_tags: Vec<(i64, String)> = reminder_tags_dsl::reminder_tags
+        .inner_join(tags_dsl::tags)
+        .filter(reminder_tags_dsl::reminder_id.eq_any(&ids))
+        .select((reminder_tags_dsl::reminder_id, tags_dsl::name))
+        .order_by(tags_dsl::name.asc())
+        .load(conn)?;
+    let mut tags_by_reminder: HashMap<i64, Vec<String>> = HashMap::with_capacity(ids.len());
+
+    for (reminder_id, tag) in reminder_tags {
+        tags_by_reminder.entry(reminder_id).or_default().push(tag);
+    }
+
+    for reminder in reminders.iter_mut() {
+        reminder.tags = tags_by_reminder.remove(&reminder.id).unwrap_or_default();
+    }
+
+    Ok(())
+}
+
+fn set_tags(conn: &mut DatabaseConnection, reminder_id: i64, tags: &[String]) -> QueryResult<()> {
+    let tags = normalize_tags(tags);
+
+    diesel::delete(
+        reminder_tags_dsl::reminder_tags.filter(reminder_tags_dsl::reminder_id.eq(reminder_id)),
+    )
+    .execute(conn)?;
+
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let existing_tags: Vec<String> = tags_dsl::tags
+        .filter(tags_dsl::name.eq_any(&tags))
+        .select(tags_dsl::name)
+        .load(conn)?;
+    let new_tags: Vec<CreateTag> = tags
+        .iter()
+        .filter(|tag| !existing_tags.contains(tag))
+        .map(|tag| CreateTag {
+            name: tag.to_owned(),
+        })
+        .collect();
+
+    // batch inserts are written differently by every backend, so they are left out
+    for new_tag in &new_tags {
+        diesel::insert_into(tags::table)
+            .values(new_tag)
+            .execute(conn)?;
+    }
+
+    let reminder_tags: Vec<CreateReminderTag> = tags_dsl::tags
+        .filter(tags_dsl::name.eq_any(&tags))
+        .select(tags_dsl::id)
+        .load::<i64>(conn)?
+        .into_iter()
+        .map(|tag_id| CreateReminderTag {
+            reminder_id,
+            tag_id,
+        })
+        .collect();
+
+    for reminder_tag in &reminder_tags {
+        diesel::insert_into(reminder_tags::table)
+            .values(reminder_tag)
+            .execute(conn)?;
+    }
+
+    Ok(())
+}
+
+fn load_alerts(conn: &mut DatabaseConnection, reminders: &mut [ReminderData]) -> QueryResult<()> {
+    let ids: Vec<i64> = reminders.iter().map(|reminder| reminder.id).collect();
+    let reminder_alerts: Vec<ReminderAlert> = reminder_alerts_dsl::reminder_alerts
+        .filter(reminder_alerts_dsl::reminder_id.eq_any(&ids))
+        .select(ReminderAlert::as_select())
+        .order_by(reminder_alerts_dsl::offset_minutes.desc())
+        .load(conn)?;
+    let mut alerts_by_reminder: HashMap<i64, Vec<AlertOffset>> = HashMap::with_capacity(ids.len());
+
+    for alert in reminder_alerts {
+        alerts_by_reminder
+            .entry(alert.reminder_id)
+            .or_default()
+            .push(AlertOffset::minutes_before(alert.offset_minutes as u32));
+    }
+
+    for reminder in reminders.iter_mut() {
+        reminder.alerts = alerts_by_reminder.remove(&reminder.id).unwrap_or_default();
+    }
+
+    Ok(())
+}
+
+fn load_relations(
+    conn: &mut DatabaseConnection,
+    reminders: &mut [ReminderData],
+) -> DatabaseResult<()> {
+    load_tags(conn, reminders)?;
+    load_alerts(conn, reminders)?;
+    load_checklists(conn, reminders)
+}
+
+/// Alerts that stay are left untouched, so they remember if they already fired
+fn set_alerts(
+    conn: &mut DatabaseConnection,
+    reminder_id: i64,
+    alerts: &[AlertOffset],
+) -> QueryResult<()> {
+    let mut offsets: Vec<i32> = alerts.iter().map(|alert| alert.minutes as i32).collect();
+    offsets.sort();
+    offsets.dedup();
+
+    diesel::delete(
+        reminder_alerts_dsl::reminder_alerts
+            .filter(reminder_alerts_dsl::reminder_id.eq(reminder_id))
+            .filter(diesel::dsl::not(
+                reminder_alerts_dsl::offset_minutes.eq_any(&offsets),
+            )),
+    )
+    .execute(conn)?;
+
+    let existing_offsets: Vec<i32> = reminder_alerts_dsl::reminder_alerts
+        .filter(reminder_alerts_dsl::reminder_id.eq(reminder_id))
+        .select(reminder_alerts_dsl::offset_minutes)
+        .load(conn)?;
+    let new_alerts: Vec<CreateReminderAlert> = offsets
+        .into_iter()
+        .filter(|offset| !existing_offsets.contains(offset))
+        .map(|offset_minutes| CreateReminderAlert {
+            reminder_id,
+            offset_minutes,
+        })
+        .collect();
+
+    for new_alert in &new_alerts {
+        diesel::insert_into(reminder_alerts::table)
+            .values(new_alert)
+            .execute(conn)?;
+    }
+
+    Ok(())
+}
+
+/// MySQL has no `RETURNING`, so the id is read back after the row was written
+fn insert_reminder(
+    conn: &mut DatabaseConnection,
+    create_reminder: &CreateReminder,
+) -> QueryResult<i64> {
+    diesel::insert_into(reminders::table)
+        .values(create_reminder)
+        .execute(conn)?;
+
+    conn.last_insert_id()
+}
+
+/// A reminder along with its tags, alerts and checklist
+fn load_reminder(conn: &mut DatabaseConnection, id: i64) -> DatabaseResult<ReminderData> {
+    let mut reminder: ReminderData = reminders_dsl::reminders
+        .find(id)
+        .select(Reminder::as_select())
+        .first::<Reminder>(conn)?
+        .try_into()?;
+
+    load_relations(conn, std::slice::from_mut(&mut reminder))?;
+
+    Ok(reminder)
+}
 
-        let reminders: Vec<ReminderData> = query
+/// Like [`load_reminder`], with `None` for a reminder that does not exist
+fn find_reminder(conn: &mut DatabaseConnection, id: i64) -> DatabaseResult<Option<ReminderData>> {
+    match load_reminder(conn, id) {
+        Ok(reminder) => Ok(Some(reminder)),
+        Err(DatabaseError::FailedToFetchResult(DieselResultError::NotFound)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// The reminders with these ids along with their tags, alerts and checklists, ordered by id
+fn load_reminders(conn: &mut DatabaseConnection, ids: &[i64]) -> DatabaseResult<Vec<ReminderData>> {
+    let mut reminders: Vec<ReminderData> = reminders_dsl::reminders
+        .filter(reminders_dsl::id.eq_any(ids))
+        .select(Reminder::as_select())
+        .order_by(reminders_dsl::id.asc())
+        .load(conn)?
+        .into_iter()
+        .map(ReminderData::try_from)
+        .collect::<DatabaseResult<_>>()?;
+
+    load_relations(conn, &mut reminders)?;
+
+    Ok(reminders)
+}
+
+/// The reminder as the last of the changes left it
+fn last_changed_reminder(changes: Vec<ReminderChangeData>) -> DatabaseResult<ReminderData> {
+    changes
+        .into_iter()
+        .last()
+        .and_then(|change| change.after)
+        .ok_or(DatabaseError::FailedToFetchResult(
+            DieselResultError::NotFound,
+        ))
+}
+
+/// Find the first occurrence of a recurring reminder after `date`.
+/// Returns the date along with its position in the series
+/// or `None` when the series ends before that.
+fn find_next_occurrence(reminder: &ReminderData, date: SimpleDate) -> Option<(SimpleDate, u32)> {
+    let recurrence = reminder.recurrence.as_ref()?;
+    // a snoozed occurrence should not shift the rest of the series
+    let mut remind_at = reminder.original_remind_at.unwrap_or(reminder.remind_at);
+    let mut occurrence = reminder.occurrence;
+
+    while remind_at <= date {
+        remind_at = recurrence.next_occurrence(remind_at, occurrence)?;
+        occurrence += 1;
+    }
+
+    Some((remind_at, occurrence))
+}
+
+fn query_reminders_in(
+    conn: &mut DatabaseConnection,
+    query: &ReminderQuery,
+) -> DatabaseResult<Vec<ReminderData>> {
+    // encrypted notes are only matched once they are decrypted, so the query runs in memory
+    let in_memory = encryption::is_encrypted() && query.reads_note();
+    let rows: Vec<Reminder> = if in_memory {
+        reminders_dsl::reminders
+            .select(Reminder::as_select())
+            .load(conn)?
+    } else {
+        query
+            .apply(
+                reminders_dsl::reminders
+                    .select(Reminder::as_select())
+                    .into_boxed(),
+            )
             .get_results(conn)?
-            .into_iter()
-            .map(|reminder| reminder.into())
-            .collect();
+    };
+    let mut reminders: Vec<ReminderData> = rows
+        .into_iter()
+        .map(ReminderData::try_from)
+        .collect::<DatabaseResult<_>>()?;
+
+    load_relations(conn, &mut reminders)?;
+
+    if in_memory {
+        return Ok(query.apply_to(reminders));
+    }
+
+    Ok(reminders)
+}
+
+pub fn query_reminders(query: &ReminderQuery) -> DatabaseResult<Vec<ReminderData>> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Fetching reminders with query: {query:?}");
+
+        let reminders = query_reminders_in(conn, query)?;
 
         debug!(target: "CORE_REMINDERS_SERVICE", "Reminders fetched: {}", reminders.len());
 
@@ -129,107 +520,508 @@ pub fn fetch_reminders(
     })
 }
 
+pub fn fetch_reminders(
+    options: Option<Vec<ReminderQueryOptions>>,
+) -> DatabaseResult<Vec<ReminderData>> {
+    query_reminders(&options.map(ReminderQuery::from).unwrap_or_default())
+}
+
+/// Search the notes of the reminders matching the query
+pub fn search_reminders(text: &str, query: &ReminderQuery) -> DatabaseResult<Vec<ReminderData>> {
+    query_reminders(&query.clone().filter(ReminderQueryOptions::Search {
+        text: text.to_owned(),
+    }))
+}
+
+/// Count the reminders matching the filter of the query, its paging is ignored
+pub fn count_reminders(query: &ReminderQuery) -> DatabaseResult<i64> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Counting reminders with query: {query:?}");
+
+        let count = if encryption::is_encrypted() && query.reads_note() {
+            let query = ReminderQuery {
+                limit: None,
+                offset: None,
+                ..query.clone()
+            };
+
+            query_reminders_in(conn, &query)?.len() as i64
+        } else {
+            reminders_dsl::reminders
+                .filter(query.filter_expression())
+                .count()
+                .get_result(conn)?
+        };
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Reminders counted: {count}");
+
+        Ok(count)
+    })
+}
+
 pub fn fetch_reminder(id: i64) -> DatabaseResult<ReminderData> {
     database::within_database(|conn| {
         debug!(target: "CORE_REMINDERS_SERVICE", "Fetching reminder with id {id}");
 
-        let reminder = reminders_dsl::reminders
-            .find(id)
-            .select(Reminder::as_select())
-            .first(conn)?;
+        let reminder = load_reminder(conn, id)?;
 
         debug!(target: "CORE_REMINDERS_SERVICE", "Found reminder {reminder:?}");
 
-        Ok(reminder.into())
+        Ok(reminder)
     })
 }
 
+fn create_reminder_in(
+    conn: &mut DatabaseConnection,
+    reminder: CreateReminderData,
+) -> DatabaseResult<Vec<ReminderChangeData>> {
+    let tags = reminder.tags.clone();
+    let alerts = reminder.alerts.clone();
+    let checklist = reminder.checklist.clone();
+    let create_reminder: CreateReminder = reminder.try_into()?;
+    let id = insert_reminder(conn, &create_reminder)?;
+
+    set_tags(conn, id, &tags)?;
+    set_alerts(conn, id, &alerts)?;
+    set_checklist(conn, id, &checklist)?;
+
+    let created_reminder = load_reminder(conn, id)?;
+
+    Ok(vec![record_change(
+        conn,
+        ReminderChangeKind::Create,
+        None,
+        Some(created_reminder),
+    )?])
+}
+
 pub fn create_reminder(reminder: CreateReminderData) -> DatabaseResult<ReminderData> {
     database::within_database(|conn| {
         debug!(target: "CORE_REMINDERS_SERVICE", "Creating reminder: {reminder:?}");
 
-        let create_reminder: CreateReminder = reminder.into();
-        let created_reminder = diesel::insert_into(reminders::table)
-            .values(&create_reminder)
-            .returning(Reminder::as_returning())
-            .get_result(conn)?;
+        let changes = conn.transaction(|conn| create_reminder_in(conn, reminder))?;
+        let created_reminder = last_changed_reminder(changes)?;
 
         debug!(target: "CORE_REMINDERS_SERVICE", "Reminder created. ID is: : {}", created_reminder.id);
 
-        Ok(created_reminder.into())
+        Ok(created_reminder)
     })
 }
 
+fn update_reminder_in(
+    conn: &mut DatabaseConnection,
+    reminder: UpdateReminderData,
+) -> DatabaseResult<Vec<ReminderChangeData>> {
+    let id = reminder.id;
+    let tags = reminder.tags.clone();
+    let alerts = reminder.alerts.clone();
+    let checklist = reminder.checklist.clone();
+    let update_reminder: UpdateReminder = reminder.try_into()?;
+    let reminder = load_reminder(conn, id)?;
+
+    // diesel refuses to run an update without any changes
+    if update_reminder.has_changes() {
+        diesel::update(reminders_dsl::reminders.find(id))
+            .set(&update_reminder)
+            .execute(conn)?;
+    }
+
+    if let Some(tags) = tags {
+        set_tags(conn, id, &tags)?;
+    }
+
+    if let Some(alerts) = alerts {
+        set_alerts(conn, id, &alerts)?;
+    }
+
+    if let Some(checklist) = checklist {
+        set_checklist(conn, id, &checklist)?;
+    }
+
+    let updated_reminder = load_reminder(conn, id)?;
+
+    Ok(vec![record_change(
+        conn,
+        ReminderChangeKind::Update,
+        Some(reminder),
+        Some(updated_reminder),
+    )?])
+}
+
 pub fn update_reminder(reminder: UpdateReminderData) -> DatabaseResult<ReminderData> {
     database::within_database(|conn| {
         debug!(target: "CORE_REMINDERS_SERVICE", "Updating reminder: {reminder:?}");
 
         let id = reminder.id;
-        let update_reminder: UpdateReminder = reminder.into();
-        let updated_reminder = diesel::update(reminders_dsl::reminders.find(id))
-            .set(&update_reminder)
-            .returning(Reminder::as_returning())
-            .get_result(conn)?;
+        let changes = conn.transaction(|conn| update_reminder_in(conn, reminder))?;
+        let updated_reminder = last_changed_reminder(changes)?;
 
         debug!(target: "CORE_REMINDERS_SERVICE", "Reminder {id} updated!");
 
-        Ok(updated_reminder.into())
+        Ok(updated_reminder)
     })
 }
 
-pub fn delete_reminders(option: ReminderQueryOptions) -> DatabaseResult<()> {
+fn snooze_reminder_in(
+    conn: &mut DatabaseConnection,
+    id: i64,
+    remind_at: SimpleDate,
+) -> DatabaseResult<Vec<ReminderChangeData>> {
+    let row = reminders_dsl::reminders
+        .find(id)
+        .select(Reminder::as_select())
+        .first::<Reminder>(conn)?;
+    let reminder = load_reminder(conn, id)?;
+    let update_reminder = UpdateReminder {
+        remind_at: Some(to_instant(remind_at)?),
+        original_remind_at: Some(Some(row.original_remind_at.unwrap_or(row.remind_at))),
+        snooze_count: Some(row.snooze_count + 1),
+        ..Default::default()
+    };
+
+    diesel::update(reminders_dsl::reminders.find(id))
+        .set(&update_reminder)
+        .execute(conn)?;
+
+    let snoozed_reminder = load_reminder(conn, id)?;
+
+    Ok(vec![record_change(
+        conn,
+        ReminderChangeKind::Update,
+        Some(reminder),
+        Some(snoozed_reminder),
+    )?])
+}
+
+/// Push a reminder back to `remind_at`.
+/// The first snooze keeps track of the date the reminder was originally set for.
+pub fn snooze_reminder(id: i64, remind_at: SimpleDate) -> DatabaseResult<ReminderData> {
     database::within_database(|conn| {
-        debug!(target: "CORE_REMINDERS_SERVICE", "Deleting reminders: {option:?}");
+        debug!(target: "CORE_REMINDERS_SERVICE", "Snoozing reminder {id} until: {remind_at}");
 
-        match option {
-            ReminderQueryOptions::RemindAtBetween {
-                end_date,
-                start_date,
-            } => {
-                diesel::delete(
-                    reminders_dsl::reminders.filter(reminders_dsl::remind_at.between(
-                        start_date.to_string().into_sql::<SqlDateType>(),
-                        end_date.to_string().into_sql::<SqlDateType>(),
-                    )),
-                )
-                .execute(conn)?;
+        let changes = conn.transaction(|conn| snooze_reminder_in(conn, id, remind_at))?;
+        let snoozed_reminder = last_changed_reminder(changes)?;
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Reminder {id} snoozed {} times!", snoozed_reminder.snooze_count);
+
+        Ok(snoozed_reminder)
+    })
+}
+
+/// Move every recurring reminder that is due at or before `date`
+/// to its next occurrence. Reminders whose series has ended stop recurring
+/// and are left to be cleaned up as regular reminders.
+pub fn schedule_next_occurrences(date: SimpleDate) -> DatabaseResult<Vec<ReminderData>> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Scheduling recurring reminders due before: {date}");
+
+        conn.transaction(|conn| {
+            let due_ids: Vec<i64> = reminders_dsl::reminders
+                .select(reminders_dsl::id)
+                .filter(reminders_dsl::recurrence.is_not_null())
+                .filter(reminders_dsl::completed_at.is_null())
+                .filter(reminders_dsl::dismissed_at.is_null())
+                .filter(reminders_dsl::deleted_at.is_null())
+                .filter(reminders_dsl::remind_at.le(instant_bound(&date)))
+                .load(conn)?;
+            let due_reminders = load_reminders(conn, &due_ids)?;
+            let mut scheduled_reminders = Vec::with_capacity(due_reminders.len());
+
+            for reminder in due_reminders {
+                let update_reminder = match find_next_occurrence(&reminder, date) {
+                    Some((remind_at, occurrence)) => UpdateReminder {
+                        remind_at: Some(to_instant(remind_at)?),
+                        occurrence: Some(occurrence as i32),
+                        original_remind_at: Some(None),
+                        snooze_count: Some(0),
+                        ..Default::default()
+                    },
+                    None => UpdateReminder {
+                        recurrence: Some(None),
+                        ..Default::default()
+                    },
+                };
+
+                diesel::update(reminders_dsl::reminders.find(reminder.id))
+                    .set(&update_reminder)
+                    .execute(conn)?;
+
+                let scheduled_reminder = load_reminder(conn, reminder.id)?;
+
+                debug!(target: "CORE_REMINDERS_SERVICE", "Reminder {} scheduled for: {}", reminder.id, scheduled_reminder.remind_at);
+
+                record_change(
+                    conn,
+                    ReminderChangeKind::Update,
+                    Some(reminder),
+                    Some(scheduled_reminder.clone()),
+                )?;
+                scheduled_reminders.push(scheduled_reminder);
             }
-            ReminderQueryOptions::RemindAtGe { date } => {
-                diesel::delete(reminders_dsl::reminders.filter(
-                    reminders_dsl::remind_at.ge(date.to_string().into_sql::<SqlDateType>()),
-                ))
-                .execute(conn)?;
+
+            Ok(scheduled_reminders)
+        })
+    })
+}
+
+fn complete_reminder_in(
+    conn: &mut DatabaseConnection,
+    id: i64,
+) -> DatabaseResult<Vec<ReminderChangeData>> {
+    let reminder = load_reminder(conn, id)?;
+    let completed_at = SimpleDate::local();
+    let occurrence_date = reminder.original_remind_at.unwrap_or(reminder.remind_at);
+    let next_occurrence =
+        find_next_occurrence(&reminder, std::cmp::max(completed_at, occurrence_date));
+
+    let Some((remind_at, occurrence)) = next_occurrence else {
+        let update_reminder = UpdateReminder {
+            completed_at: Some(Some(to_instant(completed_at)?)),
+            ..Default::default()
+        };
+
+        diesel::update(reminders_dsl::reminders.find(id))
+            .set(&update_reminder)
+            .execute(conn)?;
+
+        let completed_reminder = load_reminder(conn, id)?;
+
+        return Ok(vec![record_change(
+            conn,
+            ReminderChangeKind::Update,
+            Some(reminder),
+            Some(completed_reminder),
+        )?]);
+    };
+
+    let completed_occurrence = CreateReminder {
+        uuid: new_uuid(),
+        note: encryption::encrypt(reminder.note.clone()),
+        remind_at: to_instant(reminder.remind_at)?,
+        created_at: to_instant(reminder.created_at)?,
+        recurrence: None,
+        occurrence: reminder.occurrence as i32,
+        completed_at: Some(to_instant(completed_at)?),
+        priority: reminder.priority.into(),
+        timezone: reminder.timezone.clone(),
+    };
+    let update_reminder = UpdateReminder {
+        remind_at: Some(to_instant(remind_at)?),
+        occurrence: Some(occurrence as i32),
+        original_remind_at: Some(None),
+        snooze_count: Some(0),
+        ..Default::default()
+    };
+
+    diesel::update(reminders_dsl::reminders.find(id))
+        .set(&update_reminder)
+        .execute(conn)?;
+
+    let completed_id = insert_reminder(conn, &completed_occurrence)?;
+
+    set_tags(conn, completed_id, &reminder.tags)?;
+    set_checklist(conn, completed_id, &reminder.checklist)?;
+    set_checklist(conn, id, &unchecked(&reminder.checklist))?;
+
+    let scheduled_reminder = load_reminder(conn, id)?;
+    let completed_reminder = load_reminder(conn, completed_id)?;
+
+    Ok(vec![
+        record_change(
+            conn,
+            ReminderChangeKind::Update,
+            Some(reminder),
+            Some(scheduled_reminder),
+        )?,
+        record_change(
+            conn,
+            ReminderChangeKind::Create,
+            None,
+            Some(completed_reminder),
+        )?,
+    ])
+}
+
+/// Mark a reminder as done.
+/// For a recurring reminder the handled occurrence is kept as a completed copy
+/// and the series moves on to its next occurrence, with its checklist unchecked.
+pub fn complete_reminder(id: i64) -> DatabaseResult<ReminderData> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Completing reminder: {id}");
+
+        let changes = conn.transaction(|conn| complete_reminder_in(conn, id))?;
+        let completed_reminder = last_changed_reminder(changes)?;
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Reminder {id} completed!");
+
+        Ok(completed_reminder)
+    })
+}
+
+/// Dismiss every pending reminder that was due at or before `date`.
+/// Returns the amount of dismissed reminders.
+pub fn dismiss_overdue_reminders(date: SimpleDate) -> DatabaseResult<usize> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Dismissing reminders due before: {date}");
+
+        let dismissed = conn.transaction(|conn| {
+            let overdue_ids: Vec<i64> = reminders_dsl::reminders
+                .select(reminders_dsl::id)
+                .filter(reminders_dsl::completed_at.is_null())
+                .filter(reminders_dsl::dismissed_at.is_null())
+                .filter(reminders_dsl::deleted_at.is_null())
+                .filter(reminders_dsl::remind_at.le(instant_bound(&date)))
+                .load(conn)?;
+            let overdue_reminders = load_reminders(conn, &overdue_ids)?;
+            let dismissed = diesel::update(
+                reminders_dsl::reminders.filter(reminders_dsl::id.eq_any(&overdue_ids)),
+            )
+            .set(reminders_dsl::dismissed_at.eq(to_instant(SimpleDate::local())?))
+            .execute(conn)?;
+            let dismissed_reminders = load_reminders(conn, &overdue_ids)?;
+
+            for (reminder, dismissed_reminder) in
+                overdue_reminders.into_iter().zip(dismissed_reminders)
+            {
+                record_change(
+                    conn,
+                    ReminderChangeKind::Update,
+                    Some(reminder),
+                    Some(dismissed_reminder),
+                )?;
             }
-            ReminderQueryOptions::RemindAtLe { date } => {
-                diesel::delete(reminders_dsl::reminders.filter(
-                    reminders_dsl::remind_at.le(date.to_string().into_sql::<SqlDateType>()),
+
+            DatabaseResult::Ok(dismissed)
+        })?;
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Dismissed {dismissed} reminders.");
+
+        Ok(dismissed)
+    })
+}
+
+/// Get the alerts that are due at `date` and remember them as fired,
+/// so they are not returned again for the same `remind_at`.
+/// When several alerts of a reminder are due only the latest one is returned.
+pub fn take_due_alerts(date: SimpleDate) -> DatabaseResult<Vec<DueAlertData>> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Taking alerts due at: {date}");
+
+        let oldest_remind_at = date.sub_duration(DUE_ALERT_GRACE_PERIOD).unwrap();
+        let due_alerts = conn.transaction(|conn| {
+            let alerts: Vec<(ReminderAlert, Reminder)> = reminder_alerts_dsl::reminder_alerts
+                .inner_join(reminders_dsl::reminders)
+                .filter(reminders_dsl::completed_at.is_null())
+                .filter(reminders_dsl::dismissed_at.is_null())
+                .filter(reminders_dsl::deleted_at.is_null())
+                .filter(reminders_dsl::remind_at.ge(instant_bound(&oldest_remind_at)))
+                .select((ReminderAlert::as_select(), Reminder::as_select()))
+                .order_by((
+                    reminder_alerts_dsl::reminder_id.asc(),
+                    reminder_alerts_dsl::offset_minutes.asc(),
                 ))
-                .execute(conn)?;
-            }
-            ReminderQueryOptions::WithIds { ids } => {
-                diesel::delete(reminders_dsl::reminders.filter(reminders_dsl::id.eq_any(ids)))
+                .load(conn)?;
+            let mut due_alerts: Vec<(ReminderData, AlertOffset)> = vec![];
+
+            for (alert, reminder) in alerts {
+                let remind_at = reminder.remind_at;
+
+                if alert.fired_for == Some(remind_at) {
+                    continue;
+                }
+
+                let reminder: ReminderData = reminder.try_into()?;
+                let offset = AlertOffset::minutes_before(alert.offset_minutes as u32);
+
+                if offset.alert_at(reminder.remind_at) > date {
+                    continue;
+                }
+
+                diesel::update(reminder_alerts_dsl::reminder_alerts.find(alert.id))
+                    .set(reminder_alerts_dsl::fired_for.eq(remind_at))
                     .execute(conn)?;
+
+                // alerts are ordered by offset, so the latest due alert comes first
+                if due_alerts.last().map(|(due, _)| due.id) != Some(reminder.id) {
+                    due_alerts.push((reminder, offset));
+                }
             }
-            ReminderQueryOptions::WithoutIds { ids } => {
-                diesel::delete(
-                    reminders_dsl::reminders
-                        .filter(diesel::dsl::not(reminders_dsl::id.eq_any(ids))),
-                )
-                .execute(conn)?;
+
+            DatabaseResult::Ok(due_alerts)
+        })?;
+        let (mut reminders, offsets): (Vec<ReminderData>, Vec<AlertOffset>) =
+            due_alerts.into_iter().unzip();
+
+        load_relations(conn, &mut reminders)?;
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Found {} due alerts.", reminders.len());
+
+        Ok(reminders
+            .into_iter()
+            .zip(offsets)
+            .map(|(reminder, offset)| DueAlertData { reminder, offset })
+            .collect())
+    })
+}
+
+/// Move every reminder matching the filter to the trash.
+/// Returns the amount of deleted reminders.
+pub fn delete_reminders(filter: impl Into<ReminderFilter>) -> DatabaseResult<usize> {
+    let filter = filter.into();
+
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Deleting reminders: {filter:?}");
+
+        let deleted = conn.transaction(|conn| {
+            let ids: Vec<i64> = reminders_dsl::reminders
+                .filter(filter.to_expression())
+                .filter(reminders_dsl::deleted_at.is_null())
+                .select(reminders_dsl::id)
+                .load(conn)?;
+            let mut deleted = 0;
+
+            for id in ids {
+                deleted += delete_reminder_in(conn, id)?.len();
             }
-        };
 
-        debug!(target: "CORE_REMINDERS_SERVICE", "Deleted Reminders.");
+            DatabaseResult::Ok(deleted)
+        })?;
 
-        Ok(())
+        debug!(target: "CORE_REMINDERS_SERVICE", "Deleted {deleted} Reminders.");
+
+        Ok(deleted)
     })
 }
 
+/// Deleting a reminder that does not exist or is already in the trash changes nothing
+fn delete_reminder_in(
+    conn: &mut DatabaseConnection,
+    id: i64,
+) -> DatabaseResult<Vec<ReminderChangeData>> {
+    let Some(reminder) = find_reminder(conn, id)?.filter(|reminder| !reminder.is_deleted()) else {
+        return Ok(vec![]);
+    };
+
+    diesel::update(reminders_dsl::reminders.find(id))
+        .set(reminders_dsl::deleted_at.eq(to_instant(SimpleDate::local())?))
+        .execute(conn)?;
+
+    let deleted_reminder = load_reminder(conn, id)?;
+
+    Ok(vec![record_change(
+        conn,
+        ReminderChangeKind::Delete,
+        Some(reminder),
+        Some(deleted_reminder),
+    )?])
+}
+
+/// Move a reminder to the trash, it is kept until it is purged
 pub fn delete_reminder(id: i64) -> DatabaseResult<()> {
     database::within_database(|conn| {
         debug!(target: "CORE_REMINDERS_SERVICE", "Deleting reminder: {id}");
 
-        diesel::delete(reminders_dsl::reminders.find(id)).execute(conn)?;
+        conn.transaction(|conn| delete_reminder_in(conn, id))?;
 
         debug!(target: "CORE_REMINDERS_SERVICE", "Deleted Reminder: {id}");
 
@@ -237,58 +1029,370 @@ pub fn delete_reminder(id: i64) -> DatabaseResult<()> {
     })
 }
 
-#[cfg(test)]
-mod tests {
-    use self::database::{init_database, within_database};
-    use ctor::ctor;
-    use diesel::sql_query;
-    use diesel_migrations::{embed_migrations, EmbeddedMigrations};
-    use hkb_date::date::SimpleDate;
-    use hkb_date::duration::Duration;
-    use serial_test::serial;
-    pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("./migrations");
+/// Restoring a reminder that is not in the trash changes nothing
+fn restore_deleted_reminder_in(
+    conn: &mut DatabaseConnection,
+    id: i64,
+) -> DatabaseResult<Vec<ReminderChangeData>> {
+    let reminder = load_reminder(conn, id)?;
 
-    use super::*;
+    if !reminder.is_deleted() {
+        return Ok(vec![]);
+    }
 
-    macro_rules! create_a_reminder {
-        () => {{
-            let date =
-                SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
-            let reminder_data = CreateReminderData {
-                remind_at: date,
-                note: "Testing".to_owned(),
-            };
+    let update_reminder = UpdateReminder {
+        deleted_at: Some(None),
+        ..Default::default()
+    };
 
-            create_reminder(reminder_data).unwrap()
-        }};
+    diesel::update(reminders_dsl::reminders.find(id))
+        .set(&update_reminder)
+        .execute(conn)?;
 
-        ($date:expr) => {{
-            let reminder_data = CreateReminderData {
-                remind_at: $date,
-                note: "Testing".to_owned(),
-            };
+    let restored_reminder = load_reminder(conn, id)?;
 
-            create_reminder(reminder_data).unwrap()
-        }};
-    }
+    Ok(vec![record_change(
+        conn,
+        ReminderChangeKind::Update,
+        Some(reminder),
+        Some(restored_reminder),
+    )?])
+}
 
-    macro_rules! truncate_table {
-        () => {
-            within_database(|conn| {
-                sql_query("DELETE from reminders where 1=1")
-                    .execute(conn)
-                    .unwrap();
+/// Take a reminder back out of the trash
+pub fn restore_deleted_reminder(id: i64) -> DatabaseResult<ReminderData> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Restoring deleted reminder: {id}");
 
-                Ok(())
-            })
-            .unwrap();
-        };
+        let reminder = conn.transaction(|conn| {
+            restore_deleted_reminder_in(conn, id)?;
+
+            load_reminder(conn, id)
+        })?;
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Reminder {id} restored!");
+
+        Ok(reminder)
+    })
+}
+
+/// Purging a reminder that does not exist changes nothing
+fn purge_reminder_in(
+    conn: &mut DatabaseConnection,
+    id: i64,
+) -> DatabaseResult<Vec<ReminderChangeData>> {
+    let Some(reminder) = find_reminder(conn, id)? else {
+        return Ok(vec![]);
+    };
+
+    diesel::delete(reminders_dsl::reminders.find(id)).execute(conn)?;
+
+    Ok(vec![record_change(
+        conn,
+        ReminderChangeKind::Purge,
+        Some(reminder),
+        None,
+    )?])
+}
+
+/// Remove a reminder for good, whether it is in the trash or not
+pub fn purge_reminder(id: i64) -> DatabaseResult<()> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Purging reminder: {id}");
+
+        conn.transaction(|conn| purge_reminder_in(conn, id))?;
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Purged Reminder: {id}");
+
+        Ok(())
+    })
+}
+
+/// Remove every reminder that was moved to the trash at or before `date` for good.
+/// Returns the amount of purged reminders.
+pub fn purge_deleted_reminders(date: SimpleDate) -> DatabaseResult<usize> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Purging reminders deleted before: {date}");
+
+        let purged = conn.transaction(|conn| {
+            let ids: Vec<i64> = reminders_dsl::reminders
+                .filter(reminders_dsl::deleted_at.le(instant_bound(&date)))
+                .select(reminders_dsl::id)
+                .load(conn)?;
+            let mut purged = 0;
+
+            for id in ids {
+                purged += purge_reminder_in(conn, id)?.len();
+            }
+
+            DatabaseResult::Ok(purged)
+        })?;
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Purged {purged} reminders.");
+
+        Ok(purged)
+    })
+}
+
+/// Writes a reminder with all of its state, creating it again when it was deleted.
+/// This is how changes are taken back.
+fn put_reminder_in(
+    conn: &mut DatabaseConnection,
+    reminder: ReminderData,
+) -> DatabaseResult<Vec<ReminderChangeData>> {
+    let id = reminder.id;
+    let tags = reminder.tags.clone();
+    let alerts = reminder.alerts.clone();
+    let checklist = reminder.checklist.clone();
+    let mut restore_reminder: RestoreReminder = reminder.try_into()?;
+    let existing_reminder = find_reminder(conn, id)?;
+
+    if existing_reminder.is_some() {
+        diesel::update(reminders_dsl::reminders.find(id))
+            .set(&UpdateReminder::from(restore_reminder))
+            .execute(conn)?;
+    } else {
+        restore_reminder.uuid = available_uuid(conn, &restore_reminder.uuid)?;
+
+        diesel::insert_into(reminders::table)
+            .values(&restore_reminder)
+            .execute(conn)?;
+        conn.sync_id_sequence("reminders")?;
+    }
+
+    set_tags(conn, id, &tags)?;
+    set_alerts(conn, id, &alerts)?;
+    set_checklist(conn, id, &checklist)?;
+
+    let put_reminder = load_reminder(conn, id)?;
+    let kind = if existing_reminder.is_some() {
+        ReminderChangeKind::Update
+    } else {
+        ReminderChangeKind::Create
+    };
+
+    Ok(vec![record_change(
+        conn,
+        kind,
+        existing_reminder,
+        Some(put_reminder),
+    )?])
+}
+
+fn apply_operation(
+    conn: &mut DatabaseConnection,
+    operation: ReminderOperation,
+) -> DatabaseResult<Vec<ReminderChangeData>> {
+    match operation {
+        ReminderOperation::Create(reminder) => create_reminder_in(conn, reminder),
+        ReminderOperation::Update(reminder) => update_reminder_in(conn, reminder),
+        ReminderOperation::Delete(id) => delete_reminder_in(conn, id),
+        ReminderOperation::Snooze(id, remind_at) => snooze_reminder_in(conn, id, remind_at),
+        ReminderOperation::Complete(id) => complete_reminder_in(conn, id),
+        ReminderOperation::Restore(id) => restore_deleted_reminder_in(conn, id),
+        ReminderOperation::Purge(id) => purge_reminder_in(conn, id),
+        ReminderOperation::EditChecklist(id, edit) => edit_checklist_in(conn, id, edit),
+        ReminderOperation::Put(reminder) => put_reminder_in(conn, reminder),
+    }
+}
+
+/// Apply the operations in order, in one transaction.
+/// Returns the changes they made, if one of them fails none of them are kept.
+///
+/// The changes can be taken back with their inverse operations, from the last to the first:
+/// ```rust,no_run
+/// use hkb_core::database::services::reminders::{apply_reminder_batch, ReminderOperation};
+///
+/// let changes = apply_reminder_batch(vec![ReminderOperation::Delete(1)]).unwrap();
+///
+/// apply_reminder_batch(changes.iter().rev().map(|change| change.inverse()).collect()).unwrap();
+/// ```
+pub fn apply_reminder_batch(
+    operations: Vec<ReminderOperation>,
+) -> DatabaseResult<Vec<ReminderChangeData>> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Applying a batch of {} operations", operations.len());
+
+        let changes = conn.transaction(|conn| {
+            let mut changes = vec![];
+
+            for operation in operations {
+                changes.extend(apply_operation(conn, operation)?);
+            }
+
+            DatabaseResult::Ok(changes)
+        })?;
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Batch applied with {} changes", changes.len());
+
+        Ok(changes)
+    })
+}
+
+/// What to do with a restored reminder that has the id of an existing one
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreConflict {
+    #[default]
+    Skip,
+    Overwrite,
+    /// Keep both, the restored reminder gets a new id
+    Duplicate,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RestoreSummary {
+    pub created: usize,
+    pub overwritten: usize,
+    pub skipped: usize,
+}
+
+/// Writes reminders with all of their state, e.g. from a backup.
+/// Everything is restored in one transaction, so a failure leaves the database as it was.
+pub fn restore_reminders(
+    reminders: Vec<ReminderData>,
+    conflict: RestoreConflict,
+) -> DatabaseResult<RestoreSummary> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Restoring {} reminders, on conflict: {conflict:?}", reminders.len());
+
+        let summary = conn.transaction(|conn| {
+            let mut summary = RestoreSummary::default();
+
+            for reminder in reminders {
+                let existing_reminder = find_reminder(conn, reminder.id)?;
+                let id = match (&existing_reminder, conflict) {
+                    (None, _) => Some(reminder.id),
+                    (Some(_), RestoreConflict::Skip) => {
+                        summary.skipped += 1;
+
+                        continue;
+                    }
+                    (Some(_), RestoreConflict::Overwrite) => {
+                        diesel::delete(reminders_dsl::reminders.find(reminder.id)).execute(conn)?;
+
+                        Some(reminder.id)
+                    }
+                    (Some(_), RestoreConflict::Duplicate) => None,
+                };
+
+                let tags = reminder.tags.clone();
+                let alerts = reminder.alerts.clone();
+                let checklist = reminder.checklist.clone();
+                let mut restore_reminder: RestoreReminder = reminder.try_into()?;
+                restore_reminder.id = id;
+                restore_reminder.uuid = available_uuid(conn, &restore_reminder.uuid)?;
+
+                diesel::insert_into(reminders::table)
+                    .values(&restore_reminder)
+                    .execute(conn)?;
+
+                let restored_id = match id {
+                    Some(id) => id,
+                    None => conn.last_insert_id()?,
+                };
+
+                set_tags(conn, restored_id, &tags)?;
+                set_alerts(conn, restored_id, &alerts)?;
+                set_checklist(conn, restored_id, &checklist)?;
+
+                let restored_reminder = load_reminder(conn, restored_id)?;
+
+                if existing_reminder.is_some() && conflict == RestoreConflict::Overwrite {
+                    record_change(
+                        conn,
+                        ReminderChangeKind::Update,
+                        existing_reminder,
+                        Some(restored_reminder),
+                    )?;
+                    summary.overwritten += 1;
+                } else {
+                    record_change(
+                        conn,
+                        ReminderChangeKind::Create,
+                        None,
+                        Some(restored_reminder),
+                    )?;
+                    summary.created += 1;
+                }
+            }
+
+            conn.sync_id_sequence("reminders")?;
+
+            DatabaseResult::Ok(summary)
+        })?;
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Reminders restored: {summary:?}");
+
+        Ok(summary)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use self::database::{
+        clear_test_database, init_database, test_database_url, within_database, DatabaseKind,
+        CORE_MIGRATIONS,
+    };
+    use ctor::ctor;
+    use diesel::sql_query;
+    use hkb_date::date::SimpleDate;
+    use hkb_date::duration::Duration;
+    use hkb_date::recurrence::{Frequency, RecurrenceEnd};
+    use serial_test::serial;
+
+    use super::*;
+    use crate::database::schema::reminder_checklist_items::dsl as checklist_items_dsl;
+
+    macro_rules! create_a_reminder {
+        () => {{
+            let date =
+                SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+            let reminder_data = CreateReminderData {
+                remind_at: date,
+                note: "Testing".to_owned(),
+                recurrence: None,
+                tags: vec![],
+                priority: ReminderPriority::Normal,
+                alerts: vec![],
+                checklist: vec![],
+            };
+
+            create_reminder(reminder_data).unwrap()
+        }};
+
+        ($date:expr) => {{
+            let reminder_data = CreateReminderData {
+                remind_at: $date,
+                note: "Testing".to_owned(),
+                recurrence: None,
+                tags: vec![],
+                priority: ReminderPriority::Normal,
+                alerts: vec![],
+                checklist: vec![],
+            };
+
+            create_reminder(reminder_data).unwrap()
+        }};
+
+        ($date:expr, $recurrence:expr) => {{
+            let reminder_data = CreateReminderData {
+                remind_at: $date,
+                note: "Testing".to_owned(),
+                recurrence: Some($recurrence),
+                tags: vec![],
+                priority: ReminderPriority::Normal,
+                alerts: vec![],
+                checklist: vec![],
+            };
+
+            create_reminder(reminder_data).unwrap()
+        }};
     }
 
     #[test]
     #[ctor]
     fn init() {
-        init_database(":memory:", vec![MIGRATIONS]).unwrap();
+        init_database(&test_database_url(), vec![CORE_MIGRATIONS]).unwrap();
     }
 
     #[test]
@@ -308,11 +1412,13 @@ mod tests {
     #[test]
     #[serial]
     fn it_can_fetch_reminders() {
-        truncate_table!();
+        clear_test_database();
 
-        let reminders = [create_a_reminder!(),
+        let reminders = [
             create_a_reminder!(),
-            create_a_reminder!()];
+            create_a_reminder!(),
+            create_a_reminder!(),
+        ];
         let fetched_reminders = fetch_reminders(None).unwrap();
 
         assert_eq!(reminders.len(), fetched_reminders.len());
@@ -328,7 +1434,7 @@ mod tests {
     #[test]
     #[serial]
     fn it_can_fetch_reminders_in_between() {
-        truncate_table!();
+        clear_test_database();
 
         let d1 = SimpleDate::parse_from_str("2024-03-11 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
         let d2 = SimpleDate::parse_from_str("2024-03-12 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
@@ -352,7 +1458,10 @@ mod tests {
 
         assert_eq!(2, fetched_reminders.len());
 
-        assert_eq!(reminders.first().unwrap(), fetched_reminders.first().unwrap());
+        assert_eq!(
+            reminders.first().unwrap(),
+            fetched_reminders.first().unwrap()
+        );
         assert_eq!(reminders.get(1).unwrap(), fetched_reminders.get(1).unwrap());
 
         let start_date =
@@ -368,14 +1477,17 @@ mod tests {
 
         assert_eq!(2, fetched_reminders.len());
 
-        assert_eq!(reminders.get(2).unwrap(), fetched_reminders.first().unwrap());
+        assert_eq!(
+            reminders.get(2).unwrap(),
+            fetched_reminders.first().unwrap()
+        );
         assert_eq!(reminders.get(3).unwrap(), fetched_reminders.get(1).unwrap());
     }
 
     #[test]
     #[serial]
     fn it_can_fetch_reminders_by_filtering_out_some_ids() {
-        truncate_table!();
+        clear_test_database();
 
         let reminders = vec![
             create_a_reminder!(),
@@ -386,7 +1498,7 @@ mod tests {
         ];
         let ids_to_exclude = vec![reminders[2].id, reminders[4].id];
         let fetched_reminders = fetch_reminders(Some(vec![ReminderQueryOptions::WithoutIds {
-            ids: &ids_to_exclude,
+            ids: ids_to_exclude.clone(),
         }]))
         .unwrap();
 
@@ -409,6 +1521,11 @@ mod tests {
         let reminder_data = CreateReminderData {
             remind_at: date,
             note: "Testing".to_owned(),
+            recurrence: None,
+            tags: vec![],
+            priority: ReminderPriority::Normal,
+            alerts: vec![],
+            checklist: vec![],
         };
         let reminder = create_reminder(reminder_data).unwrap();
 
@@ -424,6 +1541,11 @@ mod tests {
             id: reminder.id,
             note: Some("Testing a new".to_owned()),
             remind_at: None,
+            recurrence: None,
+            tags: None,
+            priority: None,
+            alerts: None,
+            checklist: None,
         })
         .unwrap();
 
@@ -448,6 +1570,11 @@ mod tests {
             id: reminder.id,
             note: None,
             remind_at: Some(date),
+            recurrence: None,
+            tags: None,
+            priority: None,
+            alerts: None,
+            checklist: None,
         })
         .unwrap();
 
@@ -464,8 +1591,8 @@ mod tests {
 
         assert!(fetch_reminder(reminder.id).is_ok());
         assert!(delete_reminder(reminder.id).is_ok());
-        assert!(fetch_reminder(reminder.id).is_err());
-        assert!(fetch_reminder(reminder2.id).is_ok());
+        assert!(fetch_reminder(reminder.id).unwrap().is_deleted());
+        assert!(!fetch_reminder(reminder2.id).unwrap().is_deleted());
     }
 
     #[test]
@@ -476,12 +1603,1355 @@ mod tests {
         let reminder3 = create_a_reminder!();
 
         delete_reminders(ReminderQueryOptions::WithIds {
-            ids: &vec![reminder.id, reminder2.id],
+            ids: vec![reminder.id, reminder2.id],
         })
         .unwrap();
 
-        assert!(fetch_reminder(reminder.id).is_err());
-        assert!(fetch_reminder(reminder2.id).is_err());
-        assert!(fetch_reminder(reminder3.id).is_ok());
+        assert!(fetch_reminder(reminder.id).unwrap().is_deleted());
+        assert!(fetch_reminder(reminder2.id).unwrap().is_deleted());
+        assert!(!fetch_reminder(reminder3.id).unwrap().is_deleted());
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_create_a_recurring_reminder() {
+        let date = SimpleDate::local();
+        let recurrence = Recurrence::new(Frequency::Weekly).every(2);
+        let reminder = create_a_reminder!(date, recurrence.clone());
+        let fetched_reminder = fetch_reminder(reminder.id).unwrap();
+
+        assert_eq!(Some(recurrence), fetched_reminder.recurrence);
+        assert_eq!(1, fetched_reminder.occurrence);
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_schedule_next_occurrence_of_recurring_reminders() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let now = SimpleDate::parse_from_str("2024-04-07 09:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let recurring_reminder = create_a_reminder!(date, Recurrence::new(Frequency::Daily));
+        let reminder = create_a_reminder!(date);
+
+        let scheduled_reminders = schedule_next_occurrences(now).unwrap();
+
+        assert_eq!(1, scheduled_reminders.len());
+        assert_eq!(recurring_reminder.id, scheduled_reminders[0].id);
+        assert_eq!(
+            "2024-04-08T08:00:00Z",
+            scheduled_reminders[0].remind_at.to_string()
+        );
+        assert_eq!(4, scheduled_reminders[0].occurrence);
+        assert_eq!(
+            reminder.remind_at,
+            fetch_reminder(reminder.id).unwrap().remind_at
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn it_stops_recurring_when_series_has_ended() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let now = SimpleDate::parse_from_str("2024-04-07 09:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let recurrence = Recurrence::new(Frequency::Daily).ending(RecurrenceEnd::After(2));
+        let reminder = create_a_reminder!(date, recurrence);
+
+        let scheduled_reminders = schedule_next_occurrences(now).unwrap();
+        let fetched_reminder = fetch_reminder(reminder.id).unwrap();
+
+        assert_eq!(1, scheduled_reminders.len());
+        assert_eq!(None, fetched_reminder.recurrence);
+        assert_eq!(reminder.remind_at, fetched_reminder.remind_at);
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_snooze_a_reminder() {
+        let reminder = create_a_reminder!();
+        let first_snooze = reminder
+            .remind_at
+            .add_duration(Duration::Minute(10))
+            .unwrap();
+        let second_snooze = first_snooze.add_duration(Duration::Minute(10)).unwrap();
+
+        let snoozed_reminder = snooze_reminder(reminder.id, first_snooze).unwrap();
+
+        assert_eq!(first_snooze, snoozed_reminder.remind_at);
+        assert_eq!(
+            Some(reminder.remind_at),
+            snoozed_reminder.original_remind_at
+        );
+        assert_eq!(1, snoozed_reminder.snooze_count);
+
+        let snoozed_reminder = snooze_reminder(reminder.id, second_snooze).unwrap();
+
+        assert_eq!(second_snooze, snoozed_reminder.remind_at);
+        assert_eq!(
+            Some(reminder.remind_at),
+            snoozed_reminder.original_remind_at
+        );
+        assert_eq!(2, snoozed_reminder.snooze_count);
+    }
+
+    #[test]
+    #[serial]
+    fn it_keeps_recurring_series_when_snoozed_occurrence_is_scheduled() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let snooze_date =
+            SimpleDate::parse_from_str("2024-04-05 10:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let now = SimpleDate::parse_from_str("2024-04-05 11:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let reminder = create_a_reminder!(date, Recurrence::new(Frequency::Daily));
+
+        snooze_reminder(reminder.id, snooze_date).unwrap();
+
+        let scheduled_reminders = schedule_next_occurrences(now).unwrap();
+
+        assert_eq!(1, scheduled_reminders.len());
+        assert_eq!(
+            "2024-04-06T08:00:00Z",
+            scheduled_reminders[0].remind_at.to_string()
+        );
+        assert_eq!(None, scheduled_reminders[0].original_remind_at);
+        assert_eq!(0, scheduled_reminders[0].snooze_count);
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_complete_a_reminder() {
+        let reminder = create_a_reminder!();
+
+        assert_eq!(ReminderStatus::Pending, reminder.status());
+
+        let completed_reminder = complete_reminder(reminder.id).unwrap();
+
+        assert_eq!(reminder.id, completed_reminder.id);
+        assert_eq!(ReminderStatus::Completed, completed_reminder.status());
+    }
+
+    #[test]
+    #[serial]
+    fn it_keeps_completed_occurrence_of_recurring_reminder() {
+        clear_test_database();
+
+        let date = SimpleDate::local().add_duration(Duration::Hour(1)).unwrap();
+        let reminder = create_a_reminder!(date, Recurrence::new(Frequency::Daily));
+        let completed_reminder = complete_reminder(reminder.id).unwrap();
+        let fetched_reminder = fetch_reminder(reminder.id).unwrap();
+
+        assert_ne!(reminder.id, completed_reminder.id);
+        assert_eq!(ReminderStatus::Completed, completed_reminder.status());
+        assert_eq!(date.to_string(), completed_reminder.remind_at.to_string());
+        assert_eq!(None, completed_reminder.recurrence);
+
+        assert_eq!(ReminderStatus::Pending, fetched_reminder.status());
+        assert_eq!(2, fetched_reminder.occurrence);
+        assert_eq!(
+            date.add_duration(Duration::Day(1)).unwrap().to_string(),
+            fetched_reminder.remind_at.to_string()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn it_keeps_the_timezone_a_reminder_was_created_in() {
+        clear_test_database();
+
+        let date = SimpleDate::local().add_duration(Duration::Hour(1)).unwrap();
+        let reminder = create_a_reminder!(date, Recurrence::new(Frequency::Daily));
+        let timezone = Some("Pacific/Auckland".to_owned());
+
+        assert_eq!(current_timezone(), reminder.timezone);
+
+        within_database(|conn| {
+            diesel::update(reminders_dsl::reminders.find(reminder.id))
+                .set(reminders_dsl::timezone.eq(&timezone))
+                .execute(conn)?;
+
+            Ok(())
+        })
+        .unwrap();
+
+        let snoozed_reminder =
+            snooze_reminder(reminder.id, date.add_duration(Duration::Hour(1)).unwrap()).unwrap();
+
+        assert_eq!(timezone, snoozed_reminder.timezone);
+
+        let updated_reminder = update_reminder(UpdateReminderData {
+            id: reminder.id,
+            note: Some("Moved".to_owned()),
+            remind_at: Some(date.add_duration(Duration::Hour(2)).unwrap()),
+            recurrence: None,
+            tags: None,
+            priority: None,
+            alerts: None,
+            checklist: None,
+        })
+        .unwrap();
+
+        assert_eq!(timezone, updated_reminder.timezone);
+
+        let completed_reminder = complete_reminder(reminder.id).unwrap();
+        let scheduled_reminder = fetch_reminder(reminder.id).unwrap();
+
+        assert_eq!(timezone, completed_reminder.timezone);
+        assert_eq!(timezone, scheduled_reminder.timezone);
+
+        restore_reminders(vec![updated_reminder], RestoreConflict::Overwrite).unwrap();
+
+        assert_eq!(timezone, fetch_reminder(reminder.id).unwrap().timezone);
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_dismiss_overdue_reminders() {
+        clear_test_database();
+
+        let d1 = SimpleDate::parse_from_str("2024-03-11 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let d2 = SimpleDate::parse_from_str("2024-03-12 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let now = SimpleDate::parse_from_str("2024-03-11 09:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let overdue_reminder = create_a_reminder!(d1);
+        let completed_reminder = create_a_reminder!(d1);
+        let upcoming_reminder = create_a_reminder!(d2);
+
+        complete_reminder(completed_reminder.id).unwrap();
+
+        assert_eq!(1, dismiss_overdue_reminders(now).unwrap());
+        assert_eq!(
+            ReminderStatus::Dismissed,
+            fetch_reminder(overdue_reminder.id).unwrap().status()
+        );
+        assert_eq!(
+            ReminderStatus::Completed,
+            fetch_reminder(completed_reminder.id).unwrap().status()
+        );
+        assert_eq!(
+            ReminderStatus::Pending,
+            fetch_reminder(upcoming_reminder.id).unwrap().status()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_fetch_reminders_by_status() {
+        clear_test_database();
+
+        let pending_reminder = create_a_reminder!();
+        let completed_reminder = create_a_reminder!();
+
+        complete_reminder(completed_reminder.id).unwrap();
+
+        let pending_reminders = fetch_reminders(Some(vec![ReminderQueryOptions::WithStatus {
+            status: ReminderStatus::Pending,
+        }]))
+        .unwrap();
+        let completed_reminders = fetch_reminders(Some(vec![ReminderQueryOptions::WithStatus {
+            status: ReminderStatus::Completed,
+        }]))
+        .unwrap();
+
+        assert_eq!(1, pending_reminders.len());
+        assert_eq!(pending_reminder.id, pending_reminders[0].id);
+        assert_eq!(1, completed_reminders.len());
+        assert_eq!(completed_reminder.id, completed_reminders[0].id);
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_create_a_reminder_with_tags() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let reminder = create_reminder(CreateReminderData {
+            remind_at: date,
+            note: "Testing".to_owned(),
+            recurrence: None,
+            tags: vec![
+                "work".to_owned(),
+                " home ".to_owned(),
+                "work".to_owned(),
+                "".to_owned(),
+            ],
+            priority: ReminderPriority::Normal,
+            alerts: vec![],
+            checklist: vec![],
+        })
+        .unwrap();
+
+        assert_eq!(vec!["home".to_owned(), "work".to_owned()], reminder.tags);
+        assert_eq!(reminder.tags, fetch_reminder(reminder.id).unwrap().tags);
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_update_tags_of_a_reminder() {
+        clear_test_database();
+
+        let reminder = create_a_reminder!();
+
+        let updated_reminder = update_reminder(UpdateReminderData {
+            id: reminder.id,
+            note: None,
+            remind_at: None,
+            recurrence: None,
+            tags: Some(vec!["work".to_owned()]),
+            priority: None,
+            alerts: None,
+            checklist: None,
+        })
+        .unwrap();
+
+        assert_eq!(vec!["work".to_owned()], updated_reminder.tags);
+
+        let updated_reminder = update_reminder(UpdateReminderData {
+            id: reminder.id,
+            note: Some("Testing a new".to_owned()),
+            remind_at: None,
+            recurrence: None,
+            tags: None,
+            priority: None,
+            alerts: None,
+            checklist: None,
+        })
+        .unwrap();
+
+        // tags are left alone when not given
+        assert_eq!(vec!["work".to_owned()], updated_reminder.tags);
+
+        let updated_reminder = update_reminder(UpdateReminderData {
+            id: reminder.id,
+            note: None,
+            remind_at: None,
+            recurrence: None,
+            tags: Some(vec![]),
+            priority: None,
+            alerts: None,
+            checklist: None,
+        })
+        .unwrap();
+
+        assert!(updated_reminder.tags.is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_fetch_reminders_by_tags() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let create_tagged_reminder = |tags: Vec<&str>| {
+            create_reminder(CreateReminderData {
+                remind_at: date,
+                note: "Testing".to_owned(),
+                recurrence: None,
+                tags: tags.into_iter().map(|tag| tag.to_owned()).collect(),
+                priority: ReminderPriority::Normal,
+                alerts: vec![],
+                checklist: vec![],
+            })
+            .unwrap()
+        };
+        let work_reminder = create_tagged_reminder(vec!["work"]);
+        let home_reminder = create_tagged_reminder(vec!["home", "errands"]);
+        let untagged_reminder = create_a_reminder!();
+
+        let tags = vec!["work".to_owned(), "errands".to_owned()];
+        let tagged_reminders =
+            fetch_reminders(Some(vec![ReminderQueryOptions::WithTags { tags }])).unwrap();
+
+        assert_eq!(2, tagged_reminders.len());
+        assert_eq!(work_reminder.id, tagged_reminders[0].id);
+        assert_eq!(home_reminder.id, tagged_reminders[1].id);
+        assert_eq!(
+            vec!["errands".to_owned(), "home".to_owned()],
+            tagged_reminders[1].tags
+        );
+
+        let tags = vec!["work".to_owned()];
+        let other_reminders =
+            fetch_reminders(Some(vec![ReminderQueryOptions::WithoutTags { tags }])).unwrap();
+
+        assert_eq!(2, other_reminders.len());
+        assert_eq!(home_reminder.id, other_reminders[0].id);
+        assert_eq!(untagged_reminder.id, other_reminders[1].id);
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_delete_reminders_by_tags() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        create_reminder(CreateReminderData {
+            remind_at: date,
+            note: "Testing".to_owned(),
+            recurrence: None,
+            tags: vec!["work".to_owned()],
+            priority: ReminderPriority::Normal,
+            alerts: vec![],
+            checklist: vec![],
+        })
+        .unwrap();
+        let untagged_reminder = create_a_reminder!();
+
+        let tags = vec!["work".to_owned()];
+        delete_reminders(ReminderQueryOptions::WithTags { tags }).unwrap();
+        purge_deleted_reminders(SimpleDate::local()).unwrap();
+
+        let reminders = fetch_reminders(None).unwrap();
+        let remaining_links: i64 = within_database(|conn| {
+            Ok(reminder_tags_dsl::reminder_tags.count().get_result(conn)?)
+        })
+        .unwrap();
+
+        assert_eq!(1, reminders.len());
+        assert_eq!(untagged_reminder.id, reminders[0].id);
+        assert_eq!(0, remaining_links);
+    }
+
+    #[test]
+    #[serial]
+    fn it_keeps_tags_of_completed_occurrence_of_recurring_reminder() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let reminder = create_reminder(CreateReminderData {
+            remind_at: date,
+            note: "Testing".to_owned(),
+            recurrence: Some(Recurrence::new(Frequency::Daily)),
+            tags: vec!["work".to_owned()],
+            priority: ReminderPriority::Normal,
+            alerts: vec![],
+            checklist: vec![],
+        })
+        .unwrap();
+
+        let completed_reminder = complete_reminder(reminder.id).unwrap();
+
+        assert_ne!(reminder.id, completed_reminder.id);
+        assert_eq!(vec!["work".to_owned()], completed_reminder.tags);
+        assert_eq!(
+            vec!["work".to_owned()],
+            fetch_reminder(reminder.id).unwrap().tags
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn it_orders_reminders_by_priority() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let create_prioritized_reminder = |priority: ReminderPriority| {
+            create_reminder(CreateReminderData {
+                remind_at: date,
+                note: "Testing".to_owned(),
+                recurrence: None,
+                tags: vec![],
+                priority,
+                alerts: vec![],
+                checklist: vec![],
+            })
+            .unwrap()
+        };
+        let low_reminder = create_prioritized_reminder(ReminderPriority::Low);
+        let normal_reminder = create_prioritized_reminder(ReminderPriority::Normal);
+        let critical_reminder = create_prioritized_reminder(ReminderPriority::Critical);
+        let high_reminder = create_prioritized_reminder(ReminderPriority::High);
+
+        let ids: Vec<i64> = fetch_reminders(None)
+            .unwrap()
+            .into_iter()
+            .map(|reminder| reminder.id)
+            .collect();
+
+        assert_eq!(
+            vec![
+                critical_reminder.id,
+                high_reminder.id,
+                normal_reminder.id,
+                low_reminder.id
+            ],
+            ids
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_update_priority_of_a_reminder() {
+        clear_test_database();
+
+        let reminder = create_a_reminder!();
+
+        assert_eq!(ReminderPriority::Normal, reminder.priority);
+
+        let updated_reminder = update_reminder(UpdateReminderData {
+            id: reminder.id,
+            note: None,
+            remind_at: None,
+            recurrence: None,
+            tags: None,
+            priority: Some(ReminderPriority::Critical),
+            alerts: None,
+            checklist: None,
+        })
+        .unwrap();
+
+        assert_eq!(ReminderPriority::Critical, updated_reminder.priority);
+        assert_eq!(
+            ReminderPriority::Critical,
+            fetch_reminder(reminder.id).unwrap().priority
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_update_alerts_of_a_reminder() {
+        clear_test_database();
+
+        let reminder = create_a_reminder!();
+
+        assert!(reminder.alerts.is_empty());
+
+        let updated_reminder = update_reminder(UpdateReminderData {
+            id: reminder.id,
+            note: None,
+            remind_at: None,
+            recurrence: None,
+            tags: None,
+            priority: None,
+            alerts: Some(vec![
+                AlertOffset::AT_TIME,
+                AlertOffset::minutes_before(60 * 24),
+                AlertOffset::AT_TIME,
+            ]),
+            checklist: None,
+        })
+        .unwrap();
+
+        assert_eq!(
+            vec![AlertOffset::minutes_before(60 * 24), AlertOffset::AT_TIME],
+            updated_reminder.alerts
+        );
+        assert_eq!(
+            updated_reminder.alerts,
+            fetch_reminder(reminder.id).unwrap().alerts
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_take_due_alerts_only_once() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let reminder = create_reminder(CreateReminderData {
+            remind_at: date,
+            note: "Testing".to_owned(),
+            recurrence: None,
+            tags: vec![],
+            priority: ReminderPriority::Normal,
+            alerts: vec![AlertOffset::minutes_before(60 * 24), AlertOffset::AT_TIME],
+            checklist: vec![],
+        })
+        .unwrap();
+
+        let two_days_before = date.sub_duration(Duration::Day(2)).unwrap();
+        assert!(take_due_alerts(two_days_before).unwrap().is_empty());
+
+        let day_before = date.sub_duration(Duration::Hour(23)).unwrap();
+        let due_alerts = take_due_alerts(day_before).unwrap();
+
+        assert_eq!(1, due_alerts.len());
+        assert_eq!(reminder.id, due_alerts[0].reminder.id);
+        assert_eq!(AlertOffset::minutes_before(60 * 24), due_alerts[0].offset);
+        assert!(take_due_alerts(day_before).unwrap().is_empty());
+
+        let due_alerts = take_due_alerts(date).unwrap();
+
+        assert_eq!(1, due_alerts.len());
+        assert_eq!(AlertOffset::AT_TIME, due_alerts[0].offset);
+        assert!(take_due_alerts(date).unwrap().is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn it_only_takes_latest_of_missed_alerts() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        create_reminder(CreateReminderData {
+            remind_at: date,
+            note: "Testing".to_owned(),
+            recurrence: None,
+            tags: vec![],
+            priority: ReminderPriority::Normal,
+            alerts: AlertOffset::defaults(),
+            checklist: vec![],
+        })
+        .unwrap();
+
+        let due_alerts = take_due_alerts(date.sub_duration(Duration::Minute(2)).unwrap()).unwrap();
+
+        assert_eq!(1, due_alerts.len());
+        assert_eq!(AlertOffset::minutes_before(5), due_alerts[0].offset);
+
+        // the alerts that were skipped are not notified later
+        let due_alerts = take_due_alerts(date.sub_duration(Duration::Minute(1)).unwrap()).unwrap();
+
+        assert_eq!(1, due_alerts.len());
+        assert_eq!(AlertOffset::minutes_before(1), due_alerts[0].offset);
+    }
+
+    #[test]
+    #[serial]
+    fn it_alerts_again_when_reminder_is_snoozed() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let reminder = create_reminder(CreateReminderData {
+            remind_at: date,
+            note: "Testing".to_owned(),
+            recurrence: None,
+            tags: vec![],
+            priority: ReminderPriority::Normal,
+            alerts: vec![AlertOffset::AT_TIME],
+            checklist: vec![],
+        })
+        .unwrap();
+
+        assert_eq!(1, take_due_alerts(date).unwrap().len());
+
+        let snoozed_until = date.add_duration(Duration::Minute(10)).unwrap();
+        snooze_reminder(reminder.id, snoozed_until).unwrap();
+
+        assert!(take_due_alerts(date).unwrap().is_empty());
+        assert_eq!(1, take_due_alerts(snoozed_until).unwrap().len());
+    }
+
+    #[test]
+    #[serial]
+    fn it_does_not_take_alerts_of_handled_or_long_overdue_reminders() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let create_alerted_reminder = || {
+            create_reminder(CreateReminderData {
+                remind_at: date,
+                note: "Testing".to_owned(),
+                recurrence: None,
+                tags: vec![],
+                priority: ReminderPriority::Normal,
+                alerts: vec![AlertOffset::AT_TIME],
+                checklist: vec![],
+            })
+            .unwrap()
+        };
+        let completed_reminder = create_alerted_reminder();
+        create_alerted_reminder();
+
+        complete_reminder(completed_reminder.id).unwrap();
+
+        let long_after = date.add_duration(Duration::Hour(2)).unwrap();
+
+        assert!(take_due_alerts(long_after).unwrap().is_empty());
+        assert_eq!(1, take_due_alerts(date).unwrap().len());
+    }
+
+    macro_rules! create_a_noted_reminder {
+        ($note:literal, $date:literal) => {{
+            let date = SimpleDate::parse_from_str($date, "%Y-%m-%d %H:%M:%S").unwrap();
+
+            create_reminder(CreateReminderData {
+                remind_at: date,
+                note: $note.to_owned(),
+                recurrence: None,
+                tags: vec![],
+                priority: ReminderPriority::Normal,
+                alerts: vec![],
+                checklist: vec![],
+            })
+            .unwrap()
+        }};
+    }
+
+    fn ids_of(reminders: Vec<ReminderData>) -> Vec<i64> {
+        reminders.into_iter().map(|reminder| reminder.id).collect()
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_query_reminders_by_note() {
+        clear_test_database();
+
+        let milk = create_a_noted_reminder!("Buy Milk", "2024-04-05 08:00:00");
+        create_a_noted_reminder!("Call mom", "2024-04-05 09:00:00");
+        let discount = create_a_noted_reminder!("Use the 100% discount", "2024-04-05 10:00:00");
+
+        let query = ReminderQuery::new().filter(ReminderQueryOptions::NoteContains {
+            text: "milk".to_owned(),
+        });
+
+        assert_eq!(vec![milk.id], ids_of(query_reminders(&query).unwrap()));
+
+        // like wildcards are matched literally
+        let query = ReminderQuery::new().filter(ReminderQueryOptions::NoteContains {
+            text: "0%".to_owned(),
+        });
+
+        assert_eq!(vec![discount.id], ids_of(query_reminders(&query).unwrap()));
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_combine_filters() {
+        clear_test_database();
+
+        let milk = create_a_noted_reminder!("Buy milk", "2024-04-05 08:00:00");
+        let mom = create_a_noted_reminder!("Call mom", "2024-04-06 08:00:00");
+        let bread = create_a_noted_reminder!("Buy bread", "2024-04-07 08:00:00");
+        let note_contains = |text: &str| ReminderQueryOptions::NoteContains {
+            text: text.to_owned(),
+        };
+
+        let filter = ReminderFilter::from(note_contains("milk")).or(note_contains("mom"));
+        let query = ReminderQuery::new().filter(filter.clone());
+
+        assert_eq!(
+            vec![milk.id, mom.id],
+            ids_of(query_reminders(&query).unwrap())
+        );
+
+        let query = ReminderQuery::new().filter(!filter.clone());
+
+        assert_eq!(vec![bread.id], ids_of(query_reminders(&query).unwrap()));
+
+        let date = SimpleDate::parse_from_str("2024-04-06 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let query = ReminderQuery::new()
+            .filter(filter)
+            .filter(ReminderQueryOptions::RemindAtGe { date });
+
+        assert_eq!(vec![mom.id], ids_of(query_reminders(&query).unwrap()));
+
+        let query = ReminderQuery::new().filter(ReminderFilter::Or(vec![]));
+
+        assert!(query_reminders(&query).unwrap().is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_sort_and_page_reminders() {
+        clear_test_database();
+
+        let third = create_a_noted_reminder!("Third", "2024-04-07 08:00:00");
+        let first = create_a_noted_reminder!("First", "2024-04-05 08:00:00");
+        let second = create_a_noted_reminder!("Second", "2024-04-06 08:00:00");
+
+        let query = ReminderQuery::new().sort_by(ReminderSortField::RemindAt, SortDirection::Asc);
+
+        assert_eq!(
+            vec![first.id, second.id, third.id],
+            ids_of(query_reminders(&query).unwrap())
+        );
+
+        let query = ReminderQuery::new()
+            .sort_by(ReminderSortField::RemindAt, SortDirection::Desc)
+            .limit(2)
+            .offset(1);
+
+        assert_eq!(
+            vec![second.id, first.id],
+            ids_of(query_reminders(&query).unwrap())
+        );
+
+        let query = ReminderQuery::new()
+            .sort_by(ReminderSortField::RemindAt, SortDirection::Asc)
+            .offset(2);
+
+        assert_eq!(vec![third.id], ids_of(query_reminders(&query).unwrap()));
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_count_reminders() {
+        clear_test_database();
+
+        create_a_noted_reminder!("Buy milk", "2024-04-05 08:00:00");
+        create_a_noted_reminder!("Buy bread", "2024-04-06 08:00:00");
+        create_a_noted_reminder!("Call mom", "2024-04-07 08:00:00");
+
+        let query = ReminderQuery::new()
+            .filter(ReminderQueryOptions::NoteContains {
+                text: "buy".to_owned(),
+            })
+            .limit(1);
+
+        assert_eq!(3, count_reminders(&ReminderQuery::new()).unwrap());
+        // paging does not change the total
+        assert_eq!(2, count_reminders(&query).unwrap());
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_delete_reminders_with_a_combined_filter() {
+        clear_test_database();
+
+        create_a_noted_reminder!("Buy milk", "2024-04-05 08:00:00");
+        let mom = create_a_noted_reminder!("Call mom", "2024-04-06 08:00:00");
+        create_a_noted_reminder!("Buy bread", "2024-04-07 08:00:00");
+
+        let filter = ReminderFilter::from(ReminderQueryOptions::NoteContains {
+            text: "milk".to_owned(),
+        })
+        .or(ReminderQueryOptions::NoteContains {
+            text: "bread".to_owned(),
+        });
+
+        assert_eq!(2, delete_reminders(filter).unwrap());
+        assert_eq!(vec![mom.id], ids_of(fetch_reminders(None).unwrap()));
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_search_reminders() {
+        clear_test_database();
+
+        let dentist = create_a_noted_reminder!("Go to the Dentist", "2024-04-05 08:00:00");
+        let groceries =
+            create_a_noted_reminder!("Buy bread and milk for the kids", "2024-04-05 09:00:00");
+        create_a_noted_reminder!("Call mom", "2024-04-05 10:00:00");
+
+        let search = |text: &str| ids_of(search_reminders(text, &ReminderQuery::new()).unwrap());
+
+        assert_eq!(vec![dentist.id], search("dentist"));
+        // words are matched by their beginning, ignoring case
+        assert_eq!(vec![dentist.id], search("DENT"));
+        assert!(search("entist").is_empty());
+        // every term has to match
+        assert_eq!(vec![groceries.id], search("milk, bread"));
+        assert!(search("milk dentist").is_empty());
+        // operators of the search syntax are plain words
+        assert_eq!(vec![groceries.id], search("bread AND"));
+        assert_eq!(vec![dentist.id, groceries.id], search("the"));
+    }
+
+    #[test]
+    #[serial]
+    fn it_keeps_the_search_index_up_to_date() {
+        clear_test_database();
+
+        let reminder = create_a_noted_reminder!("Go to the dentist", "2024-04-05 08:00:00");
+
+        update_reminder(UpdateReminderData {
+            id: reminder.id,
+            note: Some("Go to the barber".to_owned()),
+            remind_at: None,
+            recurrence: None,
+            tags: None,
+            priority: None,
+            alerts: None,
+            checklist: None,
+        })
+        .unwrap();
+
+        let search = |text: &str| ids_of(search_reminders(text, &ReminderQuery::new()).unwrap());
+
+        assert!(search("dentist").is_empty());
+        assert_eq!(vec![reminder.id], search("barber"));
+
+        delete_reminder(reminder.id).unwrap();
+
+        assert!(search("barber").is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_search_within_a_query() {
+        clear_test_database();
+
+        create_a_noted_reminder!("Buy milk", "2024-04-05 08:00:00");
+        let later = create_a_noted_reminder!("Buy bread", "2024-04-07 08:00:00");
+
+        let date = SimpleDate::parse_from_str("2024-04-06 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let query = ReminderQuery::new().filter(ReminderQueryOptions::RemindAtGe { date });
+
+        assert_eq!(
+            vec![later.id],
+            ids_of(search_reminders("buy", &query).unwrap())
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn it_records_changes_of_a_reminder() {
+        clear_test_database();
+
+        let reminder = create_a_reminder!();
+        let updated_reminder = update_reminder(UpdateReminderData {
+            id: reminder.id,
+            note: Some("Updated".to_owned()),
+            remind_at: None,
+            recurrence: None,
+            tags: Some(vec!["home".to_owned()]),
+            priority: None,
+            alerts: None,
+            checklist: None,
+        })
+        .unwrap();
+
+        delete_reminder(reminder.id).unwrap();
+
+        let changes = fetch_reminder_changes(reminder.id).unwrap();
+        let kinds: Vec<ReminderChangeKind> = changes.iter().map(|change| change.kind).collect();
+
+        assert_eq!(
+            vec![
+                ReminderChangeKind::Create,
+                ReminderChangeKind::Update,
+                ReminderChangeKind::Delete
+            ],
+            kinds
+        );
+        assert_eq!(
+            (None, Some(&reminder)),
+            (changes[0].before.as_ref(), changes[0].after.as_ref())
+        );
+        assert_eq!(Some(&reminder), changes[1].before.as_ref());
+        assert_eq!(Some(&updated_reminder), changes[1].after.as_ref());
+        assert_eq!(Some(&updated_reminder), changes[2].before.as_ref());
+        assert!(changes[2].after.as_ref().unwrap().is_deleted());
+    }
+
+    #[test]
+    #[serial]
+    fn it_records_the_completed_copy_of_a_recurring_reminder() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let reminder = create_a_reminder!(date, Recurrence::new(Frequency::Daily));
+        let changes = apply_reminder_batch(vec![ReminderOperation::Complete(reminder.id)]).unwrap();
+
+        assert_eq!(2, changes.len());
+        assert_eq!(ReminderChangeKind::Update, changes[0].kind);
+        assert_eq!(reminder.id, changes[0].reminder_id);
+        assert_eq!(ReminderChangeKind::Create, changes[1].kind);
+        assert_eq!(
+            ReminderStatus::Completed,
+            fetch_reminder(changes[1].reminder_id).unwrap().status()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn it_rolls_back_a_failed_batch() {
+        clear_test_database();
+
+        let reminder = create_a_reminder!();
+        let result = apply_reminder_batch(vec![
+            ReminderOperation::Delete(reminder.id),
+            ReminderOperation::Complete(reminder.id + 100),
+        ]);
+
+        assert!(matches!(
+            result,
+            Err(DatabaseError::FailedToFetchResult(
+                DieselResultError::NotFound
+            ))
+        ));
+        assert_eq!(reminder, fetch_reminder(reminder.id).unwrap());
+        assert_eq!(1, fetch_reminder_changes(reminder.id).unwrap().len());
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_undo_and_redo_changes_with_their_inverse() {
+        clear_test_database();
+
+        let reminder = create_reminder(CreateReminderData {
+            remind_at: SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S")
+                .unwrap(),
+            note: "Testing".to_owned(),
+            recurrence: None,
+            tags: vec!["home".to_owned()],
+            priority: ReminderPriority::High,
+            alerts: vec![AlertOffset::minutes_before(15)],
+            checklist: vec![],
+        })
+        .unwrap();
+        let later = reminder.remind_at.add_duration(Duration::Hour(1)).unwrap();
+        let changes = apply_reminder_batch(vec![
+            ReminderOperation::Snooze(reminder.id, later),
+            ReminderOperation::Delete(reminder.id),
+        ])
+        .unwrap();
+        let undo = |changes: &[ReminderChangeData]| {
+            apply_reminder_batch(
+                changes
+                    .iter()
+                    .rev()
+                    .map(|change| change.inverse())
+                    .collect(),
+            )
+            .unwrap()
+        };
+
+        assert!(fetch_reminder(reminder.id).unwrap().is_deleted());
+
+        let undone_changes = undo(&changes);
+
+        assert_eq!(reminder, fetch_reminder(reminder.id).unwrap());
+
+        undo(&undone_changes);
+
+        assert!(fetch_reminder(reminder.id).unwrap().is_deleted());
+        assert_eq!(0, count_reminders(&ReminderQuery::new()).unwrap());
+    }
+
+    #[test]
+    #[serial]
+    fn it_keeps_deleted_reminders_in_the_trash() {
+        clear_test_database();
+
+        let now = SimpleDate::local();
+        let reminder = create_reminder(CreateReminderData {
+            remind_at: now,
+            note: "Testing".to_owned(),
+            recurrence: None,
+            tags: vec![],
+            priority: ReminderPriority::Normal,
+            alerts: vec![AlertOffset::AT_TIME],
+            checklist: vec![],
+        })
+        .unwrap();
+        let kept_reminder = create_a_reminder!(now);
+
+        delete_reminder(reminder.id).unwrap();
+
+        assert_eq!(
+            vec![kept_reminder.id],
+            ids_of(fetch_reminders(None).unwrap())
+        );
+        assert_eq!(1, count_reminders(&ReminderQuery::new()).unwrap());
+        assert_eq!(
+            vec![reminder.id],
+            ids_of(query_reminders(&ReminderQuery::new().trash(TrashFilter::Only)).unwrap())
+        );
+        assert_eq!(
+            2,
+            count_reminders(&ReminderQuery::new().trash(TrashFilter::Include)).unwrap()
+        );
+        assert!(take_due_alerts(now).unwrap().is_empty());
+        // only the reminder that was kept
+        assert_eq!(1, dismiss_overdue_reminders(now).unwrap());
+
+        let restored_reminder = restore_deleted_reminder(reminder.id).unwrap();
+
+        assert_eq!(reminder, restored_reminder);
+        assert_eq!(2, count_reminders(&ReminderQuery::new()).unwrap());
+    }
+
+    #[test]
+    #[serial]
+    fn it_purges_reminders_deleted_before_a_date() {
+        clear_test_database();
+
+        let reminder = create_a_reminder!();
+        let kept_reminder = create_a_reminder!();
+
+        delete_reminder(reminder.id).unwrap();
+
+        let yesterday = SimpleDate::local().sub_duration(Duration::Day(1)).unwrap();
+
+        assert_eq!(0, purge_deleted_reminders(yesterday).unwrap());
+        assert_eq!(1, purge_deleted_reminders(SimpleDate::local()).unwrap());
+        assert!(fetch_reminder(reminder.id).is_err());
+        assert!(fetch_reminder(kept_reminder.id).is_ok());
+        assert_eq!(
+            Some(ReminderChangeKind::Purge),
+            fetch_reminder_changes(reminder.id)
+                .unwrap()
+                .last()
+                .map(|change| change.kind)
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn it_removes_what_belongs_to_a_purged_reminder() {
+        clear_test_database();
+
+        let reminder = create_reminder(CreateReminderData {
+            note: "Testing".to_owned(),
+            remind_at: SimpleDate::local(),
+            recurrence: None,
+            tags: vec!["home".to_owned()],
+            priority: ReminderPriority::Normal,
+            alerts: AlertOffset::defaults(),
+            checklist: vec![ChecklistItemData::new("milk")],
+        })
+        .unwrap();
+        let count_relations = || {
+            within_database(|conn| {
+                Ok((
+                    reminder_tags_dsl::reminder_tags.count().get_result(conn)?,
+                    reminder_alerts_dsl::reminder_alerts
+                        .count()
+                        .get_result(conn)?,
+                    checklist_items_dsl::reminder_checklist_items
+                        .count()
+                        .get_result(conn)?,
+                ))
+            })
+            .unwrap()
+        };
+
+        assert_ne!((0i64, 0i64, 0i64), count_relations());
+
+        purge_reminder(reminder.id).unwrap();
+
+        assert_eq!((0, 0, 0), count_relations());
+    }
+
+    #[test]
+    #[serial]
+    fn it_can_edit_the_checklist_of_a_reminder() {
+        clear_test_database();
+
+        let reminder = create_reminder(CreateReminderData {
+            remind_at: SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S")
+                .unwrap(),
+            note: "Release".to_owned(),
+            recurrence: None,
+            tags: vec![],
+            priority: ReminderPriority::Normal,
+            alerts: vec![],
+            checklist: vec![
+                ChecklistItemData::new(" tag "),
+                ChecklistItemData::new(""),
+                ChecklistItemData::new("publish"),
+            ],
+        })
+        .unwrap();
+        let titles_of = |reminder: &ReminderData| -> Vec<String> {
+            reminder
+                .checklist
+                .iter()
+                .map(|item| item.title.clone())
+                .collect()
+        };
+        let tag = reminder.checklist[0].id;
+        let publish = reminder.checklist[1].id;
+
+        assert_eq!(vec!["tag", "publish"], titles_of(&reminder));
+        assert_eq!(
+            Some(ChecklistProgress { done: 0, total: 2 }),
+            reminder.checklist_progress()
+        );
+
+        edit_reminder_checklist(reminder.id, ChecklistEdit::Add("announce".to_owned())).unwrap();
+        edit_reminder_checklist(reminder.id, ChecklistEdit::SetDone(tag, true)).unwrap();
+
+        let edited_reminder =
+            edit_reminder_checklist(reminder.id, ChecklistEdit::Move(publish, 10)).unwrap();
+
+        assert_eq!(
+            vec!["tag", "announce", "publish"],
+            titles_of(&edited_reminder)
+        );
+        assert_eq!(tag, edited_reminder.checklist[0].id);
+        assert_eq!(
+            Some(ChecklistProgress { done: 1, total: 3 }),
+            edited_reminder.checklist_progress()
+        );
+        assert_eq!(edited_reminder, fetch_reminder(reminder.id).unwrap());
+
+        let edited_reminder =
+            edit_reminder_checklist(reminder.id, ChecklistEdit::Remove(tag)).unwrap();
+
+        assert_eq!(vec!["announce", "publish"], titles_of(&edited_reminder));
+        assert!(edit_reminder_checklist(reminder.id, ChecklistEdit::SetDone(tag, true)).is_err());
+
+        // taking the removal back brings the item back with its id
+        let change = fetch_reminder_changes(reminder.id).unwrap().pop().unwrap();
+
+        apply_reminder_batch(vec![change.inverse()]).unwrap();
+
+        let restored_reminder = fetch_reminder(reminder.id).unwrap();
+
+        assert_eq!(tag, restored_reminder.checklist[0].id);
+        assert!(restored_reminder.checklist[0].done);
+        assert_eq!(
+            vec!["tag", "announce", "publish"],
+            titles_of(&restored_reminder)
+        );
+
+        purge_reminder(reminder.id).unwrap();
+
+        let remaining_items: i64 = within_database(|conn| {
+            Ok(checklist_items_dsl::reminder_checklist_items
+                .count()
+                .get_result(conn)?)
+        })
+        .unwrap();
+
+        assert_eq!(0, remaining_items);
+    }
+
+    #[test]
+    #[serial]
+    fn it_starts_the_checklist_over_for_the_next_occurrence() {
+        clear_test_database();
+
+        let date = SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let reminder = create_reminder(CreateReminderData {
+            remind_at: date,
+            note: "Testing".to_owned(),
+            recurrence: Some(Recurrence::new(Frequency::Daily)),
+            tags: vec![],
+            priority: ReminderPriority::Normal,
+            alerts: vec![],
+            checklist: vec![
+                ChecklistItemData::new("water"),
+                ChecklistItemData::new("feed"),
+            ],
+        })
+        .unwrap();
+
+        edit_reminder_checklist(
+            reminder.id,
+            ChecklistEdit::SetDone(reminder.checklist[0].id, true),
+        )
+        .unwrap();
+
+        let completed_reminder = complete_reminder(reminder.id).unwrap();
+        let scheduled_reminder = fetch_reminder(reminder.id).unwrap();
+
+        assert_eq!(
+            Some(ChecklistProgress { done: 1, total: 2 }),
+            completed_reminder.checklist_progress()
+        );
+        assert_ne!(reminder.checklist[0].id, completed_reminder.checklist[0].id);
+        assert_eq!(
+            Some(ChecklistProgress { done: 0, total: 2 }),
+            scheduled_reminder.checklist_progress()
+        );
+        assert_eq!(reminder.checklist, scheduled_reminder.checklist);
+    }
+
+    #[test]
+    #[serial]
+    fn it_reports_reminders_with_invalid_dates() {
+        // only sqlite lets a date column hold anything else
+        if within_database(|conn| Ok(conn.kind())).unwrap() != DatabaseKind::Sqlite {
+            return;
+        }
+
+        clear_test_database();
+
+        within_database(|conn| {
+            sql_query(
+                "INSERT INTO reminders (id, note, remind_at, created_at) \
+                 VALUES (1, 'Broken', 'not a date', '2024-04-05 08:00:00')",
+            )
+            .execute(conn)
+            .unwrap();
+
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(matches!(
+            fetch_reminder(1),
+            Err(DatabaseError::FailedToFetchResult(_))
+        ));
+        assert!(query_reminders(&ReminderQuery::new()).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn it_reports_reminders_with_invalid_recurrences() {
+        clear_test_database();
+
+        let reminder = create_a_reminder!();
+
+        within_database(|conn| {
+            diesel::update(reminders_dsl::reminders.find(reminder.id))
+                .set(reminders_dsl::recurrence.eq("every other blue moon"))
+                .execute(conn)?;
+
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(matches!(
+            fetch_reminder(reminder.id),
+            Err(DatabaseError::InvalidRecurrence(_))
+        ));
+        assert!(query_reminders(&ReminderQuery::new()).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "sqlite-database")]
+    fn it_migrates_dates_of_existing_reminders() {
+        use crate::database::DatabaseBackend;
+        use diesel::migration::MigrationSource;
+        use diesel_migrations::MigrationHarness;
+
+        let mut conn = DatabaseConnection::connect_to(":memory:").unwrap();
+        let migrations: Vec<_> =
+            MigrationSource::<DatabaseBackend>::migrations(&CORE_MIGRATIONS.sqlite).unwrap();
+
+        // sets up the table of applied migrations, which running single migrations does not
+        assert!(conn.applied_migrations().unwrap().is_empty());
+
+        for migration in migrations.iter().take_while(|migration| {
+            !migration
+                .name()
+                .to_string()
+                .contains("store_reminder_dates_as_utc")
+        }) {
+            conn.run_migration(migration).unwrap();
+        }
+
+        sql_query(
+            "INSERT INTO reminders (id, note, remind_at, created_at, completed_at) \
+             VALUES (1, 'Old', '2024-04-05T08:00:00Z', '2024-04-01T10:30:00Z', '2024-04-05T09:00:00Z')",
+        )
+        .execute(&mut conn)
+        .unwrap();
+        sql_query(
+            "INSERT INTO reminder_alerts (reminder_id, offset_minutes, fired_for) \
+             VALUES (1, 0, '2024-04-05T08:00:00Z')",
+        )
+        .execute(&mut conn)
+        .unwrap();
+
+        conn.run_pending_migrations(CORE_MIGRATIONS.sqlite).unwrap();
+
+        let date = |date| SimpleDate::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap();
+        let reminder = reminders_dsl::reminders
+            .find(1)
+            .select(Reminder::as_select())
+            .first(&mut conn)
+            .unwrap();
+
+        assert_eq!(None, reminder.timezone);
+        assert_eq!(
+            Some(reminder.remind_at),
+            reminder_alerts_dsl::reminder_alerts
+                .select(reminder_alerts_dsl::fired_for)
+                .first::<Option<NaiveDateTime>>(&mut conn)
+                .unwrap()
+        );
+
+        let reminder: ReminderData = reminder.try_into().unwrap();
+
+        assert_eq!(date("2024-04-05 08:00:00"), reminder.remind_at);
+        assert_eq!(date("2024-04-01 10:30:00"), reminder.created_at);
+        assert_eq!(Some(date("2024-04-05 09:00:00")), reminder.completed_at);
     }
 }
@@ -0,0 +1,223 @@
+use hkb_date::date::SimpleDate;
+
+use super::{
+    ChecklistEdit, CreateReminderData, DueAlertData, ReminderChangeData, ReminderData,
+    ReminderOperation, ReminderQuery, ReminderRepository, UpdateReminderData,
+};
+use crate::database::{run_blocking, DatabaseResult};
+
+/// Runs the calls of a repository on the blocking thread pool of tokio,
+/// so select loops and render loops do not stall on the database.
+///
+/// Example
+/// ```rust
+/// use hkb_core::database::services::reminders::{
+///     AsyncReminderRepository, InMemoryReminderRepository, ReminderQuery,
+/// };
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let repository = AsyncReminderRepository::new(InMemoryReminderRepository::new());
+///
+/// assert_eq!(0, repository.count_reminders(ReminderQuery::new()).await.unwrap());
+/// # });
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct AsyncReminderRepository<R> {
+    repository: R,
+}
+
+impl<R: ReminderRepository + Clone + Send + 'static> AsyncReminderRepository<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    /// The wrapped repository, for code that already runs outside of async tasks
+    pub fn blocking(&self) -> &R {
+        &self.repository
+    }
+
+    pub async fn query_reminders(&self, query: ReminderQuery) -> DatabaseResult<Vec<ReminderData>> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.query_reminders(&query)).await
+    }
+
+    pub async fn count_reminders(&self, query: ReminderQuery) -> DatabaseResult<i64> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.count_reminders(&query)).await
+    }
+
+    pub async fn fetch_reminder(&self, id: i64) -> DatabaseResult<ReminderData> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.fetch_reminder(id)).await
+    }
+
+    pub async fn create_reminder(
+        &self,
+        reminder: CreateReminderData,
+    ) -> DatabaseResult<ReminderData> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.create_reminder(reminder)).await
+    }
+
+    pub async fn update_reminder(
+        &self,
+        reminder: UpdateReminderData,
+    ) -> DatabaseResult<ReminderData> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.update_reminder(reminder)).await
+    }
+
+    pub async fn delete_reminder(&self, id: i64) -> DatabaseResult<()> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.delete_reminder(id)).await
+    }
+
+    pub async fn restore_deleted_reminder(&self, id: i64) -> DatabaseResult<ReminderData> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.restore_deleted_reminder(id)).await
+    }
+
+    pub async fn purge_reminder(&self, id: i64) -> DatabaseResult<()> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.purge_reminder(id)).await
+    }
+
+    pub async fn purge_deleted_reminders(&self, date: SimpleDate) -> DatabaseResult<usize> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.purge_deleted_reminders(date)).await
+    }
+
+    pub async fn snooze_reminder(
+        &self,
+        id: i64,
+        remind_at: SimpleDate,
+    ) -> DatabaseResult<ReminderData> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.snooze_reminder(id, remind_at)).await
+    }
+
+    pub async fn complete_reminder(&self, id: i64) -> DatabaseResult<ReminderData> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.complete_reminder(id)).await
+    }
+
+    pub async fn schedule_next_occurrences(
+        &self,
+        date: SimpleDate,
+    ) -> DatabaseResult<Vec<ReminderData>> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.schedule_next_occurrences(date)).await
+    }
+
+    pub async fn dismiss_overdue_reminders(&self, date: SimpleDate) -> DatabaseResult<usize> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.dismiss_overdue_reminders(date)).await
+    }
+
+    pub async fn take_due_alerts(&self, date: SimpleDate) -> DatabaseResult<Vec<DueAlertData>> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.take_due_alerts(date)).await
+    }
+
+    pub async fn apply_reminder_batch(
+        &self,
+        operations: Vec<ReminderOperation>,
+    ) -> DatabaseResult<Vec<ReminderChangeData>> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.apply_reminder_batch(operations)).await
+    }
+
+    pub async fn fetch_reminder_changes(
+        &self,
+        reminder_id: i64,
+    ) -> DatabaseResult<Vec<ReminderChangeData>> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.fetch_reminder_changes(reminder_id)).await
+    }
+
+    pub async fn edit_reminder_checklist(
+        &self,
+        reminder_id: i64,
+        edit: ChecklistEdit,
+    ) -> DatabaseResult<ReminderData> {
+        let repository = self.repository.clone();
+
+        run_blocking(move || repository.edit_reminder_checklist(reminder_id, edit)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hkb_date::duration::Duration;
+
+    use super::*;
+    use crate::database::services::reminders::{InMemoryReminderRepository, ReminderPriority};
+    use crate::database::DatabaseError;
+
+    fn create_data(note: &str) -> CreateReminderData {
+        CreateReminderData {
+            note: note.to_owned(),
+            remind_at: SimpleDate::local().add_duration(Duration::Day(1)).unwrap(),
+            recurrence: None,
+            tags: vec![],
+            priority: ReminderPriority::Normal,
+            alerts: vec![],
+            checklist: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn it_runs_calls_off_the_async_task() {
+        let repository = AsyncReminderRepository::new(InMemoryReminderRepository::new());
+        let (first, second) = tokio::join!(
+            repository.create_reminder(create_data("First")),
+            repository.create_reminder(create_data("Second"))
+        );
+        let (first, second) = (first.unwrap(), second.unwrap());
+
+        assert_ne!(first.id, second.id);
+        assert_eq!(first, repository.fetch_reminder(first.id).await.unwrap());
+        assert_eq!(
+            2,
+            repository
+                .count_reminders(ReminderQuery::new())
+                .await
+                .unwrap()
+        );
+
+        repository.delete_reminder(first.id).await.unwrap();
+
+        assert_eq!(
+            vec![second],
+            repository
+                .query_reminders(ReminderQuery::new())
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn it_returns_the_errors_of_the_repository() {
+        let repository = AsyncReminderRepository::new(InMemoryReminderRepository::new());
+
+        assert!(matches!(
+            repository.fetch_reminder(1).await,
+            Err(DatabaseError::FailedToFetchResult(_))
+        ));
+    }
+}
@@ -0,0 +1,213 @@
+use diesel::result::Error as DieselResultError;
+use diesel::{Connection, ExpressionMethods, QueryDsl, QueryResult, RunQueryDsl, SelectableHelper};
+use log::debug;
+use std::collections::HashMap;
+
+use super::{
+    last_changed_reminder, load_reminder, record_change, ChecklistEdit, ChecklistItemData,
+    ReminderChangeData, ReminderChangeKind, ReminderData,
+};
+use crate::database::{
+    self, encryption,
+    models::checklists::{
+        CreateReminderChecklistItem, ReminderChecklistItem, UpdateReminderChecklistItem,
+    },
+    schema::reminder_checklist_items::{self, dsl as checklist_items_dsl},
+    DatabaseConnection, DatabaseError, DatabaseResult,
+};
+
+/// Titles are trimmed and items without one are left out
+pub(super) fn normalize_checklist(items: &[ChecklistItemData]) -> Vec<ChecklistItemData> {
+    items
+        .iter()
+        .map(|item| ChecklistItemData {
+            title: item.title.trim().to_owned(),
+            ..item.clone()
+        })
+        .filter(|item| !item.title.is_empty())
+        .collect()
+}
+
+/// The checklist of the next occurrence of a recurring reminder starts over
+pub(super) fn unchecked(items: &[ChecklistItemData]) -> Vec<ChecklistItemData> {
+    items
+        .iter()
+        .map(|item| ChecklistItemData {
+            done: false,
+            ..item.clone()
+        })
+        .collect()
+}
+
+pub(super) fn load_checklists(
+    conn: &mut DatabaseConnection,
+    reminders: &mut [ReminderData],
+) -> DatabaseResult<()> {
+    let ids: Vec<i64> = reminders.iter().map(|reminder| reminder.id).collect();
+    let items: Vec<ReminderChecklistItem> = checklist_items_dsl::reminder_checklist_items
+        .filter(checklist_items_dsl::reminder_id.eq_any(&ids))
+        .select(ReminderChecklistItem::as_select())
+        .order_by((
+            checklist_items_dsl::position.asc(),
+            checklist_items_dsl::id.asc(),
+        ))
+        .load(conn)?;
+    let mut items_by_reminder: HashMap<i64, Vec<ChecklistItemData>> =
+        HashMap::with_capacity(ids.len());
+
+    for item in items {
+        items_by_reminder
+            .entry(item.reminder_id)
+            .or_default()
+            .push(ChecklistItemData {
+                id: item.id,
+                title: encryption::decrypt(item.title)?,
+                done: item.done,
+            });
+    }
+
+    for reminder in reminders.iter_mut() {
+        reminder.checklist = items_by_reminder.remove(&reminder.id).unwrap_or_default();
+    }
+
+    Ok(())
+}
+
+/// Items that stay are updated in place, items the reminder does not have are added.
+/// Added items keep their ids when no other item has them, e.g. when they come from a backup.
+pub(super) fn set_checklist(
+    conn: &mut DatabaseConnection,
+    reminder_id: i64,
+    items: &[ChecklistItemData],
+) -> QueryResult<()> {
+    let items = normalize_checklist(items);
+    let existing_ids: Vec<i64> = checklist_items_dsl::reminder_checklist_items
+        .filter(checklist_items_dsl::reminder_id.eq(reminder_id))
+        .select(checklist_items_dsl::id)
+        .load(conn)?;
+    let kept_ids: Vec<i64> = items
+        .iter()
+        .map(|item| item.id)
+        .filter(|id| existing_ids.contains(id))
+        .collect();
+
+    diesel::delete(
+        checklist_items_dsl::reminder_checklist_items
+            .filter(checklist_items_dsl::reminder_id.eq(reminder_id))
+            .filter(diesel::dsl::not(checklist_items_dsl::id.eq_any(&kept_ids))),
+    )
+    .execute(conn)?;
+
+    let ids: Vec<i64> = items.iter().map(|item| item.id).collect();
+    let taken_ids: Vec<i64> = checklist_items_dsl::reminder_checklist_items
+        .filter(checklist_items_dsl::id.eq_any(&ids))
+        .select(checklist_items_dsl::id)
+        .load(conn)?;
+    let mut inserted_ids = false;
+
+    for (position, item) in items.into_iter().enumerate() {
+        if kept_ids.contains(&item.id) {
+            diesel::update(checklist_items_dsl::reminder_checklist_items.find(item.id))
+                .set(&UpdateReminderChecklistItem {
+                    title: encryption::encrypt(item.title),
+                    position: position as i32,
+                    done: item.done,
+                })
+                .execute(conn)?;
+        } else {
+            let id = Some(item.id).filter(|id| *id > 0 && !taken_ids.contains(id));
+            inserted_ids |= id.is_some();
+
+            diesel::insert_into(reminder_checklist_items::table)
+                .values(&CreateReminderChecklistItem {
+                    id,
+                    reminder_id,
+                    title: encryption::encrypt(item.title),
+                    position: position as i32,
+                    done: item.done,
+                })
+                .execute(conn)?;
+        }
+    }
+
+    if inserted_ids {
+        conn.sync_id_sequence("reminder_checklist_items")?;
+    }
+
+    Ok(())
+}
+
+/// Editing an item the checklist does not have fails
+pub(super) fn apply_checklist_edit(
+    checklist: &mut Vec<ChecklistItemData>,
+    edit: ChecklistEdit,
+) -> DatabaseResult<()> {
+    let position_of =
+        |checklist: &[ChecklistItemData], id: i64| {
+            checklist.iter().position(|item| item.id == id).ok_or(
+                DatabaseError::FailedToFetchResult(DieselResultError::NotFound),
+            )
+        };
+
+    match edit {
+        ChecklistEdit::Add(title) => checklist.push(ChecklistItemData::new(title)),
+        ChecklistEdit::Rename(id, title) => {
+            let position = position_of(checklist, id)?;
+
+            checklist[position].title = title;
+        }
+        ChecklistEdit::SetDone(id, done) => {
+            let position = position_of(checklist, id)?;
+
+            checklist[position].done = done;
+        }
+        ChecklistEdit::Move(id, new_position) => {
+            let item = checklist.remove(position_of(checklist, id)?);
+
+            checklist.insert(new_position.min(checklist.len()), item);
+        }
+        ChecklistEdit::Remove(id) => {
+            checklist.remove(position_of(checklist, id)?);
+        }
+    }
+
+    Ok(())
+}
+
+pub(super) fn edit_checklist_in(
+    conn: &mut DatabaseConnection,
+    reminder_id: i64,
+    edit: ChecklistEdit,
+) -> DatabaseResult<Vec<ReminderChangeData>> {
+    let reminder = load_reminder(conn, reminder_id)?;
+    let mut checklist = reminder.checklist.clone();
+
+    apply_checklist_edit(&mut checklist, edit)?;
+    set_checklist(conn, reminder_id, &checklist)?;
+
+    let edited_reminder = load_reminder(conn, reminder_id)?;
+
+    Ok(vec![record_change(
+        conn,
+        ReminderChangeKind::Update,
+        Some(reminder),
+        Some(edited_reminder),
+    )?])
+}
+
+/// Add, check, move or remove an item of the checklist of a reminder
+pub fn edit_reminder_checklist(
+    reminder_id: i64,
+    edit: ChecklistEdit,
+) -> DatabaseResult<ReminderData> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Editing checklist of reminder {reminder_id}: {edit:?}");
+
+        let changes = conn.transaction(|conn| edit_checklist_in(conn, reminder_id, edit))?;
+        let edited_reminder = last_changed_reminder(changes)?;
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Checklist of reminder {reminder_id} edited!");
+
+        Ok(edited_reminder)
+    })
+}
@@ -0,0 +1,116 @@
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+use hkb_date::date::SimpleDate;
+use log::debug;
+
+use super::sync::{next_version, ChangeVersion};
+use super::{from_instant, to_instant, ReminderChangeData, ReminderChangeKind, ReminderData};
+use crate::database::{
+    self, encryption,
+    models::changes::{CreateReminderChange, ReminderChange},
+    schema::reminder_changes::{self, dsl as reminder_changes_dsl},
+    DatabaseConnection, DatabaseError, DatabaseResult,
+};
+
+/// Snapshots hold the note of the reminder, so they are encrypted along with it
+fn serialize_snapshot(reminder: Option<&ReminderData>) -> DatabaseResult<Option<String>> {
+    Ok(reminder
+        .map(serde_json::to_string)
+        .transpose()?
+        .map(encryption::encrypt))
+}
+
+pub(super) fn deserialize_snapshot(
+    snapshot: Option<String>,
+) -> DatabaseResult<Option<ReminderData>> {
+    let snapshot = snapshot.map(encryption::decrypt).transpose()?;
+
+    Ok(snapshot.as_deref().map(serde_json::from_str).transpose()?)
+}
+
+impl TryFrom<ReminderChange> for ReminderChangeData {
+    type Error = DatabaseError;
+
+    fn try_from(val: ReminderChange) -> Result<Self, Self::Error> {
+        Ok(ReminderChangeData {
+            id: val.id,
+            reminder_id: val.reminder_id,
+            kind: val
+                .operation
+                .parse()
+                .map_err(DatabaseError::InvalidChange)?,
+            before: deserialize_snapshot(val.snapshot_before)?,
+            after: deserialize_snapshot(val.snapshot_after)?,
+            changed_at: from_instant(val.changed_at)?,
+        })
+    }
+}
+
+/// Writes a change made on this device to the journal, in the transaction that made it
+pub(super) fn record_change(
+    conn: &mut DatabaseConnection,
+    kind: ReminderChangeKind,
+    before: Option<ReminderData>,
+    after: Option<ReminderData>,
+) -> DatabaseResult<ReminderChangeData> {
+    let version = next_version(conn)?;
+
+    record_change_at(conn, kind, before, after, version)
+}
+
+/// Writes a change with the clock and the device it was made with, e.g. a change of another device
+pub(super) fn record_change_at(
+    conn: &mut DatabaseConnection,
+    kind: ReminderChangeKind,
+    before: Option<ReminderData>,
+    after: Option<ReminderData>,
+    (clock, device_id): ChangeVersion,
+) -> DatabaseResult<ReminderChangeData> {
+    let (reminder_id, reminder_uuid) = match (&before, &after) {
+        (_, Some(reminder)) | (Some(reminder), None) => (reminder.id, reminder.uuid.clone()),
+        (None, None) => unreachable!("a change has a reminder before or after it"),
+    };
+    let changed_at = to_instant(SimpleDate::local())?;
+
+    diesel::insert_into(reminder_changes::table)
+        .values(&CreateReminderChange {
+            reminder_id,
+            operation: kind.to_string(),
+            snapshot_before: serialize_snapshot(before.as_ref())?,
+            snapshot_after: serialize_snapshot(after.as_ref())?,
+            changed_at,
+            reminder_uuid: Some(reminder_uuid),
+            clock,
+            device_id: Some(device_id),
+        })
+        .execute(conn)?;
+
+    Ok(ReminderChangeData {
+        id: conn.last_insert_id()?,
+        reminder_id,
+        kind,
+        before,
+        after,
+        changed_at: from_instant(changed_at)?,
+    })
+}
+
+/// Every change of a reminder, from the oldest to the newest.
+/// Changes are kept after the reminder is deleted.
+pub fn fetch_reminder_changes(reminder_id: i64) -> DatabaseResult<Vec<ReminderChangeData>> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Fetching changes of reminder {reminder_id}");
+
+        let changes: Vec<ReminderChangeData> = reminder_changes_dsl::reminder_changes
+            .filter(reminder_changes_dsl::reminder_id.eq(reminder_id))
+            .select(ReminderChange::as_select())
+            .order_by(reminder_changes_dsl::id.asc())
+            .load(conn)?
+            .into_iter()
+            .map(ReminderChangeData::try_from)
+            .collect::<DatabaseResult<_>>()?;
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Changes fetched: {}", changes.len());
+
+        Ok(changes)
+    })
+}
@@ -0,0 +1,607 @@
+use diesel::{
+    expression::{is_aggregate, AppearsOnTable, BoxableExpression, ValidGrouping},
+    query_builder::{AstPass, QueryFragment, QueryId},
+    sql_types::{Bool, Text},
+    BoolExpressionMethods, EscapeExpressionMethods, Expression, ExpressionMethods, IntoSql,
+    QueryDsl, QueryResult, SelectableExpression, TextExpressionMethods,
+};
+use hkb_date::date::SimpleDate;
+
+use super::{instant_bound, ReminderData, ReminderStatus};
+use crate::database::{
+    schema::reminder_tags::dsl as reminder_tags_dsl,
+    schema::reminders::{self, dsl as reminders_dsl},
+    schema::tags::dsl as tags_dsl,
+    DatabaseBackend,
+};
+
+diesel::define_sql_function!(fn lower(text: Text) -> Text);
+
+pub(super) type BoxedReminderExpression =
+    Box<dyn BoxableExpression<reminders::table, DatabaseBackend, SqlType = Bool>>;
+pub(super) type BoxedReminderQuery<'a, ST> = reminders::BoxedQuery<'a, DatabaseBackend, ST>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReminderQueryOptions {
+    RemindAtGe {
+        date: SimpleDate,
+    },
+    RemindAtLe {
+        date: SimpleDate,
+    },
+    RemindAtBetween {
+        end_date: SimpleDate,
+        start_date: SimpleDate,
+    },
+    WithIds {
+        ids: Vec<i64>,
+    },
+    WithoutIds {
+        ids: Vec<i64>,
+    },
+    WithStatus {
+        status: ReminderStatus,
+    },
+    /// Reminders that have at least one of the tags
+    WithTags {
+        tags: Vec<String>,
+    },
+    /// Reminders that have none of the tags
+    WithoutTags {
+        tags: Vec<String>,
+    },
+    /// Reminders with a note containing the text, ignoring case
+    NoteContains {
+        text: String,
+    },
+    /// Reminders with a note that has words starting with every search term.
+    /// Uses the full-text index when the database has one.
+    Search {
+        text: String,
+    },
+}
+
+impl ReminderQueryOptions {
+    /// Checks the option against a reminder in memory, the way the database would
+    pub fn matches(&self, reminder: &ReminderData) -> bool {
+        match self {
+            ReminderQueryOptions::RemindAtGe { date } => reminder.remind_at >= *date,
+            ReminderQueryOptions::RemindAtLe { date } => reminder.remind_at <= *date,
+            ReminderQueryOptions::RemindAtBetween {
+                end_date,
+                start_date,
+            } => reminder.remind_at >= *start_date && reminder.remind_at <= *end_date,
+            ReminderQueryOptions::WithIds { ids } => ids.contains(&reminder.id),
+            ReminderQueryOptions::WithoutIds { ids } => !ids.contains(&reminder.id),
+            ReminderQueryOptions::WithStatus { status } => reminder.status() == *status,
+            ReminderQueryOptions::WithTags { tags } => {
+                reminder.tags.iter().any(|tag| tags.contains(tag))
+            }
+            ReminderQueryOptions::WithoutTags { tags } => {
+                !reminder.tags.iter().any(|tag| tags.contains(tag))
+            }
+            ReminderQueryOptions::NoteContains { text } => {
+                reminder.note.to_lowercase().contains(&text.to_lowercase())
+            }
+            ReminderQueryOptions::Search { text } => {
+                let words = search_terms(&reminder.note);
+
+                search_terms(text)
+                    .iter()
+                    .all(|term| words.iter().any(|word| word.starts_with(term.as_str())))
+            }
+        }
+    }
+}
+
+/// A filter expression that combines query options.
+///
+/// Example
+/// ```rust
+/// use hkb_core::database::services::reminders::{ReminderFilter, ReminderQueryOptions};
+/// let filter = ReminderFilter::from(ReminderQueryOptions::NoteContains {
+///     text: "milk".to_owned(),
+/// })
+/// .or(ReminderQueryOptions::WithTags {
+///     tags: vec!["groceries".to_owned()],
+/// });
+/// let filter = !filter;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReminderFilter {
+    Is(ReminderQueryOptions),
+    /// Matches when all of the filters match, an empty list matches everything
+    And(Vec<ReminderFilter>),
+    /// Matches when any of the filters match, an empty list matches nothing
+    Or(Vec<ReminderFilter>),
+    Not(Box<ReminderFilter>),
+}
+
+impl ReminderFilter {
+    pub fn and(self, filter: impl Into<ReminderFilter>) -> Self {
+        match self {
+            ReminderFilter::And(mut filters) => {
+                filters.push(filter.into());
+
+                ReminderFilter::And(filters)
+            }
+            _ => ReminderFilter::And(vec![self, filter.into()]),
+        }
+    }
+
+    pub fn or(self, filter: impl Into<ReminderFilter>) -> Self {
+        match self {
+            ReminderFilter::Or(mut filters) => {
+                filters.push(filter.into());
+
+                ReminderFilter::Or(filters)
+            }
+            _ => ReminderFilter::Or(vec![self, filter.into()]),
+        }
+    }
+
+    pub fn matches(&self, reminder: &ReminderData) -> bool {
+        match self {
+            ReminderFilter::Is(option) => option.matches(reminder),
+            ReminderFilter::And(filters) => filters.iter().all(|filter| filter.matches(reminder)),
+            ReminderFilter::Or(filters) => filters.iter().any(|filter| filter.matches(reminder)),
+            ReminderFilter::Not(filter) => !filter.matches(reminder),
+        }
+    }
+
+    /// Whether the filter looks into the notes, which the database cannot do while they are encrypted
+    pub(super) fn reads_note(&self) -> bool {
+        match self {
+            ReminderFilter::Is(option) => matches!(
+                option,
+                ReminderQueryOptions::NoteContains { .. } | ReminderQueryOptions::Search { .. }
+            ),
+            ReminderFilter::And(filters) | ReminderFilter::Or(filters) => {
+                filters.iter().any(ReminderFilter::reads_note)
+            }
+            ReminderFilter::Not(filter) => filter.reads_note(),
+        }
+    }
+
+    pub(super) fn to_expression(&self) -> BoxedReminderExpression {
+        match self {
+            ReminderFilter::Is(option) => option_expression(option),
+            ReminderFilter::And(filters) => filters
+                .iter()
+                .map(|filter| filter.to_expression())
+                .reduce(|left, right| Box::new(left.and(right)))
+                .unwrap_or_else(|| Box::new(true.into_sql::<Bool>())),
+            ReminderFilter::Or(filters) => filters
+                .iter()
+                .map(|filter| filter.to_expression())
+                .reduce(|left, right| Box::new(left.or(right)))
+                .unwrap_or_else(|| Box::new(false.into_sql::<Bool>())),
+            ReminderFilter::Not(filter) => Box::new(diesel::dsl::not(filter.to_expression())),
+        }
+    }
+}
+
+impl std::ops::Not for ReminderFilter {
+    type Output = ReminderFilter;
+
+    fn not(self) -> Self::Output {
+        match self {
+            ReminderFilter::Not(filter) => *filter,
+            _ => ReminderFilter::Not(Box::new(self)),
+        }
+    }
+}
+
+impl From<ReminderQueryOptions> for ReminderFilter {
+    fn from(val: ReminderQueryOptions) -> Self {
+        ReminderFilter::Is(val)
+    }
+}
+
+impl From<Vec<ReminderQueryOptions>> for ReminderFilter {
+    fn from(val: Vec<ReminderQueryOptions>) -> Self {
+        ReminderFilter::And(val.into_iter().map(ReminderFilter::from).collect())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderSortField {
+    Id,
+    RemindAt,
+    CreatedAt,
+    Priority,
+    /// Reminders that are not in the trash sort before the ones that are
+    DeletedAt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReminderSort {
+    pub field: ReminderSortField,
+    pub direction: SortDirection,
+}
+
+/// Whether a query looks at the reminders in the trash
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrashFilter {
+    #[default]
+    Exclude,
+    Include,
+    Only,
+}
+
+impl TrashFilter {
+    pub fn matches(&self, reminder: &ReminderData) -> bool {
+        match self {
+            TrashFilter::Exclude => !reminder.is_deleted(),
+            TrashFilter::Include => true,
+            TrashFilter::Only => reminder.is_deleted(),
+        }
+    }
+
+    fn to_expression(self) -> BoxedReminderExpression {
+        match self {
+            TrashFilter::Exclude => Box::new(reminders_dsl::deleted_at.is_null()),
+            TrashFilter::Include => Box::new(true.into_sql::<Bool>()),
+            TrashFilter::Only => Box::new(reminders_dsl::deleted_at.is_not_null()),
+        }
+    }
+}
+
+/// Which reminders to get and in what order.
+/// Without any sorting more important reminders come first.
+/// Reminders in the trash are left out unless the query asks for them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReminderQuery {
+    pub filter: Option<ReminderFilter>,
+    pub trash: TrashFilter,
+    pub sort: Vec<ReminderSort>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl ReminderQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Narrow down the query, filters are AND-ed together
+    pub fn filter(mut self, filter: impl Into<ReminderFilter>) -> Self {
+        self.filter = Some(match self.filter {
+            Some(current) => current.and(filter),
+            None => filter.into(),
+        });
+
+        self
+    }
+
+    pub fn trash(mut self, trash: TrashFilter) -> Self {
+        self.trash = trash;
+
+        self
+    }
+
+    pub fn sort_by(mut self, field: ReminderSortField, direction: SortDirection) -> Self {
+        self.sort.push(ReminderSort { field, direction });
+
+        self
+    }
+
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.limit = Some(limit);
+
+        self
+    }
+
+    pub fn offset(mut self, offset: i64) -> Self {
+        self.offset = Some(offset);
+
+        self
+    }
+
+    pub fn matches(&self, reminder: &ReminderData) -> bool {
+        self.trash.matches(reminder)
+            && self
+                .filter
+                .as_ref()
+                .map_or(true, |filter| filter.matches(reminder))
+    }
+
+    pub(super) fn reads_note(&self) -> bool {
+        self.filter.as_ref().is_some_and(ReminderFilter::reads_note)
+    }
+
+    /// Filters, sorts and pages reminders in memory, the same way `apply` does in the database
+    pub(super) fn apply_to(&self, reminders: Vec<ReminderData>) -> Vec<ReminderData> {
+        let mut reminders: Vec<ReminderData> = reminders
+            .into_iter()
+            .filter(|reminder| self.matches(reminder))
+            .collect();
+        let default_sort = [ReminderSort {
+            field: ReminderSortField::Priority,
+            direction: SortDirection::Desc,
+        }];
+        let sort = if self.sort.is_empty() {
+            &default_sort[..]
+        } else {
+            &self.sort[..]
+        };
+
+        reminders.sort_by(|a, b| {
+            sort.iter()
+                .map(|sort| {
+                    let ordering = match sort.field {
+                        ReminderSortField::Id => a.id.cmp(&b.id),
+                        ReminderSortField::RemindAt => a.remind_at.cmp(&b.remind_at),
+                        ReminderSortField::CreatedAt => a.created_at.cmp(&b.created_at),
+                        ReminderSortField::Priority => a.priority.cmp(&b.priority),
+                        ReminderSortField::DeletedAt => a.deleted_at.cmp(&b.deleted_at),
+                    };
+
+                    match sort.direction {
+                        SortDirection::Asc => ordering,
+                        SortDirection::Desc => ordering.reverse(),
+                    }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| a.id.cmp(&b.id))
+        });
+
+        reminders
+            .into_iter()
+            .skip(self.offset.unwrap_or_default().max(0) as usize)
+            .take(self.limit.map_or(usize::MAX, |limit| limit.max(0) as usize))
+            .collect()
+    }
+
+    pub(super) fn filter_expression(&self) -> BoxedReminderExpression {
+        match &self.filter {
+            Some(filter) => Box::new(self.trash.to_expression().and(filter.to_expression())),
+            None => self.trash.to_expression(),
+        }
+    }
+
+    pub(super) fn apply<'a, ST>(
+        &self,
+        query: BoxedReminderQuery<'a, ST>,
+    ) -> BoxedReminderQuery<'a, ST> {
+        let mut query = query.filter(self.filter_expression());
+
+        if self.sort.is_empty() {
+            query = query.then_order_by(reminders_dsl::priority.desc());
+        }
+
+        for sort in self.sort.iter() {
+            query = match (sort.field, sort.direction) {
+                (ReminderSortField::Id, SortDirection::Asc) => {
+                    query.then_order_by(reminders_dsl::id.asc())
+                }
+                (ReminderSortField::Id, SortDirection::Desc) => {
+                    query.then_order_by(reminders_dsl::id.desc())
+                }
+                (ReminderSortField::RemindAt, SortDirection::Asc) => {
+                    query.then_order_by(reminders_dsl::remind_at.asc())
+                }
+                (ReminderSortField::RemindAt, SortDirection::Desc) => {
+                    query.then_order_by(reminders_dsl::remind_at.desc())
+                }
+                (ReminderSortField::CreatedAt, SortDirection::Asc) => {
+                    query.then_order_by(reminders_dsl::created_at.asc())
+                }
+                (ReminderSortField::CreatedAt, SortDirection::Desc) => {
+                    query.then_order_by(reminders_dsl::created_at.desc())
+                }
+                (ReminderSortField::Priority, SortDirection::Asc) => {
+                    query.then_order_by(reminders_dsl::priority.asc())
+                }
+                (ReminderSortField::Priority, SortDirection::Desc) => {
+                    query.then_order_by(reminders_dsl::priority.desc())
+                }
+                // backends disagree on where nulls go, so they are ordered first explicitly
+                (ReminderSortField::DeletedAt, SortDirection::Asc) => query
+                    .then_order_by(reminders_dsl::deleted_at.is_not_null().asc())
+                    .then_order_by(reminders_dsl::deleted_at.asc()),
+                (ReminderSortField::DeletedAt, SortDirection::Desc) => query
+                    .then_order_by(reminders_dsl::deleted_at.is_not_null().desc())
+                    .then_order_by(reminders_dsl::deleted_at.desc()),
+            };
+        }
+
+        // keeps the order stable when paging through equal values
+        query = query.then_order_by(reminders_dsl::id.asc());
+
+        if let Some(limit) = self.limit {
+            query = query.limit(limit);
+        }
+
+        if let Some(offset) = self.offset {
+            // sqlite and mysql only accept an offset after a limit
+            if self.limit.is_none() {
+                query = query.limit(i64::MAX);
+            }
+
+            query = query.offset(offset);
+        }
+
+        query
+    }
+}
+
+impl From<Vec<ReminderQueryOptions>> for ReminderQuery {
+    fn from(val: Vec<ReminderQueryOptions>) -> Self {
+        ReminderQuery::new().filter(val)
+    }
+}
+
+/// Splits a search into the terms that are matched against the words of a note
+pub fn search_terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| term.to_lowercase())
+        .collect()
+}
+
+/// Matches the reminders with a word in their note starting with every term.
+/// The backend is only known once the query runs, so the sql is picked then.
+#[derive(Debug, Clone)]
+struct NoteSearch {
+    /// terms for the fts5 index of sqlite, quoted so words like "and" or "near" are not operators
+    #[cfg(feature = "sqlite-database")]
+    fts_pattern: String,
+    /// the same for the `tsvector` index of postgres
+    #[cfg(feature = "postgres-database")]
+    ts_query: String,
+    /// mysql has no index and looks for the terms anywhere in the note
+    #[cfg(feature = "mysql-database")]
+    like_patterns: Vec<String>,
+}
+
+impl NoteSearch {
+    fn new(terms: Vec<String>) -> Self {
+        #[allow(unused_variables)]
+        let join = |pattern: fn(&String) -> String, separator| {
+            terms
+                .iter()
+                .map(pattern)
+                .collect::<Vec<String>>()
+                .join(separator)
+        };
+
+        Self {
+            #[cfg(feature = "sqlite-database")]
+            fts_pattern: join(|term| format!("\"{term}\"*"), " "),
+            #[cfg(feature = "postgres-database")]
+            ts_query: join(|term| format!("{term}:*"), " & "),
+            #[cfg(feature = "mysql-database")]
+            like_patterns: terms.iter().map(|term| format!("%{term}%")).collect(),
+        }
+    }
+}
+
+impl Expression for NoteSearch {
+    type SqlType = Bool;
+}
+
+impl AppearsOnTable<reminders::table> for NoteSearch {}
+
+impl SelectableExpression<reminders::table> for NoteSearch {}
+
+impl<GB> ValidGrouping<GB> for NoteSearch {
+    type IsAggregate = is_aggregate::Never;
+}
+
+impl QueryId for NoteSearch {
+    type QueryId = ();
+
+    const HAS_STATIC_QUERY_ID: bool = false;
+}
+
+impl QueryFragment<DatabaseBackend> for NoteSearch {
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, DatabaseBackend>) -> QueryResult<()> {
+        match out.backend() {
+            #[cfg(feature = "sqlite-database")]
+            DatabaseBackend::Sqlite(_) => {
+                out.push_sql(
+                    "reminders.id IN (SELECT rowid FROM reminders_search WHERE reminders_search MATCH ",
+                );
+                out.push_bind_param::<Text, _>(&self.fts_pattern)?;
+                out.push_sql(")");
+            }
+            #[cfg(feature = "mysql-database")]
+            DatabaseBackend::Mysql(_) => {
+                for (i, pattern) in self.like_patterns.iter().enumerate() {
+                    if i > 0 {
+                        out.push_sql(" AND ");
+                    }
+
+                    out.push_sql("reminders.note LIKE ");
+                    out.push_bind_param::<Text, _>(pattern)?;
+                }
+            }
+            #[cfg(feature = "postgres-database")]
+            DatabaseBackend::Postgres(_) => {
+                out.push_sql("to_tsvector('simple', reminders.note) @@ to_tsquery('simple', ");
+                out.push_bind_param::<Text, _>(&self.ts_query)?;
+                out.push_sql(")");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn search_expression(terms: Vec<String>) -> BoxedReminderExpression {
+    if terms.is_empty() {
+        return Box::new(true.into_sql::<Bool>());
+    }
+
+    Box::new(NoteSearch::new(terms))
+}
+
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn option_expression(option: &ReminderQueryOptions) -> BoxedReminderExpression {
+    match option {
+        ReminderQueryOptions::RemindAtBetween {
+            end_date,
+            start_date,
+        } => Box::new(
+            reminders_dsl::remind_at.between(instant_bound(start_date), instant_bound(end_date)),
+        ),
+        ReminderQueryOptions::RemindAtGe { date } => {
+            Box::new(reminders_dsl::remind_at.ge(instant_bound(date)))
+        }
+        ReminderQueryOptions::RemindAtLe { date } => {
+            Box::new(reminders_dsl::remind_at.le(instant_bound(date)))
+        }
+        ReminderQueryOptions::WithIds { ids } => Box::new(reminders_dsl::id.eq_any(ids.clone())),
+        ReminderQueryOptions::WithoutIds { ids } => {
+            Box::new(diesel::dsl::not(reminders_dsl::id.eq_any(ids.clone())))
+        }
+        ReminderQueryOptions::WithStatus { status } => match status {
+            ReminderStatus::Pending => Box::new(
+                reminders_dsl::completed_at
+                    .is_null()
+                    .and(reminders_dsl::dismissed_at.is_null()),
+            ),
+            ReminderStatus::Completed => Box::new(reminders_dsl::completed_at.is_not_null()),
+            ReminderStatus::Dismissed => Box::new(
+                reminders_dsl::completed_at
+                    .is_null()
+                    .and(reminders_dsl::dismissed_at.is_not_null()),
+            ),
+        },
+        ReminderQueryOptions::WithTags { tags } => Box::new(
+            reminders_dsl::id.eq_any(
+                reminder_tags_dsl::reminder_tags
+                    .inner_join(tags_dsl::tags)
+                    .filter(tags_dsl::name.eq_any(tags.clone()))
+                    .select(reminder_tags_dsl::reminder_id),
+            ),
+        ),
+        ReminderQueryOptions::WithoutTags { tags } => Box::new(diesel::dsl::not(
+            reminders_dsl::id.eq_any(
+                reminder_tags_dsl::reminder_tags
+                    .inner_join(tags_dsl::tags)
+                    .filter(tags_dsl::name.eq_any(tags.clone()))
+                    .select(reminder_tags_dsl::reminder_id),
+            ),
+        )),
+        // postgres compares case sensitively, the others only ignore case for ascii
+        ReminderQueryOptions::NoteContains { text } => Box::new(
+            lower(reminders_dsl::note)
+                .like(format!("%{}%", escape_like(&text.to_lowercase())))
+                .escape('\\'),
+        ),
+        ReminderQueryOptions::Search { text } => search_expression(search_terms(text)),
+    }
+}
@@ -0,0 +1,1133 @@
+use diesel::result::Error as DieselResultError;
+use hkb_date::date::SimpleDate;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{
+    apply_checklist_edit, find_next_occurrence, new_uuid, normalize_checklist, normalize_tags,
+    unchecked, AlertOffset, ChecklistEdit, ChecklistItemData, CreateReminderData, DueAlertData,
+    ReminderChangeData, ReminderChangeKind, ReminderData, ReminderOperation, ReminderQuery,
+    ReminderStatus, UpdateReminderData, DUE_ALERT_GRACE_PERIOD,
+};
+use crate::database::{DatabaseError, DatabaseResult};
+
+/// Where reminders are kept.
+/// Lets the client and the daemon work with the database or with reminders kept in memory, e.g. in tests.
+pub trait ReminderRepository {
+    fn query_reminders(&self, query: &ReminderQuery) -> DatabaseResult<Vec<ReminderData>>;
+
+    /// Count the reminders matching the filter of the query, its paging is ignored
+    fn count_reminders(&self, query: &ReminderQuery) -> DatabaseResult<i64>;
+
+    fn fetch_reminder(&self, id: i64) -> DatabaseResult<ReminderData>;
+
+    fn create_reminder(&self, reminder: CreateReminderData) -> DatabaseResult<ReminderData>;
+
+    fn update_reminder(&self, reminder: UpdateReminderData) -> DatabaseResult<ReminderData>;
+
+    /// Move a reminder to the trash, see [`super::delete_reminder`]
+    fn delete_reminder(&self, id: i64) -> DatabaseResult<()>;
+
+    /// Take a reminder back out of the trash
+    fn restore_deleted_reminder(&self, id: i64) -> DatabaseResult<ReminderData>;
+
+    /// Remove a reminder for good
+    fn purge_reminder(&self, id: i64) -> DatabaseResult<()>;
+
+    /// See [`super::purge_deleted_reminders`]
+    fn purge_deleted_reminders(&self, date: SimpleDate) -> DatabaseResult<usize>;
+
+    /// Push a reminder back to `remind_at`, see [`super::snooze_reminder`]
+    fn snooze_reminder(&self, id: i64, remind_at: SimpleDate) -> DatabaseResult<ReminderData>;
+
+    /// Mark a reminder as done, see [`super::complete_reminder`]
+    fn complete_reminder(&self, id: i64) -> DatabaseResult<ReminderData>;
+
+    /// See [`super::schedule_next_occurrences`]
+    fn schedule_next_occurrences(&self, date: SimpleDate) -> DatabaseResult<Vec<ReminderData>>;
+
+    /// See [`super::dismiss_overdue_reminders`]
+    fn dismiss_overdue_reminders(&self, date: SimpleDate) -> DatabaseResult<usize>;
+
+    /// See [`super::take_due_alerts`]
+    fn take_due_alerts(&self, date: SimpleDate) -> DatabaseResult<Vec<DueAlertData>>;
+
+    /// Apply the operations all at once or not at all, see [`super::apply_reminder_batch`]
+    fn apply_reminder_batch(
+        &self,
+        operations: Vec<ReminderOperation>,
+    ) -> DatabaseResult<Vec<ReminderChangeData>>;
+
+    /// See [`super::fetch_reminder_changes`]
+    fn fetch_reminder_changes(&self, reminder_id: i64) -> DatabaseResult<Vec<ReminderChangeData>>;
+
+    /// Add, check, move or remove an item of the checklist of a reminder
+    fn edit_reminder_checklist(
+        &self,
+        reminder_id: i64,
+        edit: ChecklistEdit,
+    ) -> DatabaseResult<ReminderData>;
+}
+
+/// Reminders kept in the database set up with `init_database`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DieselReminderRepository;
+
+impl ReminderRepository for DieselReminderRepository {
+    fn query_reminders(&self, query: &ReminderQuery) -> DatabaseResult<Vec<ReminderData>> {
+        super::query_reminders(query)
+    }
+
+    fn count_reminders(&self, query: &ReminderQuery) -> DatabaseResult<i64> {
+        super::count_reminders(query)
+    }
+
+    fn fetch_reminder(&self, id: i64) -> DatabaseResult<ReminderData> {
+        super::fetch_reminder(id)
+    }
+
+    fn create_reminder(&self, reminder: CreateReminderData) -> DatabaseResult<ReminderData> {
+        super::create_reminder(reminder)
+    }
+
+    fn update_reminder(&self, reminder: UpdateReminderData) -> DatabaseResult<ReminderData> {
+        super::update_reminder(reminder)
+    }
+
+    fn delete_reminder(&self, id: i64) -> DatabaseResult<()> {
+        super::delete_reminder(id)
+    }
+
+    fn restore_deleted_reminder(&self, id: i64) -> DatabaseResult<ReminderData> {
+        super::restore_deleted_reminder(id)
+    }
+
+    fn purge_reminder(&self, id: i64) -> DatabaseResult<()> {
+        super::purge_reminder(id)
+    }
+
+    fn purge_deleted_reminders(&self, date: SimpleDate) -> DatabaseResult<usize> {
+        super::purge_deleted_reminders(date)
+    }
+
+    fn snooze_reminder(&self, id: i64, remind_at: SimpleDate) -> DatabaseResult<ReminderData> {
+        super::snooze_reminder(id, remind_at)
+    }
+
+    fn complete_reminder(&self, id: i64) -> DatabaseResult<ReminderData> {
+        super::complete_reminder(id)
+    }
+
+    fn schedule_next_occurrences(&self, date: SimpleDate) -> DatabaseResult<Vec<ReminderData>> {
+        super::schedule_next_occurrences(date)
+    }
+
+    fn dismiss_overdue_reminders(&self, date: SimpleDate) -> DatabaseResult<usize> {
+        super::dismiss_overdue_reminders(date)
+    }
+
+    fn take_due_alerts(&self, date: SimpleDate) -> DatabaseResult<Vec<DueAlertData>> {
+        super::take_due_alerts(date)
+    }
+
+    fn apply_reminder_batch(
+        &self,
+        operations: Vec<ReminderOperation>,
+    ) -> DatabaseResult<Vec<ReminderChangeData>> {
+        super::apply_reminder_batch(operations)
+    }
+
+    fn fetch_reminder_changes(&self, reminder_id: i64) -> DatabaseResult<Vec<ReminderChangeData>> {
+        super::fetch_reminder_changes(reminder_id)
+    }
+
+    fn edit_reminder_checklist(
+        &self,
+        reminder_id: i64,
+        edit: ChecklistEdit,
+    ) -> DatabaseResult<ReminderData> {
+        super::edit_reminder_checklist(reminder_id, edit)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct InMemoryReminders {
+    last_id: i64,
+    reminders: Vec<ReminderData>,
+    /// The `remind_at` an alert last fired for, by reminder id and offset
+    fired_alerts: HashMap<(i64, AlertOffset), SimpleDate>,
+    last_change_id: i64,
+    changes: Vec<ReminderChangeData>,
+    last_checklist_item_id: i64,
+}
+
+impl InMemoryReminders {
+    fn find_mut(&mut self, id: i64) -> DatabaseResult<&mut ReminderData> {
+        self.reminders
+            .iter_mut()
+            .find(|reminder| reminder.id == id)
+            .ok_or(DatabaseError::FailedToFetchResult(
+                DieselResultError::NotFound,
+            ))
+    }
+
+    /// Items the reminder does not have yet keep their ids when no other item has them,
+    /// like the database does
+    fn checklist_with_ids(
+        &mut self,
+        reminder_id: i64,
+        items: &[ChecklistItemData],
+    ) -> Vec<ChecklistItemData> {
+        let mut checklist = normalize_checklist(items);
+        let existing_ids: Vec<(i64, i64)> = self
+            .reminders
+            .iter()
+            .flat_map(|reminder| reminder.checklist.iter().map(|item| (reminder.id, item.id)))
+            .collect();
+
+        for item in checklist.iter_mut() {
+            let owner = existing_ids
+                .iter()
+                .find(|(_, id)| *id == item.id)
+                .map(|(owner, _)| *owner);
+
+            if owner == Some(reminder_id) {
+                continue;
+            }
+
+            if item.id <= 0 || owner.is_some() {
+                self.last_checklist_item_id += 1;
+                item.id = self.last_checklist_item_id;
+            } else {
+                self.last_checklist_item_id = self.last_checklist_item_id.max(item.id);
+            }
+        }
+
+        checklist
+    }
+
+    /// A uuid another reminder has is replaced by a new one, like the database does
+    fn available_uuid(&self, uuid: &str) -> String {
+        if uuid.is_empty() || self.reminders.iter().any(|reminder| reminder.uuid == uuid) {
+            new_uuid()
+        } else {
+            uuid.to_owned()
+        }
+    }
+
+    fn insert(&mut self, mut reminder: ReminderData) -> ReminderData {
+        self.last_id += 1;
+
+        reminder.id = self.last_id;
+        reminder.uuid = new_uuid();
+        reminder.tags = sorted_tags(&reminder.tags);
+        reminder.alerts = sorted_alerts(&reminder.alerts);
+        reminder.checklist = self.checklist_with_ids(reminder.id, &reminder.checklist);
+        self.reminders.push(reminder.clone());
+
+        reminder
+    }
+
+    fn record(
+        &mut self,
+        kind: ReminderChangeKind,
+        before: Option<ReminderData>,
+        after: Option<ReminderData>,
+    ) -> ReminderChangeData {
+        self.last_change_id += 1;
+
+        let change = ReminderChangeData {
+            id: self.last_change_id,
+            reminder_id: after.as_ref().or(before.as_ref()).map_or(0, |r| r.id),
+            kind,
+            before,
+            after,
+            changed_at: SimpleDate::local(),
+        };
+        self.changes.push(change.clone());
+
+        change
+    }
+
+    fn create(&mut self, reminder: CreateReminderData) -> Vec<ReminderChangeData> {
+        let created_reminder = self.insert(ReminderData {
+            id: 0,
+            uuid: String::new(),
+            note: reminder.note,
+            remind_at: reminder.remind_at,
+            created_at: SimpleDate::local(),
+            recurrence: reminder.recurrence,
+            occurrence: 1,
+            original_remind_at: None,
+            snooze_count: 0,
+            completed_at: None,
+            dismissed_at: None,
+            tags: reminder.tags,
+            priority: reminder.priority,
+            alerts: reminder.alerts,
+            deleted_at: None,
+            checklist: reminder.checklist,
+            timezone: super::current_timezone(),
+        });
+
+        vec![self.record(ReminderChangeKind::Create, None, Some(created_reminder))]
+    }
+
+    fn update(&mut self, update: UpdateReminderData) -> DatabaseResult<Vec<ReminderChangeData>> {
+        let checklist = update
+            .checklist
+            .map(|checklist| self.checklist_with_ids(update.id, &checklist));
+        let reminder = self.find_mut(update.id)?;
+        let before = reminder.clone();
+
+        if let Some(note) = update.note {
+            reminder.note = note;
+        }
+
+        if let Some(remind_at) = update.remind_at {
+            reminder.remind_at = remind_at;
+        }
+
+        if let Some(recurrence) = update.recurrence {
+            reminder.recurrence = recurrence;
+        }
+
+        if let Some(priority) = update.priority {
+            reminder.priority = priority;
+        }
+
+        if let Some(tags) = update.tags {
+            reminder.tags = sorted_tags(&tags);
+        }
+
+        if let Some(alerts) = update.alerts {
+            reminder.alerts = sorted_alerts(&alerts);
+        }
+
+        if let Some(checklist) = checklist {
+            reminder.checklist = checklist;
+        }
+
+        let reminder = reminder.clone();
+
+        self.forget_removed_alerts(&reminder);
+
+        Ok(vec![self.record(
+            ReminderChangeKind::Update,
+            Some(before),
+            Some(reminder),
+        )])
+    }
+
+    /// Alerts that stay remember if they already fired
+    fn forget_removed_alerts(&mut self, reminder: &ReminderData) {
+        self.fired_alerts
+            .retain(|(id, offset), _| *id != reminder.id || reminder.alerts.contains(offset));
+    }
+
+    fn delete(&mut self, id: i64) -> Vec<ReminderChangeData> {
+        let Some(reminder) = self
+            .reminders
+            .iter_mut()
+            .find(|reminder| reminder.id == id && !reminder.is_deleted())
+        else {
+            return vec![];
+        };
+        let before = reminder.clone();
+
+        reminder.deleted_at = Some(SimpleDate::local());
+
+        let reminder = reminder.clone();
+
+        vec![self.record(ReminderChangeKind::Delete, Some(before), Some(reminder))]
+    }
+
+    fn restore(&mut self, id: i64) -> DatabaseResult<Vec<ReminderChangeData>> {
+        let reminder = self.find_mut(id)?;
+
+        if !reminder.is_deleted() {
+            return Ok(vec![]);
+        }
+
+        let before = reminder.clone();
+
+        reminder.deleted_at = None;
+
+        let reminder = reminder.clone();
+
+        Ok(vec![self.record(
+            ReminderChangeKind::Update,
+            Some(before),
+            Some(reminder),
+        )])
+    }
+
+    fn purge(&mut self, id: i64) -> Vec<ReminderChangeData> {
+        let Some(position) = self.reminders.iter().position(|reminder| reminder.id == id) else {
+            return vec![];
+        };
+        let reminder = self.reminders.remove(position);
+
+        self.fired_alerts
+            .retain(|(reminder_id, _), _| *reminder_id != id);
+
+        vec![self.record(ReminderChangeKind::Purge, Some(reminder), None)]
+    }
+
+    fn snooze(
+        &mut self,
+        id: i64,
+        remind_at: SimpleDate,
+    ) -> DatabaseResult<Vec<ReminderChangeData>> {
+        let reminder = self.find_mut(id)?;
+        let before = reminder.clone();
+
+        reminder.original_remind_at =
+            Some(reminder.original_remind_at.unwrap_or(reminder.remind_at));
+        reminder.remind_at = remind_at;
+        reminder.snooze_count += 1;
+
+        let reminder = reminder.clone();
+
+        Ok(vec![self.record(
+            ReminderChangeKind::Update,
+            Some(before),
+            Some(reminder),
+        )])
+    }
+
+    fn complete(&mut self, id: i64) -> DatabaseResult<Vec<ReminderChangeData>> {
+        let reminder = self.find_mut(id)?;
+        let before = reminder.clone();
+        let completed_at = SimpleDate::local();
+        let occurrence_date = reminder.original_remind_at.unwrap_or(reminder.remind_at);
+        let next_occurrence =
+            find_next_occurrence(reminder, std::cmp::max(completed_at, occurrence_date));
+
+        let Some((remind_at, occurrence)) = next_occurrence else {
+            reminder.completed_at = Some(completed_at);
+
+            let reminder = reminder.clone();
+
+            return Ok(vec![self.record(
+                ReminderChangeKind::Update,
+                Some(before),
+                Some(reminder),
+            )]);
+        };
+
+        // the handled occurrence is kept as a completed copy, without its alerts
+        let completed_occurrence = ReminderData {
+            recurrence: None,
+            original_remind_at: None,
+            snooze_count: 0,
+            completed_at: Some(completed_at),
+            alerts: vec![],
+            ..reminder.clone()
+        };
+
+        reminder.remind_at = remind_at;
+        reminder.occurrence = occurrence;
+        reminder.original_remind_at = None;
+        reminder.snooze_count = 0;
+        reminder.checklist = unchecked(&reminder.checklist);
+
+        let scheduled_reminder = reminder.clone();
+        let completed_reminder = self.insert(completed_occurrence);
+
+        Ok(vec![
+            self.record(
+                ReminderChangeKind::Update,
+                Some(before),
+                Some(scheduled_reminder),
+            ),
+            self.record(ReminderChangeKind::Create, None, Some(completed_reminder)),
+        ])
+    }
+
+    fn put(&mut self, mut reminder: ReminderData) -> Vec<ReminderChangeData> {
+        reminder.tags = sorted_tags(&reminder.tags);
+        reminder.alerts = sorted_alerts(&reminder.alerts);
+        reminder.checklist = self.checklist_with_ids(reminder.id, &reminder.checklist);
+
+        let before = match self.find_mut(reminder.id) {
+            Ok(existing_reminder) => {
+                reminder.uuid = existing_reminder.uuid.clone();
+
+                Some(std::mem::replace(existing_reminder, reminder.clone()))
+            }
+            Err(_) => {
+                reminder.uuid = self.available_uuid(&reminder.uuid);
+                self.last_id = self.last_id.max(reminder.id);
+                self.reminders.push(reminder.clone());
+
+                None
+            }
+        };
+        let kind = if before.is_some() {
+            ReminderChangeKind::Update
+        } else {
+            ReminderChangeKind::Create
+        };
+
+        self.forget_removed_alerts(&reminder);
+
+        vec![self.record(kind, before, Some(reminder))]
+    }
+
+    fn edit_checklist(
+        &mut self,
+        id: i64,
+        edit: ChecklistEdit,
+    ) -> DatabaseResult<Vec<ReminderChangeData>> {
+        let mut checklist = self.find_mut(id)?.checklist.clone();
+
+        apply_checklist_edit(&mut checklist, edit)?;
+
+        let checklist = self.checklist_with_ids(id, &checklist);
+        let reminder = self.find_mut(id)?;
+        let before = reminder.clone();
+
+        reminder.checklist = checklist;
+
+        let reminder = reminder.clone();
+
+        Ok(vec![self.record(
+            ReminderChangeKind::Update,
+            Some(before),
+            Some(reminder),
+        )])
+    }
+
+    fn apply(&mut self, operation: ReminderOperation) -> DatabaseResult<Vec<ReminderChangeData>> {
+        match operation {
+            ReminderOperation::Create(reminder) => Ok(self.create(reminder)),
+            ReminderOperation::Update(reminder) => self.update(reminder),
+            ReminderOperation::Delete(id) => Ok(self.delete(id)),
+            ReminderOperation::Snooze(id, remind_at) => self.snooze(id, remind_at),
+            ReminderOperation::Complete(id) => self.complete(id),
+            ReminderOperation::Restore(id) => self.restore(id),
+            ReminderOperation::Purge(id) => Ok(self.purge(id)),
+            ReminderOperation::Put(reminder) => Ok(self.put(reminder)),
+            ReminderOperation::EditChecklist(id, edit) => self.edit_checklist(id, edit),
+        }
+    }
+}
+
+/// The reminder as the last of the changes left it
+fn last_changed_reminder(changes: Vec<ReminderChangeData>) -> ReminderData {
+    // every operation returning a reminder records a change with it
+    changes
+        .into_iter()
+        .last()
+        .and_then(|change| change.after)
+        .unwrap()
+}
+
+/// Tags are loaded by name from the database
+fn sorted_tags(tags: &[String]) -> Vec<String> {
+    let mut tags = normalize_tags(tags);
+    tags.sort();
+
+    tags
+}
+
+/// Alerts are loaded from the earliest to the latest from the database
+fn sorted_alerts(alerts: &[AlertOffset]) -> Vec<AlertOffset> {
+    let mut alerts = alerts.to_vec();
+    alerts.sort_by(|a, b| b.cmp(a));
+    alerts.dedup();
+
+    alerts
+}
+
+/// Reminders kept in memory, behaving like the database does.
+/// Clones share the same reminders.
+///
+/// Example
+/// ```rust
+/// use hkb_core::database::services::reminders::{
+///     fakes, CreateReminderData, InMemoryReminderRepository, ReminderQuery, ReminderRepository,
+/// };
+/// let repository = InMemoryReminderRepository::new();
+/// let reminder = fakes::create_reminder();
+///
+/// repository
+///     .create_reminder(CreateReminderData {
+///         note: reminder.note,
+///         remind_at: reminder.remind_at,
+///         recurrence: None,
+///         tags: vec![],
+///         priority: reminder.priority,
+///         alerts: vec![],
+///         checklist: vec![],
+///     })
+///     .unwrap();
+///
+/// assert_eq!(1, repository.count_reminders(&ReminderQuery::new()).unwrap());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct InMemoryReminderRepository {
+    reminders: Arc<Mutex<InMemoryReminders>>,
+}
+
+impl InMemoryReminderRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ReminderRepository for InMemoryReminderRepository {
+    fn query_reminders(&self, query: &ReminderQuery) -> DatabaseResult<Vec<ReminderData>> {
+        let reminders = self.reminders.lock();
+
+        Ok(query.apply_to(reminders.reminders.clone()))
+    }
+
+    fn count_reminders(&self, query: &ReminderQuery) -> DatabaseResult<i64> {
+        let reminders = self.reminders.lock();
+        let count = reminders
+            .reminders
+            .iter()
+            .filter(|reminder| query.matches(reminder))
+            .count();
+
+        Ok(count as i64)
+    }
+
+    fn fetch_reminder(&self, id: i64) -> DatabaseResult<ReminderData> {
+        let mut reminders = self.reminders.lock();
+
+        reminders.find_mut(id).map(|reminder| reminder.clone())
+    }
+
+    fn create_reminder(&self, reminder: CreateReminderData) -> DatabaseResult<ReminderData> {
+        let mut reminders = self.reminders.lock();
+
+        Ok(last_changed_reminder(reminders.create(reminder)))
+    }
+
+    fn update_reminder(&self, update: UpdateReminderData) -> DatabaseResult<ReminderData> {
+        let mut reminders = self.reminders.lock();
+
+        Ok(last_changed_reminder(reminders.update(update)?))
+    }
+
+    fn delete_reminder(&self, id: i64) -> DatabaseResult<()> {
+        let mut reminders = self.reminders.lock();
+
+        reminders.delete(id);
+
+        Ok(())
+    }
+
+    fn restore_deleted_reminder(&self, id: i64) -> DatabaseResult<ReminderData> {
+        let mut reminders = self.reminders.lock();
+
+        reminders.restore(id)?;
+        reminders.find_mut(id).map(|reminder| reminder.clone())
+    }
+
+    fn purge_reminder(&self, id: i64) -> DatabaseResult<()> {
+        let mut reminders = self.reminders.lock();
+
+        reminders.purge(id);
+
+        Ok(())
+    }
+
+    fn purge_deleted_reminders(&self, date: SimpleDate) -> DatabaseResult<usize> {
+        let mut reminders = self.reminders.lock();
+        let ids: Vec<i64> = reminders
+            .reminders
+            .iter()
+            .filter(|reminder| {
+                reminder
+                    .deleted_at
+                    .is_some_and(|deleted_at| deleted_at <= date)
+            })
+            .map(|reminder| reminder.id)
+            .collect();
+
+        Ok(ids.into_iter().map(|id| reminders.purge(id).len()).sum())
+    }
+
+    fn snooze_reminder(&self, id: i64, remind_at: SimpleDate) -> DatabaseResult<ReminderData> {
+        let mut reminders = self.reminders.lock();
+
+        Ok(last_changed_reminder(reminders.snooze(id, remind_at)?))
+    }
+
+    fn complete_reminder(&self, id: i64) -> DatabaseResult<ReminderData> {
+        let mut reminders = self.reminders.lock();
+
+        Ok(last_changed_reminder(reminders.complete(id)?))
+    }
+
+    fn schedule_next_occurrences(&self, date: SimpleDate) -> DatabaseResult<Vec<ReminderData>> {
+        let mut reminders = self.reminders.lock();
+        let mut scheduled_reminders = vec![];
+
+        for reminder in reminders.reminders.iter_mut() {
+            if reminder.recurrence.is_none()
+                || reminder.is_deleted()
+                || reminder.status() != ReminderStatus::Pending
+                || reminder.remind_at > date
+            {
+                continue;
+            }
+
+            let before = reminder.clone();
+
+            match find_next_occurrence(reminder, date) {
+                Some((remind_at, occurrence)) => {
+                    reminder.remind_at = remind_at;
+                    reminder.occurrence = occurrence;
+                    reminder.original_remind_at = None;
+                    reminder.snooze_count = 0;
+                }
+                None => reminder.recurrence = None,
+            }
+
+            scheduled_reminders.push((before, reminder.clone()));
+        }
+
+        Ok(scheduled_reminders
+            .into_iter()
+            .map(|(before, scheduled_reminder)| {
+                reminders.record(
+                    ReminderChangeKind::Update,
+                    Some(before),
+                    Some(scheduled_reminder.clone()),
+                );
+
+                scheduled_reminder
+            })
+            .collect())
+    }
+
+    fn dismiss_overdue_reminders(&self, date: SimpleDate) -> DatabaseResult<usize> {
+        let mut reminders = self.reminders.lock();
+        let dismissed_at = SimpleDate::local();
+        let mut dismissed_reminders = vec![];
+
+        for reminder in reminders.reminders.iter_mut() {
+            if reminder.status() == ReminderStatus::Pending
+                && !reminder.is_deleted()
+                && reminder.remind_at <= date
+            {
+                let before = reminder.clone();
+
+                reminder.dismissed_at = Some(dismissed_at);
+                dismissed_reminders.push((before, reminder.clone()));
+            }
+        }
+
+        let dismissed = dismissed_reminders.len();
+
+        for (before, dismissed_reminder) in dismissed_reminders {
+            reminders.record(
+                ReminderChangeKind::Update,
+                Some(before),
+                Some(dismissed_reminder),
+            );
+        }
+
+        Ok(dismissed)
+    }
+
+    fn take_due_alerts(&self, date: SimpleDate) -> DatabaseResult<Vec<DueAlertData>> {
+        let mut reminders = self.reminders.lock();
+        let oldest_remind_at = date.sub_duration(DUE_ALERT_GRACE_PERIOD).unwrap();
+        let mut due_reminders: Vec<ReminderData> = reminders
+            .reminders
+            .iter()
+            .filter(|reminder| {
+                reminder.status() == ReminderStatus::Pending
+                    && !reminder.is_deleted()
+                    && reminder.remind_at >= oldest_remind_at
+            })
+            .cloned()
+            .collect();
+        let mut due_alerts = vec![];
+
+        due_reminders.sort_by_key(|reminder| reminder.id);
+
+        for reminder in due_reminders {
+            // from the latest alert to the earliest, so the latest due alert comes first
+            let mut offsets = reminder.alerts.clone();
+            offsets.sort();
+
+            let mut due_offset = None;
+
+            for offset in offsets {
+                let key = (reminder.id, offset);
+
+                if reminders.fired_alerts.get(&key) == Some(&reminder.remind_at)
+                    || offset.alert_at(reminder.remind_at) > date
+                {
+                    continue;
+                }
+
+                reminders.fired_alerts.insert(key, reminder.remind_at);
+                due_offset = due_offset.or(Some(offset));
+            }
+
+            if let Some(offset) = due_offset {
+                due_alerts.push(DueAlertData { reminder, offset });
+            }
+        }
+
+        Ok(due_alerts)
+    }
+
+    /// The operations are applied to a copy of the reminders,
+    /// which replaces them once every operation succeeded
+    fn apply_reminder_batch(
+        &self,
+        operations: Vec<ReminderOperation>,
+    ) -> DatabaseResult<Vec<ReminderChangeData>> {
+        let mut reminders = self.reminders.lock();
+        let mut staged_reminders = reminders.clone();
+        let mut changes = vec![];
+
+        for operation in operations {
+            changes.extend(staged_reminders.apply(operation)?);
+        }
+
+        *reminders = staged_reminders;
+
+        Ok(changes)
+    }
+
+    fn fetch_reminder_changes(&self, reminder_id: i64) -> DatabaseResult<Vec<ReminderChangeData>> {
+        let reminders = self.reminders.lock();
+
+        Ok(reminders
+            .changes
+            .iter()
+            .filter(|change| change.reminder_id == reminder_id)
+            .cloned()
+            .collect())
+    }
+
+    fn edit_reminder_checklist(
+        &self,
+        reminder_id: i64,
+        edit: ChecklistEdit,
+    ) -> DatabaseResult<ReminderData> {
+        let mut reminders = self.reminders.lock();
+
+        Ok(last_changed_reminder(
+            reminders.edit_checklist(reminder_id, edit)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::{clear_test_database, init_database, test_database_url, CORE_MIGRATIONS};
+    use ctor::ctor;
+    use hkb_date::duration::Duration;
+    use hkb_date::recurrence::{Frequency, Recurrence};
+    use serial_test::serial;
+
+    use super::*;
+    use crate::database::services::reminders::{
+        ReminderFilter, ReminderPriority, ReminderQueryOptions, ReminderSortField, SortDirection,
+        TrashFilter,
+    };
+
+    #[test]
+    #[ctor]
+    fn init() {
+        init_database(&test_database_url(), vec![CORE_MIGRATIONS]).unwrap();
+    }
+
+    fn date(date: &str) -> SimpleDate {
+        SimpleDate::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn create(
+        repository: &impl ReminderRepository,
+        note: &str,
+        remind_at: SimpleDate,
+    ) -> ReminderData {
+        repository
+            .create_reminder(CreateReminderData {
+                note: note.to_owned(),
+                remind_at,
+                recurrence: None,
+                tags: vec![],
+                priority: ReminderPriority::Normal,
+                alerts: vec![],
+                checklist: vec![],
+            })
+            .unwrap()
+    }
+
+    /// The same checks run against every backend, so they stay interchangeable
+    fn check_repository(repository: impl ReminderRepository) {
+        let milk = repository
+            .create_reminder(CreateReminderData {
+                note: "Buy milk".to_owned(),
+                remind_at: date("2024-04-05 08:00:00"),
+                recurrence: None,
+                tags: vec!["shop".to_owned(), " home".to_owned(), "shop".to_owned()],
+                priority: ReminderPriority::High,
+                alerts: vec![AlertOffset::AT_TIME, AlertOffset::minutes_before(15)],
+                checklist: vec![],
+            })
+            .unwrap();
+        let call = create(&repository, "Call the bank", date("2024-04-06 08:00:00"));
+
+        assert_eq!(vec!["home".to_owned(), "shop".to_owned()], milk.tags);
+        assert_eq!(
+            vec![AlertOffset::minutes_before(15), AlertOffset::AT_TIME],
+            milk.alerts
+        );
+        assert_eq!(milk, repository.fetch_reminder(milk.id).unwrap());
+        assert!(matches!(
+            repository.fetch_reminder(call.id + 100),
+            Err(DatabaseError::FailedToFetchResult(
+                DieselResultError::NotFound
+            ))
+        ));
+
+        let query = ReminderQuery::new().filter(
+            ReminderFilter::from(ReminderQueryOptions::Search {
+                text: "BAN".to_owned(),
+            })
+            .or(ReminderQueryOptions::WithTags {
+                tags: vec!["shop".to_owned()],
+            }),
+        );
+        let ids: Vec<i64> = repository
+            .query_reminders(&query.sort_by(ReminderSortField::RemindAt, SortDirection::Desc))
+            .unwrap()
+            .iter()
+            .map(|reminder| reminder.id)
+            .collect();
+
+        assert_eq!(vec![call.id, milk.id], ids);
+        assert_eq!(
+            vec![call.id],
+            repository
+                .query_reminders(&ReminderQuery::new().limit(1).offset(1))
+                .unwrap()
+                .iter()
+                .map(|reminder| reminder.id)
+                .collect::<Vec<i64>>()
+        );
+
+        let updated = repository
+            .update_reminder(UpdateReminderData {
+                id: call.id,
+                note: Some("Call mom".to_owned()),
+                remind_at: None,
+                recurrence: Some(Some(Recurrence::new(Frequency::Daily))),
+                tags: Some(vec!["family".to_owned()]),
+                priority: None,
+                alerts: Some(vec![AlertOffset::minutes_before(5)]),
+                checklist: None,
+            })
+            .unwrap();
+
+        assert_eq!("Call mom", updated.note);
+        assert_eq!(vec!["family".to_owned()], updated.tags);
+
+        repository
+            .edit_reminder_checklist(milk.id, ChecklistEdit::Add(" oat ".to_owned()))
+            .unwrap();
+
+        let listed = repository
+            .edit_reminder_checklist(milk.id, ChecklistEdit::Add("whole".to_owned()))
+            .unwrap();
+        let (oat, whole) = (listed.checklist[0].id, listed.checklist[1].id);
+
+        repository
+            .edit_reminder_checklist(milk.id, ChecklistEdit::SetDone(oat, true))
+            .unwrap();
+
+        let listed = repository
+            .edit_reminder_checklist(milk.id, ChecklistEdit::Move(whole, 0))
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                ChecklistItemData {
+                    id: whole,
+                    title: "whole".to_owned(),
+                    done: false,
+                },
+                ChecklistItemData {
+                    id: oat,
+                    title: "oat".to_owned(),
+                    done: true,
+                },
+            ],
+            listed.checklist
+        );
+        assert_eq!(listed, repository.fetch_reminder(milk.id).unwrap());
+        assert!(repository
+            .edit_reminder_checklist(milk.id, ChecklistEdit::Remove(oat + whole))
+            .is_err());
+
+        let snoozed = repository
+            .snooze_reminder(call.id, date("2024-04-06 09:00:00"))
+            .unwrap();
+
+        assert_eq!(
+            Some(date("2024-04-06 08:00:00")),
+            snoozed.original_remind_at
+        );
+        assert_eq!(1, snoozed.snooze_count);
+
+        let due_alerts = repository
+            .take_due_alerts(date("2024-04-05 08:00:00"))
+            .unwrap();
+
+        assert_eq!(1, due_alerts.len());
+        assert_eq!(milk.id, due_alerts[0].reminder.id);
+        assert_eq!(AlertOffset::AT_TIME, due_alerts[0].offset);
+        assert!(repository
+            .take_due_alerts(date("2024-04-05 08:00:00"))
+            .unwrap()
+            .is_empty());
+
+        let scheduled = repository
+            .schedule_next_occurrences(date("2024-04-06 10:00:00"))
+            .unwrap();
+
+        assert_eq!(1, scheduled.len());
+        assert_eq!(date("2024-04-07 08:00:00"), scheduled[0].remind_at);
+        assert_eq!(2, scheduled[0].occurrence);
+        assert_eq!(None, scheduled[0].original_remind_at);
+
+        let completed = repository.complete_reminder(call.id).unwrap();
+        let series = repository.fetch_reminder(call.id).unwrap();
+
+        assert_ne!(call.id, completed.id);
+        assert_eq!(ReminderStatus::Completed, completed.status());
+        assert!(completed.alerts.is_empty());
+        assert_eq!(ReminderStatus::Pending, series.status());
+        assert!(series.remind_at > completed.remind_at);
+
+        assert_eq!(
+            1,
+            repository
+                .dismiss_overdue_reminders(date("2024-04-05 09:00:00"))
+                .unwrap()
+        );
+        assert_eq!(
+            ReminderStatus::Dismissed,
+            repository.fetch_reminder(milk.id).unwrap().status()
+        );
+
+        repository.delete_reminder(milk.id).unwrap();
+
+        assert_eq!(
+            2,
+            repository.count_reminders(&ReminderQuery::new()).unwrap()
+        );
+        assert_eq!(
+            vec![milk.id],
+            repository
+                .query_reminders(&ReminderQuery::new().trash(TrashFilter::Only))
+                .unwrap()
+                .iter()
+                .map(|reminder| reminder.id)
+                .collect::<Vec<i64>>()
+        );
+        assert!(repository.fetch_reminder(milk.id).unwrap().is_deleted());
+
+        let milk_changes = repository.fetch_reminder_changes(milk.id).unwrap();
+        let kinds: Vec<ReminderChangeKind> =
+            milk_changes.iter().map(|change| change.kind).collect();
+
+        // the checklist was edited four times before the reminder got dismissed
+        assert_eq!(
+            vec![
+                ReminderChangeKind::Create,
+                ReminderChangeKind::Update,
+                ReminderChangeKind::Update,
+                ReminderChangeKind::Update,
+                ReminderChangeKind::Update,
+                ReminderChangeKind::Update,
+                ReminderChangeKind::Delete
+            ],
+            kinds
+        );
+        assert!(repository
+            .apply_reminder_batch(vec![
+                ReminderOperation::Delete(call.id),
+                ReminderOperation::Complete(call.id + 100),
+            ])
+            .is_err());
+        assert!(!repository.fetch_reminder(call.id).unwrap().is_deleted());
+
+        let deletion = milk_changes.last().unwrap();
+        let restored = repository
+            .apply_reminder_batch(vec![deletion.inverse()])
+            .unwrap();
+
+        assert_eq!(ReminderChangeKind::Update, restored[0].kind);
+        assert_eq!(
+            deletion.before.as_ref(),
+            Some(&repository.fetch_reminder(milk.id).unwrap())
+        );
+
+        repository.delete_reminder(milk.id).unwrap();
+        repository.delete_reminder(call.id).unwrap();
+
+        assert!(!repository
+            .restore_deleted_reminder(call.id)
+            .unwrap()
+            .is_deleted());
+        assert_eq!(
+            1,
+            repository
+                .purge_deleted_reminders(SimpleDate::local())
+                .unwrap()
+        );
+        assert!(repository.fetch_reminder(milk.id).is_err());
+        assert_eq!(
+            Some(ReminderChangeKind::Purge),
+            repository
+                .fetch_reminder_changes(milk.id)
+                .unwrap()
+                .last()
+                .map(|change| change.kind)
+        );
+
+        repository.purge_reminder(call.id).unwrap();
+
+        assert!(repository.fetch_reminder(call.id).is_err());
+    }
+
+    #[test]
+    fn it_keeps_reminders_in_memory() {
+        check_repository(InMemoryReminderRepository::new());
+    }
+
+    #[test]
+    #[serial]
+    fn it_keeps_reminders_in_the_database() {
+        clear_test_database();
+
+        check_repository(DieselReminderRepository);
+    }
+
+    #[test]
+    fn it_shares_reminders_between_clones() {
+        let repository = InMemoryReminderRepository::new();
+        let reminder = create(
+            &repository.clone(),
+            "Testing",
+            SimpleDate::local().add_duration(Duration::Day(1)).unwrap(),
+        );
+
+        assert_eq!(reminder, repository.fetch_reminder(reminder.id).unwrap());
+    }
+}
@@ -0,0 +1,731 @@
+use diesel::{
+    Connection, ExpressionMethods, NullableExpressionMethods, OptionalExtension, QueryDsl,
+    QueryResult, RunQueryDsl, SelectableHelper,
+};
+use log::debug;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use super::{
+    journal::{deserialize_snapshot, record_change_at},
+    load_reminder, load_reminders, record_change, set_alerts, set_checklist, set_tags,
+    ChecklistItemData, ReminderChangeKind, SyncBatch, SyncChangeData,
+};
+use crate::database::{
+    self,
+    models::changes::ReminderChange,
+    models::reminders::{RestoreReminder, UpdateReminder},
+    models::sync::SyncDevice,
+    schema::reminder_changes::dsl as reminder_changes_dsl,
+    schema::reminders::{self, dsl as reminders_dsl},
+    schema::sync_devices::{self, dsl as sync_devices_dsl},
+    DatabaseConnection, DatabaseError, DatabaseResult,
+};
+
+/// The clock of a change and the device that made it
+pub(super) type ChangeVersion = (i64, String);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SyncSummary {
+    pub applied: usize,
+    /// Changes that lost against a newer change of the same reminder
+    pub skipped: usize,
+}
+
+/// A random (version 4) uuid
+pub(super) fn new_uuid() -> String {
+    let mut bytes = [0u8; 16];
+
+    getrandom::getrandom(&mut bytes).expect("the system has no source of randomness");
+
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex = bytes
+        .iter()
+        .fold(String::with_capacity(32), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+
+            hex
+        });
+
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// The uuid when no other reminder has it, otherwise a new one,
+/// e.g. for reminders from backups made before reminders had uuids
+pub(super) fn available_uuid(conn: &mut DatabaseConnection, uuid: &str) -> QueryResult<String> {
+    if uuid.is_empty() {
+        return Ok(new_uuid());
+    }
+
+    let taken: i64 = reminders_dsl::reminders
+        .filter(reminders_dsl::uuid.eq(uuid))
+        .count()
+        .get_result(conn)?;
+
+    Ok(if taken > 0 {
+        new_uuid()
+    } else {
+        uuid.to_owned()
+    })
+}
+
+/// The device is made up the first time it is asked for
+fn local_device_id_in(conn: &mut DatabaseConnection) -> QueryResult<String> {
+    let device_id = sync_devices_dsl::sync_devices
+        .filter(sync_devices_dsl::is_local.eq(true))
+        .select(sync_devices_dsl::device_id)
+        .order_by(sync_devices_dsl::device_id.asc())
+        .first::<String>(conn)
+        .optional()?;
+
+    if let Some(device_id) = device_id {
+        return Ok(device_id);
+    }
+
+    let device = SyncDevice {
+        device_id: new_uuid(),
+        is_local: true,
+        last_change_id: 0,
+    };
+
+    diesel::insert_into(sync_devices::table)
+        .values(&device)
+        .execute(conn)?;
+
+    Ok(device.device_id)
+}
+
+/// A change made on this device comes after every change it knows of
+pub(super) fn next_version(conn: &mut DatabaseConnection) -> QueryResult<ChangeVersion> {
+    let clock: Option<i64> = reminder_changes_dsl::reminder_changes
+        .select(diesel::dsl::max(reminder_changes_dsl::clock))
+        .first(conn)?;
+
+    Ok((clock.unwrap_or(0) + 1, local_device_id_in(conn)?))
+}
+
+fn latest_version(conn: &mut DatabaseConnection, uuid: &str) -> QueryResult<Option<ChangeVersion>> {
+    let version = reminder_changes_dsl::reminder_changes
+        .filter(reminder_changes_dsl::reminder_uuid.eq(uuid))
+        .select((reminder_changes_dsl::clock, reminder_changes_dsl::device_id))
+        .order_by((
+            reminder_changes_dsl::clock.desc(),
+            reminder_changes_dsl::device_id.desc(),
+        ))
+        .first::<(i64, Option<String>)>(conn)
+        .optional()?;
+
+    Ok(version.map(|(clock, device_id)| (clock, device_id.unwrap_or_default())))
+}
+
+fn fetch_sync_cursor_in(conn: &mut DatabaseConnection, device_id: &str) -> QueryResult<i64> {
+    Ok(sync_devices_dsl::sync_devices
+        .find(device_id)
+        .select(sync_devices_dsl::last_change_id)
+        .first(conn)
+        .optional()?
+        .unwrap_or(0))
+}
+
+fn set_sync_cursor(
+    conn: &mut DatabaseConnection,
+    device_id: &str,
+    last_change_id: i64,
+) -> QueryResult<()> {
+    let updated = diesel::update(sync_devices_dsl::sync_devices.find(device_id))
+        .set(sync_devices_dsl::last_change_id.eq(last_change_id))
+        .execute(conn)?;
+
+    if updated == 0 {
+        diesel::insert_into(sync_devices::table)
+            .values(&SyncDevice {
+                device_id: device_id.to_owned(),
+                is_local: false,
+                last_change_id,
+            })
+            .execute(conn)?;
+    }
+
+    Ok(())
+}
+
+impl TryFrom<ReminderChange> for SyncChangeData {
+    type Error = DatabaseError;
+
+    fn try_from(val: ReminderChange) -> Result<Self, Self::Error> {
+        Ok(SyncChangeData {
+            id: val.id,
+            uuid: val.reminder_uuid.unwrap_or_default(),
+            clock: val.clock,
+            device_id: val.device_id.unwrap_or_default(),
+            kind: val
+                .operation
+                .parse()
+                .map_err(DatabaseError::InvalidChange)?,
+            reminder: deserialize_snapshot(val.snapshot_after)?,
+        })
+    }
+}
+
+/// Reminders written before changes were synced have no change with their uuid,
+/// they are recorded as created so other devices get them too
+fn record_unsynced_reminders(conn: &mut DatabaseConnection) -> DatabaseResult<()> {
+    let synced_uuids = reminder_changes_dsl::reminder_changes
+        .filter(reminder_changes_dsl::reminder_uuid.is_not_null())
+        .select(reminder_changes_dsl::reminder_uuid);
+    let ids: Vec<i64> = reminders_dsl::reminders
+        .filter(diesel::dsl::not(
+            reminders_dsl::uuid.nullable().eq_any(synced_uuids),
+        ))
+        .select(reminders_dsl::id)
+        .load(conn)?;
+
+    for reminder in load_reminders(conn, &ids)? {
+        record_change(conn, ReminderChangeKind::Create, None, Some(reminder))?;
+    }
+
+    Ok(())
+}
+
+fn fetch_sync_batch_in(conn: &mut DatabaseConnection, since: i64) -> DatabaseResult<SyncBatch> {
+    record_unsynced_reminders(conn)?;
+
+    let changes: Vec<ReminderChange> = reminder_changes_dsl::reminder_changes
+        .filter(reminder_changes_dsl::id.gt(since))
+        .filter(reminder_changes_dsl::reminder_uuid.is_not_null())
+        .select(ReminderChange::as_select())
+        .order_by(reminder_changes_dsl::id.asc())
+        .load(conn)?;
+    // the changes of a reminder only get newer, so its last change is all another device needs
+    let mut uuids = HashSet::with_capacity(changes.len());
+    let mut changes: Vec<SyncChangeData> = changes
+        .into_iter()
+        .rev()
+        .filter(|change| uuids.insert(change.reminder_uuid.clone()))
+        .map(SyncChangeData::try_from)
+        .collect::<DatabaseResult<_>>()?;
+
+    changes.reverse();
+
+    Ok(SyncBatch {
+        device_id: local_device_id_in(conn)?,
+        changes,
+    })
+}
+
+/// The reminder takes the state of the change when the change is newer than its last one,
+/// the change is then recorded with the clock and the device it was made with.
+/// Returns whether the change was applied.
+fn apply_sync_change_in(
+    conn: &mut DatabaseConnection,
+    change: SyncChangeData,
+) -> DatabaseResult<bool> {
+    if let Some((clock, device_id)) = latest_version(conn, &change.uuid)? {
+        if (clock, device_id.as_str()) >= change.version() {
+            return Ok(false);
+        }
+    }
+
+    let existing_id: Option<i64> = reminders_dsl::reminders
+        .filter(reminders_dsl::uuid.eq(&change.uuid))
+        .select(reminders_dsl::id)
+        .first(conn)
+        .optional()?;
+    let existing_reminder = existing_id.map(|id| load_reminder(conn, id)).transpose()?;
+    let version = (change.clock, change.device_id);
+
+    let Some(reminder) = change.reminder else {
+        // a reminder this device never had does not need to be purged
+        let Some(existing_reminder) = existing_reminder else {
+            return Ok(false);
+        };
+
+        diesel::delete(reminders_dsl::reminders.find(existing_reminder.id)).execute(conn)?;
+        record_change_at(
+            conn,
+            ReminderChangeKind::Purge,
+            Some(existing_reminder),
+            None,
+            version,
+        )?;
+
+        return Ok(true);
+    };
+
+    let tags = reminder.tags.clone();
+    let alerts = reminder.alerts.clone();
+    // the ids of the items are the ids of the other device
+    let checklist: Vec<ChecklistItemData> = reminder
+        .checklist
+        .iter()
+        .map(|item| ChecklistItemData {
+            id: 0,
+            ..item.clone()
+        })
+        .collect();
+    let mut restore_reminder: RestoreReminder = reminder.try_into()?;
+    restore_reminder.uuid = change.uuid;
+
+    let id = match &existing_reminder {
+        Some(existing_reminder) => {
+            diesel::update(reminders_dsl::reminders.find(existing_reminder.id))
+                .set(&UpdateReminder::from(restore_reminder))
+                .execute(conn)?;
+
+            existing_reminder.id
+        }
+        None => {
+            restore_reminder.id = None;
+
+            diesel::insert_into(reminders::table)
+                .values(&restore_reminder)
+                .execute(conn)?;
+
+            conn.last_insert_id()?
+        }
+    };
+
+    set_tags(conn, id, &tags)?;
+    set_alerts(conn, id, &alerts)?;
+    set_checklist(conn, id, &checklist)?;
+
+    let synced_reminder = load_reminder(conn, id)?;
+    let kind = match (&existing_reminder, change.kind) {
+        (None, _) => ReminderChangeKind::Create,
+        (Some(_), ReminderChangeKind::Create) => ReminderChangeKind::Update,
+        (Some(_), kind) => kind,
+    };
+
+    record_change_at(
+        conn,
+        kind,
+        existing_reminder,
+        Some(synced_reminder),
+        version,
+    )?;
+
+    Ok(true)
+}
+
+fn apply_sync_batch_in(
+    conn: &mut DatabaseConnection,
+    batch: SyncBatch,
+) -> DatabaseResult<SyncSummary> {
+    let mut summary = SyncSummary::default();
+    let local_device_id = local_device_id_in(conn)?;
+
+    if batch.device_id == local_device_id {
+        return Ok(summary);
+    }
+
+    let cursor = fetch_sync_cursor_in(conn, &batch.device_id)?;
+    let mut last_change_id = cursor;
+
+    for change in batch.changes {
+        if change.id <= cursor {
+            continue;
+        }
+
+        last_change_id = last_change_id.max(change.id);
+
+        // changes of this device that the other device passes back
+        if change.device_id == local_device_id {
+            continue;
+        }
+
+        if apply_sync_change_in(conn, change)? {
+            summary.applied += 1;
+        } else {
+            summary.skipped += 1;
+        }
+    }
+
+    set_sync_cursor(conn, &batch.device_id, last_change_id)?;
+
+    Ok(summary)
+}
+
+/// The id this device sends its changes with
+pub fn local_device_id() -> DatabaseResult<String> {
+    database::within_database(|conn| Ok(local_device_id_in(conn)?))
+}
+
+/// The last change of every reminder changed after the change `since` of this device,
+/// `0` gets every reminder. This is what another device pulls to catch up.
+pub fn fetch_sync_batch(since: i64) -> DatabaseResult<SyncBatch> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Fetching changes to sync after change {since}");
+
+        let batch = conn.transaction(|conn| fetch_sync_batch_in(conn, since))?;
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Changes to sync fetched: {}", batch.changes.len());
+
+        Ok(batch)
+    })
+}
+
+/// The id of the last change taken from another device, the changes after it are the ones to pull
+pub fn fetch_sync_cursor(device_id: &str) -> DatabaseResult<i64> {
+    database::within_database(|conn| Ok(fetch_sync_cursor_in(conn, device_id)?))
+}
+
+/// The id of the last change this device handed to the other devices,
+/// the changes after it are the ones to push next
+pub fn fetch_export_cursor() -> DatabaseResult<i64> {
+    database::within_database(|conn| {
+        let device_id = local_device_id_in(conn)?;
+
+        Ok(fetch_sync_cursor_in(conn, &device_id)?)
+    })
+}
+
+pub fn set_export_cursor(last_change_id: i64) -> DatabaseResult<()> {
+    database::within_database(|conn| {
+        let device_id = local_device_id_in(conn)?;
+
+        Ok(set_sync_cursor(conn, &device_id, last_change_id)?)
+    })
+}
+
+/// Apply the changes another device pushed or this device pulled, in one transaction.
+/// The last writer wins: a change is kept when it is newer than the last change of its reminder,
+/// so every device ends up with the same reminders no matter the order they sync in.
+/// Changes taken from a device before and changes made by this device are left out.
+pub fn apply_sync_batch(batch: SyncBatch) -> DatabaseResult<SyncSummary> {
+    database::within_database(|conn| {
+        debug!(target: "CORE_REMINDERS_SERVICE", "Applying {} changes of device {}", batch.changes.len(), batch.device_id);
+
+        let summary = conn.transaction(|conn| apply_sync_batch_in(conn, batch))?;
+
+        debug!(target: "CORE_REMINDERS_SERVICE", "Changes synced: {summary:?}");
+
+        Ok(summary)
+    })
+}
+
+#[cfg(all(test, feature = "sqlite-database"))]
+mod tests {
+    use diesel_migrations::MigrationHarness;
+    use hkb_date::date::SimpleDate;
+
+    use super::super::{
+        complete_reminder_in, create_reminder_in, delete_reminder_in, purge_reminder_in,
+        update_reminder_in, CreateReminderData, ReminderData, ReminderPriority, UpdateReminderData,
+    };
+    use super::*;
+    use crate::database::{DatabaseKind, CORE_MIGRATIONS};
+
+    /// A database of its own, like the one of another device
+    fn open_device() -> DatabaseConnection {
+        let mut conn = DatabaseConnection::connect_to(":memory:").unwrap();
+
+        conn.run_pending_migrations(CORE_MIGRATIONS.for_kind(DatabaseKind::Sqlite))
+            .unwrap();
+
+        conn
+    }
+
+    /// Takes the changes of `from` that `to` did not take yet
+    fn pull(to: &mut DatabaseConnection, from: &mut DatabaseConnection) -> SyncSummary {
+        let device_id = local_device_id_in(from).unwrap();
+        let cursor = fetch_sync_cursor_in(to, &device_id).unwrap();
+        let batch = fetch_sync_batch_in(from, cursor).unwrap();
+
+        apply_sync_batch_in(to, batch).unwrap()
+    }
+
+    fn sync(first: &mut DatabaseConnection, second: &mut DatabaseConnection) {
+        pull(first, second);
+        pull(second, first);
+    }
+
+    fn create(conn: &mut DatabaseConnection, note: &str) -> ReminderData {
+        let changes = create_reminder_in(
+            conn,
+            CreateReminderData {
+                note: note.to_owned(),
+                remind_at: SimpleDate::parse_from_str("2024-04-05 08:00:00", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+                recurrence: None,
+                tags: vec!["home".to_owned()],
+                priority: ReminderPriority::Normal,
+                alerts: vec![],
+                checklist: vec![ChecklistItemData::new("Milk")],
+            },
+        )
+        .unwrap();
+
+        changes.into_iter().last().unwrap().after.unwrap()
+    }
+
+    fn rename(conn: &mut DatabaseConnection, uuid: &str, note: &str) {
+        let id = reminders_dsl::reminders
+            .filter(reminders_dsl::uuid.eq(uuid))
+            .select(reminders_dsl::id)
+            .first(conn)
+            .unwrap();
+
+        update_reminder_in(
+            conn,
+            UpdateReminderData {
+                id,
+                note: Some(note.to_owned()),
+                remind_at: None,
+                recurrence: None,
+                tags: None,
+                priority: None,
+                alerts: None,
+                checklist: None,
+            },
+        )
+        .unwrap();
+    }
+
+    fn find_by_uuid(conn: &mut DatabaseConnection, uuid: &str) -> Option<ReminderData> {
+        reminders_of(conn)
+            .into_iter()
+            .find(|reminder| reminder.uuid == uuid)
+    }
+
+    /// The reminders by uuid, without the ids that are local to the device
+    fn reminders_of(conn: &mut DatabaseConnection) -> Vec<ReminderData> {
+        let ids: Vec<i64> = reminders_dsl::reminders
+            .select(reminders_dsl::id)
+            .load(conn)
+            .unwrap();
+        let mut reminders = load_reminders(conn, &ids).unwrap();
+
+        for reminder in reminders.iter_mut() {
+            reminder.id = 0;
+
+            for item in reminder.checklist.iter_mut() {
+                item.id = 0;
+            }
+        }
+
+        reminders.sort_by(|a, b| a.uuid.cmp(&b.uuid));
+
+        reminders
+    }
+
+    #[test]
+    fn it_syncs_the_reminders_of_two_devices() {
+        let mut laptop = open_device();
+        let mut desktop = open_device();
+
+        let groceries = create(&mut laptop, "Groceries");
+        let dentist = create(&mut desktop, "Dentist");
+
+        assert_eq!(
+            SyncSummary {
+                applied: 1,
+                skipped: 0
+            },
+            pull(&mut desktop, &mut laptop)
+        );
+        assert_eq!(
+            SyncSummary {
+                applied: 1,
+                skipped: 0
+            },
+            pull(&mut laptop, &mut desktop)
+        );
+
+        let reminders = reminders_of(&mut laptop);
+
+        assert_eq!(reminders, reminders_of(&mut desktop));
+        assert_eq!(2, reminders.len());
+        assert_eq!(
+            "Groceries",
+            find_by_uuid(&mut desktop, &groceries.uuid).unwrap().note
+        );
+        assert_eq!(
+            vec![ChecklistItemData {
+                id: 0,
+                title: "Milk".to_owned(),
+                done: false
+            }],
+            find_by_uuid(&mut laptop, &dentist.uuid).unwrap().checklist
+        );
+
+        // nothing changed since the last sync
+        assert_eq!(SyncSummary::default(), pull(&mut desktop, &mut laptop));
+
+        for change in fetch_sync_batch_in(&mut desktop, 0).unwrap().changes {
+            assert!(!apply_sync_change_in(&mut laptop, change).unwrap());
+        }
+    }
+
+    #[test]
+    fn it_passes_changes_on_to_devices_that_did_not_see_them() {
+        let mut laptop = open_device();
+        let mut desktop = open_device();
+        let mut phone = open_device();
+
+        let groceries = create(&mut laptop, "Groceries");
+
+        sync(&mut desktop, &mut laptop);
+        rename(&mut desktop, &groceries.uuid, "Groceries for the weekend");
+        sync(&mut phone, &mut desktop);
+
+        assert_eq!(reminders_of(&mut desktop), reminders_of(&mut phone));
+
+        sync(&mut laptop, &mut phone);
+
+        assert_eq!(
+            "Groceries for the weekend",
+            find_by_uuid(&mut laptop, &groceries.uuid).unwrap().note
+        );
+    }
+
+    #[test]
+    fn it_keeps_the_last_change_of_a_reminder_changed_on_both_devices() {
+        let mut laptop = open_device();
+        let mut desktop = open_device();
+
+        let groceries = create(&mut laptop, "Groceries");
+
+        sync(&mut laptop, &mut desktop);
+
+        // the desktop changed the reminder more often, so its clock is further along
+        rename(&mut laptop, &groceries.uuid, "Groceries on the laptop");
+        rename(&mut desktop, &groceries.uuid, "Groceries");
+        rename(&mut desktop, &groceries.uuid, "Groceries on the desktop");
+
+        sync(&mut laptop, &mut desktop);
+
+        assert_eq!(reminders_of(&mut laptop), reminders_of(&mut desktop));
+        assert_eq!(
+            "Groceries on the desktop",
+            find_by_uuid(&mut laptop, &groceries.uuid).unwrap().note
+        );
+
+        // a change made after the sync comes after everything the device has seen
+        rename(&mut laptop, &groceries.uuid, "Groceries after the sync");
+        sync(&mut desktop, &mut laptop);
+
+        assert_eq!(
+            "Groceries after the sync",
+            find_by_uuid(&mut desktop, &groceries.uuid).unwrap().note
+        );
+    }
+
+    #[test]
+    fn it_breaks_ties_between_changes_with_the_same_clock_by_their_devices() {
+        let mut laptop = open_device();
+        let mut desktop = open_device();
+
+        let groceries = create(&mut laptop, "Groceries");
+
+        sync(&mut laptop, &mut desktop);
+        rename(&mut laptop, &groceries.uuid, "laptop");
+        rename(&mut desktop, &groceries.uuid, "desktop");
+
+        // the laptop pulls first, so the order the devices sync in does not matter
+        sync(&mut laptop, &mut desktop);
+
+        let laptop_id = local_device_id_in(&mut laptop).unwrap();
+        let desktop_id = local_device_id_in(&mut desktop).unwrap();
+        let expected_note = if laptop_id > desktop_id {
+            "laptop"
+        } else {
+            "desktop"
+        };
+
+        assert_eq!(reminders_of(&mut laptop), reminders_of(&mut desktop));
+        assert_eq!(
+            expected_note,
+            find_by_uuid(&mut desktop, &groceries.uuid).unwrap().note
+        );
+    }
+
+    #[test]
+    fn it_syncs_deleted_completed_and_purged_reminders() {
+        let mut laptop = open_device();
+        let mut desktop = open_device();
+
+        let groceries = create(&mut laptop, "Groceries");
+        let dentist = create(&mut laptop, "Dentist");
+        let rent = create(&mut laptop, "Rent");
+
+        sync(&mut laptop, &mut desktop);
+
+        delete_reminder_in(&mut laptop, groceries.id).unwrap();
+        complete_reminder_in(&mut laptop, dentist.id).unwrap();
+        purge_reminder_in(&mut laptop, rent.id).unwrap();
+
+        assert_eq!(
+            SyncSummary {
+                applied: 3,
+                skipped: 0
+            },
+            pull(&mut desktop, &mut laptop)
+        );
+        assert_eq!(reminders_of(&mut laptop), reminders_of(&mut desktop));
+        assert!(find_by_uuid(&mut desktop, &groceries.uuid)
+            .unwrap()
+            .is_deleted());
+        assert!(find_by_uuid(&mut desktop, &dentist.uuid)
+            .unwrap()
+            .completed_at
+            .is_some());
+        assert_eq!(None, find_by_uuid(&mut desktop, &rent.uuid));
+    }
+
+    #[test]
+    fn it_brings_back_a_purged_reminder_that_was_changed_later_on_another_device() {
+        let mut laptop = open_device();
+        let mut desktop = open_device();
+
+        let groceries = create(&mut laptop, "Groceries");
+
+        sync(&mut laptop, &mut desktop);
+
+        purge_reminder_in(&mut laptop, groceries.id).unwrap();
+        rename(&mut desktop, &groceries.uuid, "Groceries");
+        rename(&mut desktop, &groceries.uuid, "Groceries, still needed");
+
+        sync(&mut laptop, &mut desktop);
+
+        assert_eq!(reminders_of(&mut laptop), reminders_of(&mut desktop));
+        assert_eq!(
+            "Groceries, still needed",
+            find_by_uuid(&mut laptop, &groceries.uuid).unwrap().note
+        );
+    }
+
+    #[test]
+    fn it_sends_reminders_written_before_changes_were_synced() {
+        let mut laptop = open_device();
+        let mut desktop = open_device();
+
+        let groceries = create(&mut laptop, "Groceries");
+
+        diesel::delete(reminder_changes_dsl::reminder_changes)
+            .execute(&mut laptop)
+            .unwrap();
+        sync(&mut laptop, &mut desktop);
+
+        assert_eq!(reminders_of(&mut laptop), reminders_of(&mut desktop));
+        assert!(find_by_uuid(&mut desktop, &groceries.uuid).is_some());
+    }
+
+    #[test]
+    fn it_makes_up_version_4_uuids() {
+        let uuid = new_uuid();
+
+        assert_eq!(36, uuid.len());
+        assert_eq!(Some('4'), uuid.chars().nth(14));
+        assert!("89ab".contains(uuid.chars().nth(19).unwrap()));
+        assert_ne!(uuid, new_uuid());
+    }
+}
@@ -0,0 +1,345 @@
+use log::{debug, error};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error as ThisError;
+
+use super::encryption;
+use super::reminders::{self, SyncBatch, SyncSummary};
+use crate::database::DatabaseError;
+
+const SYNC_FILE_EXTENSION: &str = "json";
+
+#[derive(ThisError, Debug)]
+pub enum SyncError {
+    #[error("Failed to access the sync directory")]
+    FailedToAccessDirectory(#[from] std::io::Error),
+    #[error("Failed to write the changes of this device")]
+    FailedToWriteChanges(#[from] serde_json::Error),
+    #[error(transparent)]
+    FailedToAccessDatabase(#[from] DatabaseError),
+    #[error("Encrypted databases are not synced, their changes would be shared in plain text")]
+    EncryptedDatabase,
+}
+
+pub type SyncResult<T> = Result<T, SyncError>;
+
+/// The directory a device writes its changes to
+pub fn device_directory(directory: impl AsRef<Path>, device_id: &str) -> PathBuf {
+    directory.as_ref().join(device_id)
+}
+
+/// The file of the changes of a device up to its change `last_change_id`,
+/// the names are padded so the files of a device sort in the order they were written
+pub fn changes_file(device_directory: impl AsRef<Path>, last_change_id: i64) -> PathBuf {
+    device_directory
+        .as_ref()
+        .join(format!("{last_change_id:020}.{SYNC_FILE_EXTENSION}"))
+}
+
+fn read_batch(path: &Path) -> SyncResult<Option<SyncBatch>> {
+    match serde_json::from_reader(BufReader::new(File::open(path)?)) {
+        Ok(batch) => Ok(Some(batch)),
+        Err(e) => {
+            // the file might still be on its way from the other device
+            error!(target: "CORE_SYNC_SERVICE", "Skipping invalid changes in {}: {e}", path.display());
+
+            Ok(None)
+        }
+    }
+}
+
+/// The files of a device with changes after its change `cursor`, the oldest first
+fn pending_files(device_directory: &Path, cursor: i64) -> SyncResult<Vec<PathBuf>> {
+    let mut files = vec![];
+
+    for entry in fs::read_dir(device_directory)? {
+        let path = entry?.path();
+        let is_sync_file = path
+            .extension()
+            .is_some_and(|extension| extension == SYNC_FILE_EXTENSION);
+        let last_change_id = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<i64>().ok());
+
+        match last_change_id {
+            Some(last_change_id) if is_sync_file && last_change_id > cursor => {
+                files.push((last_change_id, path))
+            }
+            _ => continue,
+        }
+    }
+
+    files.sort();
+
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Applies the changes of a device this device did not take yet
+fn apply_device_changes(device_directory: &Path, device_id: &str) -> SyncResult<SyncSummary> {
+    let cursor = reminders::fetch_sync_cursor(device_id)?;
+    let mut summary = SyncSummary::default();
+
+    for path in pending_files(device_directory, cursor)? {
+        // the later files are taken once this one is valid, so no change of the device is skipped
+        let Some(batch) = read_batch(&path)? else {
+            break;
+        };
+        let applied = reminders::apply_sync_batch(batch)?;
+
+        summary.applied += applied.applied;
+        summary.skipped += applied.skipped;
+    }
+
+    Ok(summary)
+}
+
+/// Writes the changes made since the last sync to a new file of this device, when there are any
+fn export_changes(local_directory: &Path) -> SyncResult<()> {
+    let batch = reminders::fetch_sync_batch(reminders::fetch_export_cursor()?)?;
+    let Some(last_change_id) = batch.changes.iter().map(|change| change.id).max() else {
+        return Ok(());
+    };
+    let file = changes_file(local_directory, last_change_id);
+    let partial_file = file.with_extension("partial");
+    let mut writer = BufWriter::new(File::create(&partial_file)?);
+
+    serde_json::to_writer(&mut writer, &batch)?;
+    writer.flush()?;
+    fs::rename(partial_file, file)?;
+    reminders::set_export_cursor(last_change_id)?;
+
+    Ok(())
+}
+
+/// Syncs the reminders with the other devices through a directory they share,
+/// e.g. one kept in sync by Syncthing or a network drive.
+/// Every device writes the changes it made since its last sync to a new file in a directory named after the device,
+/// and applies the files of the other devices it did not take yet, see [`reminders::apply_sync_batch`].
+/// Encrypted databases are not synced, the files are written in plain text.
+pub fn sync_directory(directory: impl AsRef<Path>) -> SyncResult<SyncSummary> {
+    if encryption::is_database_encrypted()? {
+        return Err(SyncError::EncryptedDatabase);
+    }
+
+    let directory = directory.as_ref();
+    let device_id = reminders::local_device_id()?;
+    let local_directory = device_directory(directory, &device_id);
+    fs::create_dir_all(&local_directory)?;
+
+    debug!(target: "CORE_SYNC_SERVICE", "Syncing with the devices in: {}", directory.display());
+
+    let mut summary = SyncSummary::default();
+
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+
+        if !path.is_dir() || path == local_directory {
+            continue;
+        }
+
+        let Some(device_id) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let applied = apply_device_changes(&path, device_id)?;
+
+        summary.applied += applied.applied;
+        summary.skipped += applied.skipped;
+    }
+
+    // written after the changes of the others are applied, so they are passed on to every device
+    export_changes(&local_directory)?;
+
+    debug!(target: "CORE_SYNC_SERVICE", "Synced with the devices: {summary:?}");
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::{clear_test_database, init_database, test_database_url, CORE_MIGRATIONS};
+    use ctor::ctor;
+    use hkb_date::date::SimpleDate;
+    use serial_test::serial;
+
+    use super::*;
+    use crate::database::services::encryption::{rotate_database_key, unlock_database};
+    use crate::database::services::reminders::{
+        create_reminder, fakes, fetch_reminders, CreateReminderData, ReminderChangeKind,
+        ReminderData, ReminderPriority, SyncChangeData,
+    };
+    use crate::database::DatabaseKey;
+
+    #[test]
+    #[ctor]
+    fn init() {
+        init_database(&test_database_url(), vec![CORE_MIGRATIONS]).unwrap();
+    }
+
+    fn create_a_reminder(note: &str) {
+        create_reminder(CreateReminderData {
+            note: note.to_owned(),
+            remind_at: SimpleDate::local(),
+            recurrence: None,
+            tags: vec![],
+            priority: ReminderPriority::Normal,
+            alerts: vec![],
+            checklist: vec![],
+        })
+        .unwrap();
+    }
+
+    /// The batches this device wrote, the oldest first
+    fn exported_batches(directory: &Path) -> Vec<SyncBatch> {
+        let local_directory = device_directory(directory, &reminders::local_device_id().unwrap());
+
+        pending_files(&local_directory, 0)
+            .unwrap()
+            .iter()
+            .map(|path| serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    #[serial]
+    fn it_syncs_through_a_shared_directory() {
+        clear_test_database();
+
+        let directory = std::env::temp_dir().join(format!("hkb-sync-{}", std::process::id()));
+        let desktop_reminder = ReminderData {
+            uuid: "3f0c3b1e-4f5e-4d6a-9b7c-8d9e0f1a2b3c".to_owned(),
+            note: "Reminder of the desktop".to_owned(),
+            ..fakes::create_reminder()
+        };
+        let desktop_batch = SyncBatch {
+            device_id: "desktop".to_owned(),
+            changes: vec![SyncChangeData {
+                id: 1,
+                uuid: desktop_reminder.uuid.clone(),
+                clock: 1,
+                device_id: "desktop".to_owned(),
+                kind: ReminderChangeKind::Create,
+                reminder: Some(desktop_reminder.clone()),
+            }],
+        };
+        let desktop_directory = device_directory(&directory, "desktop");
+
+        fs::create_dir_all(&desktop_directory).unwrap();
+        fs::write(
+            changes_file(&desktop_directory, 1),
+            serde_json::to_string(&desktop_batch).unwrap(),
+        )
+        .unwrap();
+        fs::write(directory.join("notes.txt"), "not a device").unwrap();
+        create_a_reminder("Reminder of the laptop");
+
+        assert_eq!(
+            SyncSummary {
+                applied: 1,
+                skipped: 0
+            },
+            sync_directory(&directory).unwrap()
+        );
+        assert_eq!(SyncSummary::default(), sync_directory(&directory).unwrap());
+
+        let reminders = fetch_reminders(None).unwrap();
+        let batches = exported_batches(&directory);
+
+        assert_eq!(2, reminders.len());
+        assert!(reminders
+            .iter()
+            .any(|reminder| reminder.uuid == desktop_reminder.uuid));
+        // nothing changed in the second sync, so nothing was written
+        assert_eq!(1, batches.len());
+        assert_eq!(2, batches[0].changes.len());
+
+        create_a_reminder("Another reminder of the laptop");
+        sync_directory(&directory).unwrap();
+
+        let batches = exported_batches(&directory);
+
+        assert_eq!(2, batches.len());
+        assert_eq!(
+            vec!["Another reminder of the laptop"],
+            batches[1]
+                .changes
+                .iter()
+                .map(|change| change.reminder.as_ref().unwrap().note.as_str())
+                .collect::<Vec<&str>>()
+        );
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn it_only_takes_the_files_after_an_invalid_one_once_it_is_valid() {
+        clear_test_database();
+
+        let directory =
+            std::env::temp_dir().join(format!("hkb-sync-partial-{}", std::process::id()));
+        let desktop_directory = device_directory(&directory, "desktop");
+        let batch = |id: i64, uuid: &str| SyncBatch {
+            device_id: "desktop".to_owned(),
+            changes: vec![SyncChangeData {
+                id,
+                uuid: uuid.to_owned(),
+                clock: id,
+                device_id: "desktop".to_owned(),
+                kind: ReminderChangeKind::Create,
+                reminder: Some(ReminderData {
+                    uuid: uuid.to_owned(),
+                    ..fakes::create_reminder()
+                }),
+            }],
+        };
+
+        fs::create_dir_all(&desktop_directory).unwrap();
+        fs::write(changes_file(&desktop_directory, 1), "{\"device_id\":").unwrap();
+        fs::write(
+            changes_file(&desktop_directory, 2),
+            serde_json::to_string(&batch(2, "5d0c3b1e-4f5e-4d6a-9b7c-8d9e0f1a2b3c")).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(SyncSummary::default(), sync_directory(&directory).unwrap());
+
+        fs::write(
+            changes_file(&desktop_directory, 1),
+            serde_json::to_string(&batch(1, "4e0c3b1e-4f5e-4d6a-9b7c-8d9e0f1a2b3c")).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            SyncSummary {
+                applied: 2,
+                skipped: 0
+            },
+            sync_directory(&directory).unwrap()
+        );
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn it_does_not_sync_an_encrypted_database() {
+        clear_test_database();
+
+        let directory =
+            std::env::temp_dir().join(format!("hkb-sync-encrypted-{}", std::process::id()));
+        let key = DatabaseKey::from_passphrase("correct horse").with_kdf_iterations(1_000);
+
+        create_a_reminder("Dentist at 9");
+        unlock_database(Some(&key)).unwrap();
+
+        assert!(matches!(
+            sync_directory(&directory),
+            Err(SyncError::EncryptedDatabase)
+        ));
+        assert!(!directory.exists());
+
+        rotate_database_key(None).unwrap();
+    }
+}
@@ -1,10 +1,210 @@
 use hkb_date::date::SimpleDate;
+use hkb_date::duration::Duration;
+use hkb_date::recurrence::Recurrence;
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReminderStatus {
+    Pending,
+    Completed,
+    Dismissed,
+}
+
+/// Ordered from the least to the most important
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum ReminderPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Critical,
+}
+
+impl ReminderPriority {
+    pub const ALL: [ReminderPriority; 4] = [
+        ReminderPriority::Low,
+        ReminderPriority::Normal,
+        ReminderPriority::High,
+        ReminderPriority::Critical,
+    ];
+}
+
+impl std::fmt::Display for ReminderPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let priority = match self {
+            ReminderPriority::Low => "low",
+            ReminderPriority::Normal => "normal",
+            ReminderPriority::High => "high",
+            ReminderPriority::Critical => "critical",
+        };
+
+        write!(f, "{priority}")
+    }
+}
+
+impl std::str::FromStr for ReminderPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ReminderPriority::ALL
+            .into_iter()
+            .find(|priority| priority.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("Unknown priority: {s}"))
+    }
+}
+
+const MINUTES_IN_AN_HOUR: u32 = 60;
+const MINUTES_IN_A_DAY: u32 = 24 * MINUTES_IN_AN_HOUR;
+const MINUTES_IN_A_WEEK: u32 = 7 * MINUTES_IN_A_DAY;
+const ALERT_UNITS: [(u32, &str); 4] = [
+    (MINUTES_IN_A_WEEK, "week"),
+    (MINUTES_IN_A_DAY, "day"),
+    (MINUTES_IN_AN_HOUR, "hour"),
+    (1, "minute"),
+];
+
+/// When to get alerted about a reminder, relative to its `remind_at`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct AlertOffset {
+    /// Minutes before `remind_at`, 0 is at the time of the reminder
+    pub minutes: u32,
+}
+
+impl AlertOffset {
+    pub const AT_TIME: AlertOffset = AlertOffset { minutes: 0 };
+
+    pub fn minutes_before(minutes: u32) -> Self {
+        Self { minutes }
+    }
+
+    /// The warnings a reminder gets when none are specified
+    pub fn defaults() -> Vec<AlertOffset> {
+        vec![
+            Self::minutes_before(30),
+            Self::minutes_before(15),
+            Self::minutes_before(5),
+            Self::minutes_before(1),
+        ]
+    }
+
+    pub fn alert_at(&self, remind_at: SimpleDate) -> SimpleDate {
+        remind_at
+            .sub_duration(Duration::Minute(self.minutes))
+            .unwrap_or(remind_at)
+    }
+}
+
+impl std::fmt::Display for AlertOffset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.minutes == 0 {
+            return write!(f, "at time");
+        }
+
+        // use the biggest unit that describes the offset exactly
+        let (unit_minutes, unit) = ALERT_UNITS
+            .into_iter()
+            .find(|(unit_minutes, _)| self.minutes % unit_minutes == 0)
+            .unwrap_or((1, "minute"));
+        let value = self.minutes / unit_minutes;
+        let plural = if value == 1 { "" } else { "s" };
+
+        write!(f, "{value} {unit}{plural} before")
+    }
+}
+
+/// Parses offsets like "at time", "15 minutes before" or "1 day"
+impl std::str::FromStr for AlertOffset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let offset = s.trim().to_lowercase();
+
+        if offset == "at time" || offset == "at the time" {
+            return Ok(Self::AT_TIME);
+        }
+
+        let parts: Vec<&str> = offset.split_whitespace().collect();
+        let (value, unit) = match parts.as_slice() {
+            [value, unit] | [value, unit, "before"] => (value, unit),
+            _ => return Err(format!("Invalid alert offset: {s}")),
+        };
+        let value: u32 = value
+            .parse()
+            .map_err(|_| format!("Invalid alert offset: {s}"))?;
+        let unit = unit.strip_suffix('s').unwrap_or(unit);
+        let unit_minutes = match unit {
+            "min" | "minute" => 1,
+            "hour" => MINUTES_IN_AN_HOUR,
+            "day" => MINUTES_IN_A_DAY,
+            "week" => MINUTES_IN_A_WEEK,
+            _ => return Err(format!("Invalid alert offset: {s}")),
+        };
+
+        Ok(Self::minutes_before(value * unit_minutes))
+    }
+}
+
+/// An item of the checklist of a reminder
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecklistItemData {
+    /// 0 for an item that was not saved yet
+    pub id: i64,
+    pub title: String,
+    pub done: bool,
+}
+
+impl ChecklistItemData {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            id: 0,
+            title: title.into(),
+            done: false,
+        }
+    }
+}
+
+/// How many items of a checklist are done
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecklistProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl ChecklistProgress {
+    pub fn is_complete(&self) -> bool {
+        self.done == self.total
+    }
+}
+
+impl std::fmt::Display for ChecklistProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.done, self.total)
+    }
+}
+
+/// A change to the checklist of a reminder, items are picked by their id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChecklistEdit {
+    /// Append an item with this title
+    Add(String),
+    Rename(i64, String),
+    SetDone(i64, bool),
+    /// Move an item to a position, positions past the end move it last
+    Move(i64, usize),
+    Remove(i64),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CreateReminderData {
     pub note: String,
     pub remind_at: SimpleDate,
+    pub recurrence: Option<Recurrence>,
+    pub tags: Vec<String>,
+    pub priority: ReminderPriority,
+    pub alerts: Vec<AlertOffset>,
+    pub checklist: Vec<ChecklistItemData>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -12,27 +212,258 @@ pub struct UpdateReminderData {
     pub id: i64,
     pub note: Option<String>,
     pub remind_at: Option<SimpleDate>,
+    pub recurrence: Option<Option<Recurrence>>,
+    pub tags: Option<Vec<String>>,
+    pub priority: Option<ReminderPriority>,
+    pub alerts: Option<Vec<AlertOffset>>,
+    /// Items that stay keep their ids, items with an id the reminder does not have are added
+    pub checklist: Option<Vec<ChecklistItemData>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ReminderData {
     pub id: i64,
+    /// The same on every device the reminder is synced to, the id is not
+    #[serde(default)]
+    pub uuid: String,
     pub note: String,
     pub remind_at: SimpleDate,
     pub created_at: SimpleDate,
+    pub recurrence: Option<Recurrence>,
+    /// Position of `remind_at` in the recurrence series, starting from 1
+    pub occurrence: u32,
+    /// The date the reminder was set for before it got snoozed
+    pub original_remind_at: Option<SimpleDate>,
+    pub snooze_count: u32,
+    pub completed_at: Option<SimpleDate>,
+    pub dismissed_at: Option<SimpleDate>,
+    pub tags: Vec<String>,
+    pub priority: ReminderPriority,
+    /// Ordered from the earliest alert to the latest
+    pub alerts: Vec<AlertOffset>,
+    /// When the reminder was moved to the trash
+    #[serde(default)]
+    pub deleted_at: Option<SimpleDate>,
+    /// Ordered the way the items are shown
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItemData>,
+    /// IANA name of the zone of the system the reminder was created on
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+impl ReminderData {
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// `None` for a reminder without a checklist
+    pub fn checklist_progress(&self) -> Option<ChecklistProgress> {
+        if self.checklist.is_empty() {
+            return None;
+        }
+
+        Some(ChecklistProgress {
+            done: self.checklist.iter().filter(|item| item.done).count(),
+            total: self.checklist.len(),
+        })
+    }
+
+    pub fn status(&self) -> ReminderStatus {
+        if self.completed_at.is_some() {
+            ReminderStatus::Completed
+        } else if self.dismissed_at.is_some() {
+            ReminderStatus::Dismissed
+        } else {
+            ReminderStatus::Pending
+        }
+    }
+}
+
+/// An alert of a reminder that is due to be notified
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DueAlertData {
+    pub reminder: ReminderData,
+    pub offset: AlertOffset,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReminderChangeKind {
+    Create,
+    Update,
+    /// Moved to the trash
+    Delete,
+    /// Removed for good
+    Purge,
+}
+
+impl std::fmt::Display for ReminderChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            ReminderChangeKind::Create => "create",
+            ReminderChangeKind::Update => "update",
+            ReminderChangeKind::Delete => "delete",
+            ReminderChangeKind::Purge => "purge",
+        };
+
+        write!(f, "{kind}")
+    }
+}
+
+impl std::str::FromStr for ReminderChangeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            ReminderChangeKind::Create,
+            ReminderChangeKind::Update,
+            ReminderChangeKind::Delete,
+            ReminderChangeKind::Purge,
+        ]
+        .into_iter()
+        .find(|kind| kind.to_string() == s)
+        .ok_or_else(|| format!("Unknown change: {s}"))
+    }
+}
+
+/// A change recorded in the journal of the reminders
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReminderChangeData {
+    pub id: i64,
+    pub reminder_id: i64,
+    pub kind: ReminderChangeKind,
+    /// The reminder before the change, missing when it was created
+    pub before: Option<ReminderData>,
+    /// The reminder after the change, missing when it was purged
+    pub after: Option<ReminderData>,
+    pub changed_at: SimpleDate,
+}
+
+impl ReminderChangeData {
+    /// The operation that takes the reminder back to how it was before the change,
+    /// a created reminder is purged so it does not stay behind in the trash
+    pub fn inverse(&self) -> ReminderOperation {
+        match &self.before {
+            Some(before) => ReminderOperation::Put(before.clone()),
+            None => ReminderOperation::Purge(self.reminder_id),
+        }
+    }
+}
+
+/// A change to the reminders, applied together with others in one batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReminderOperation {
+    Create(CreateReminderData),
+    Update(UpdateReminderData),
+    /// Move the reminder to the trash
+    Delete(i64),
+    Snooze(i64, SimpleDate),
+    Complete(i64),
+    /// Take the reminder back out of the trash
+    Restore(i64),
+    /// Remove the reminder for good
+    Purge(i64),
+    EditChecklist(i64, ChecklistEdit),
+    /// Write the reminder with all of its state under its id, creating it when it is missing
+    Put(ReminderData),
+}
+
+/// The last change of a reminder, the way it is sent to other devices
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncChangeData {
+    /// The id of the change in the journal of the device that sent it
+    pub id: i64,
+    pub uuid: String,
+    /// Lamport clock of the change
+    pub clock: i64,
+    /// The device that made the change, which is not always the one that sent it
+    pub device_id: String,
+    pub kind: ReminderChangeKind,
+    /// The reminder after the change, missing when it was purged
+    pub reminder: Option<ReminderData>,
+}
+
+impl SyncChangeData {
+    /// Of two changes of a reminder the one with the greater version is kept,
+    /// changes made at the same clock are ordered by their devices
+    pub fn version(&self) -> (i64, &str) {
+        (self.clock, &self.device_id)
+    }
+}
+
+/// The changes one device sends to another, ordered by their ids
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncBatch {
+    pub device_id: String,
+    pub changes: Vec<SyncChangeData>,
 }
 
 pub mod fakes {
     use hkb_date::date::SimpleDate;
 
-    use super::ReminderData;
+    use super::{ReminderData, ReminderPriority};
 
     pub fn create_reminder() -> ReminderData {
         ReminderData {
             id: 1,
+            uuid: String::new(),
             note: "Testing".to_owned(),
             remind_at: SimpleDate::local(),
             created_at: SimpleDate::local(),
+            recurrence: None,
+            occurrence: 1,
+            original_remind_at: None,
+            snooze_count: 0,
+            completed_at: None,
+            dismissed_at: None,
+            tags: vec![],
+            priority: ReminderPriority::Normal,
+            alerts: vec![],
+            deleted_at: None,
+            checklist: vec![],
+            timezone: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_can_parse_alert_offsets() {
+        assert_eq!(AlertOffset::AT_TIME, "at time".parse().unwrap());
+        assert_eq!(
+            AlertOffset::minutes_before(15),
+            "15 minutes before".parse().unwrap()
+        );
+        assert_eq!(AlertOffset::minutes_before(120), "2 hours".parse().unwrap());
+        assert_eq!(
+            AlertOffset::minutes_before(60 * 24),
+            " 1 Day before ".parse().unwrap()
+        );
+        assert!("before".parse::<AlertOffset>().is_err());
+        assert!("5 fortnights before".parse::<AlertOffset>().is_err());
+    }
+
+    #[test]
+    fn it_can_display_alert_offsets() {
+        assert_eq!("at time", AlertOffset::AT_TIME.to_string());
+        assert_eq!(
+            "1 minute before",
+            AlertOffset::minutes_before(1).to_string()
+        );
+        assert_eq!(
+            "90 minutes before",
+            AlertOffset::minutes_before(90).to_string()
+        );
+        assert_eq!(
+            "2 days before",
+            AlertOffset::minutes_before(60 * 48).to_string()
+        );
+        assert_eq!(
+            "1 week before",
+            AlertOffset::minutes_before(60 * 24 * 7).to_string()
+        );
+    }
+}
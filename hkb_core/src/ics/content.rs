@@ -0,0 +1,454 @@
+use hkb_date::date::SimpleDate;
+
+use super::{IcsError, IcsResult};
+
+// RFC 5545 section 3.1, lines longer than this are folded
+const MAX_LINE_OCTETS: usize = 75;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Property {
+    pub line: usize,
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl Property {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Component {
+    pub line: usize,
+    pub name: String,
+    pub properties: Vec<Property>,
+    pub components: Vec<Component>,
+}
+
+impl Component {
+    fn new(line: usize, name: String) -> Self {
+        Self {
+            line,
+            name,
+            properties: vec![],
+            components: vec![],
+        }
+    }
+
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties
+            .iter()
+            .find(|property| property.name == name)
+    }
+}
+
+/// Joins folded lines back together, keeping the number of the line they started on
+fn unfold(text: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = vec![];
+
+    for (index, line) in text.lines().enumerate() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some((_, previous))) => previous.push_str(continuation),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push((index + 1, line.to_owned())),
+        }
+    }
+
+    lines
+}
+
+/// Splits on the separator, unless it is in a quoted parameter value
+fn split_unquoted(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut in_quotes = false;
+    let mut start = 0;
+
+    for (index, c) in text.char_indices() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == separator && !in_quotes {
+            parts.push(&text[start..index]);
+            start = index + 1;
+        }
+    }
+
+    parts.push(&text[start..]);
+
+    parts
+}
+
+fn parse_content_line(line: usize, text: &str) -> IcsResult<Property> {
+    let invalid = |reason: &str| IcsError::InvalidContentLine {
+        line,
+        reason: reason.to_owned(),
+    };
+
+    let mut in_quotes = false;
+    let value_start = text
+        .char_indices()
+        .find(|(_, c)| {
+            if *c == '"' {
+                in_quotes = !in_quotes;
+            }
+
+            *c == ':' && !in_quotes
+        })
+        .map(|(index, _)| index)
+        .ok_or_else(|| invalid("missing a value"))?;
+
+    let mut parts = split_unquoted(&text[..value_start], ';').into_iter();
+    let name = parts.next().unwrap_or_default().trim().to_uppercase();
+
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(invalid("invalid property name"));
+    }
+
+    let params = parts
+        .map(|param| {
+            param
+                .split_once('=')
+                .map(|(name, value)| {
+                    (
+                        name.trim().to_uppercase(),
+                        value.trim_matches('"').to_owned(),
+                    )
+                })
+                .ok_or_else(|| invalid("parameter without a value"))
+        })
+        .collect::<IcsResult<Vec<(String, String)>>>()?;
+
+    Ok(Property {
+        line,
+        name,
+        params,
+        value: text[value_start + 1..].to_owned(),
+    })
+}
+
+pub(super) fn parse_components(text: &str) -> IcsResult<Vec<Component>> {
+    let mut components = vec![];
+    let mut open_components: Vec<Component> = vec![];
+
+    for (line, content) in unfold(text) {
+        let property = parse_content_line(line, &content)?;
+
+        match property.name.as_str() {
+            "BEGIN" => {
+                open_components.push(Component::new(line, property.value.trim().to_uppercase()))
+            }
+            "END" => {
+                let component = open_components
+                    .pop()
+                    .filter(|component| component.name.eq_ignore_ascii_case(property.value.trim()))
+                    .ok_or_else(|| IcsError::UnbalancedComponent {
+                        line,
+                        name: property.value.trim().to_uppercase(),
+                    })?;
+
+                match open_components.last_mut() {
+                    Some(parent) => parent.components.push(component),
+                    None => components.push(component),
+                }
+            }
+            _ => match open_components.last_mut() {
+                Some(component) => component.properties.push(property),
+                None => {
+                    return Err(IcsError::InvalidContentLine {
+                        line,
+                        reason: "property outside of a component".to_owned(),
+                    })
+                }
+            },
+        }
+    }
+
+    if let Some(component) = open_components.pop() {
+        return Err(IcsError::UnbalancedComponent {
+            line: component.line,
+            name: component.name,
+        });
+    }
+
+    Ok(components)
+}
+
+pub(super) fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+pub(super) fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+
+            continue;
+        }
+
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Splits a list of text values on the commas that are not escaped
+pub(super) fn split_text_list(text: &str) -> Vec<String> {
+    let mut values = vec![];
+    let mut start = 0;
+    let mut escaped = false;
+
+    for (index, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' => {
+                values.push(unescape_text(&text[start..index]));
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    values.push(unescape_text(&text[start..]));
+
+    values
+}
+
+/// Writes a content line, folding it when it gets too long
+pub(super) fn write_line(output: &mut String, line: &str) {
+    let mut width = 0;
+
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            output.push_str("\r\n ");
+            width = 1;
+        }
+
+        output.push(c);
+        width += c.len_utf8();
+    }
+
+    output.push_str("\r\n");
+}
+
+pub(super) fn format_date_time(date: &SimpleDate) -> String {
+    date.format("%Y%m%dT%H%M%S")
+}
+
+/// Parses DATE and DATE-TIME values, UTC times are turned into local ones.
+/// Dates without a time are read as the start of the day.
+pub(super) fn parse_date_time(value: &str) -> Option<SimpleDate> {
+    let value = value.trim();
+
+    if value.len() == 8 {
+        return SimpleDate::parse_from_str(format!("{value}T000000"), "%Y%m%dT%H%M%S").ok();
+    }
+
+    match value.strip_suffix(['Z', 'z']) {
+        Some(value) => SimpleDate::parse_from_str(value, "%Y%m%dT%H%M%S")
+            .ok()
+            .map(|date| date.utc_to_local()),
+        None => SimpleDate::parse_from_str(value, "%Y%m%dT%H%M%S").ok(),
+    }
+}
+
+/// Formats minutes as a negative duration (e.g. -PT15M), zero is written as PT0S
+pub(super) fn format_duration_before(minutes: u32) -> String {
+    const MINUTES_IN_A_DAY: u32 = 24 * 60;
+    const MINUTES_IN_A_WEEK: u32 = 7 * MINUTES_IN_A_DAY;
+
+    if minutes == 0 {
+        return "PT0S".to_owned();
+    }
+
+    if minutes % MINUTES_IN_A_WEEK == 0 {
+        return format!("-P{}W", minutes / MINUTES_IN_A_WEEK);
+    }
+
+    let days = minutes / MINUTES_IN_A_DAY;
+    let hours = minutes % MINUTES_IN_A_DAY / 60;
+    let minutes = minutes % 60;
+    let mut duration = "-P".to_owned();
+
+    if days > 0 {
+        duration.push_str(&format!("{days}D"));
+    }
+
+    if hours > 0 || minutes > 0 {
+        duration.push('T');
+    }
+
+    if hours > 0 {
+        duration.push_str(&format!("{hours}H"));
+    }
+
+    if minutes > 0 {
+        duration.push_str(&format!("{minutes}M"));
+    }
+
+    duration
+}
+
+/// Parses a duration value (e.g. -P1DT2H) into signed seconds
+pub(super) fn parse_duration(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (sign, value) = match value.strip_prefix('-') {
+        Some(value) => (-1, value),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+
+    let mut seconds = 0;
+    let mut number = String::new();
+    let mut in_time = false;
+    let mut has_value = false;
+
+    for c in value.strip_prefix('P')?.chars() {
+        match (c, in_time) {
+            ('0'..='9', _) => number.push(c),
+            ('T', false) if number.is_empty() => in_time = true,
+            _ => {
+                let unit = match (c, in_time) {
+                    ('W', false) => 7 * 24 * 60 * 60,
+                    ('D', false) => 24 * 60 * 60,
+                    ('H', true) => 60 * 60,
+                    ('M', true) => 60,
+                    ('S', true) => 1,
+                    _ => return None,
+                };
+
+                seconds += number.parse::<i64>().ok()? * unit;
+                number.clear();
+                has_value = true;
+            }
+        }
+    }
+
+    (has_value && number.is_empty()).then_some(sign * seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_can_parse_content_lines() {
+        let property = parse_content_line(
+            1,
+            "dtstart;TZID=\"Europe/Sofia\";VALUE=DATE-TIME:20240405T080000",
+        )
+        .unwrap();
+
+        assert_eq!("DTSTART", property.name);
+        assert_eq!(Some("Europe/Sofia"), property.param("tzid"));
+        assert_eq!(Some("DATE-TIME"), property.param("VALUE"));
+        assert_eq!("20240405T080000", property.value);
+
+        // separators in quotes belong to the parameter
+        let property = parse_content_line(1, "ATTENDEE;CN=\"Doe; John\":mailto:j@d.com").unwrap();
+
+        assert_eq!(Some("Doe; John"), property.param("CN"));
+        assert_eq!("mailto:j@d.com", property.value);
+
+        assert!(parse_content_line(1, "SUMMARY").is_err());
+        assert!(parse_content_line(1, ":value").is_err());
+    }
+
+    #[test]
+    fn it_folds_and_unfolds_long_lines() {
+        let line = format!("SUMMARY:{}", "ж".repeat(60));
+        let mut output = String::new();
+        write_line(&mut output, &line);
+
+        assert!(output
+            .split("\r\n")
+            .all(|line| line.len() <= MAX_LINE_OCTETS));
+        assert_eq!(vec![(1, line)], unfold(&output));
+    }
+
+    #[test]
+    fn it_escapes_text() {
+        let text = "Milk, eggs; bread\\butter\nand jam";
+
+        assert_eq!(
+            "Milk\\, eggs\\; bread\\\\butter\\nand jam",
+            escape_text(text)
+        );
+        assert_eq!(text, unescape_text(&escape_text(text)));
+        assert_eq!(
+            vec!["work".to_owned(), "a,b".to_owned()],
+            split_text_list("work,a\\,b")
+        );
+    }
+
+    #[test]
+    fn it_can_format_and_parse_durations() {
+        assert_eq!("PT0S", format_duration_before(0));
+        assert_eq!("-PT15M", format_duration_before(15));
+        assert_eq!("-PT1H30M", format_duration_before(90));
+        assert_eq!("-P1DT2H", format_duration_before(26 * 60));
+        assert_eq!("-P2W", format_duration_before(2 * 7 * 24 * 60));
+
+        assert_eq!(Some(0), parse_duration("PT0S"));
+        assert_eq!(Some(-15 * 60), parse_duration("-PT15M"));
+        assert_eq!(Some(-(26 * 60 * 60 + 30)), parse_duration("-P1DT2H0M30S"));
+        assert_eq!(Some(2 * 7 * 24 * 60 * 60), parse_duration("+P2W"));
+
+        assert_eq!(None, parse_duration("-PT"));
+        assert_eq!(None, parse_duration("-P15M"));
+        assert_eq!(None, parse_duration("-PT15"));
+        assert_eq!(None, parse_duration("15M"));
+    }
+
+    #[test]
+    fn it_can_parse_dates() {
+        let date = SimpleDate::parse_from_str("2024-04-05 08:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        assert_eq!("20240405T083000", format_date_time(&date));
+        assert_eq!(Some(date), parse_date_time("20240405T083000"));
+        assert_eq!(
+            Some(date.utc_to_local()),
+            parse_date_time("20240405T083000Z")
+        );
+        assert_eq!(
+            Some(date.start_of_day().unwrap()),
+            parse_date_time("20240405")
+        );
+        assert_eq!(None, parse_date_time("2024-04-05"));
+    }
+
+    #[test]
+    fn it_requires_components_to_be_closed() {
+        assert!(matches!(
+            parse_components("BEGIN:VCALENDAR\nBEGIN:VTODO\nEND:VCALENDAR\n"),
+            Err(IcsError::UnbalancedComponent { line: 3, .. })
+        ));
+        assert!(matches!(
+            parse_components("BEGIN:VCALENDAR\n"),
+            Err(IcsError::UnbalancedComponent { line: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,142 @@
+use hkb_date::date::SimpleDate;
+
+use super::content::{escape_text, format_date_time, format_duration_before, write_line};
+use super::rrule::format_rrule;
+use crate::dtos::reminders::{ReminderData, ReminderPriority, ReminderStatus};
+
+pub const PRODUCT_ID: &str = "-//hkb//reminders//EN";
+
+/// The calendar component reminders are exported as
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IcsComponent {
+    /// A task, which keeps track of being completed
+    #[default]
+    Todo,
+    Event,
+}
+
+impl IcsComponent {
+    fn name(&self) -> &'static str {
+        match self {
+            IcsComponent::Todo => "VTODO",
+            IcsComponent::Event => "VEVENT",
+        }
+    }
+}
+
+// RFC 5545 section 3.8.1.9, 1 is the highest priority and 9 the lowest
+pub(super) fn priority_value(priority: ReminderPriority) -> u8 {
+    match priority {
+        ReminderPriority::Critical => 1,
+        ReminderPriority::High => 3,
+        ReminderPriority::Normal => 5,
+        ReminderPriority::Low => 9,
+    }
+}
+
+/// Writes reminders as an iCalendar file.
+///
+/// Example
+/// ```rust
+/// use hkb_core::dtos::reminders::fakes;
+/// use hkb_core::ics::{IcsComponent, IcsExporter};
+/// let calendar = IcsExporter::new(IcsComponent::Event).export(&[fakes::create_reminder()]);
+///
+/// assert!(calendar.contains("BEGIN:VEVENT"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IcsExporter {
+    component: IcsComponent,
+    stamp: SimpleDate,
+}
+
+impl IcsExporter {
+    pub fn new(component: IcsComponent) -> Self {
+        Self {
+            component,
+            stamp: SimpleDate::now(),
+        }
+    }
+
+    /// The UTC time written as DTSTAMP, defaults to the time the exporter was created
+    pub fn stamped_at(mut self, stamp: SimpleDate) -> Self {
+        self.stamp = stamp;
+
+        self
+    }
+
+    pub fn export(&self, reminders: &[ReminderData]) -> String {
+        let mut output = String::new();
+
+        write_line(&mut output, "BEGIN:VCALENDAR");
+        write_line(&mut output, "VERSION:2.0");
+        write_line(&mut output, &format!("PRODID:{PRODUCT_ID}"));
+
+        for reminder in reminders {
+            self.export_reminder(&mut output, reminder);
+        }
+
+        write_line(&mut output, "END:VCALENDAR");
+
+        output
+    }
+
+    fn export_reminder(&self, output: &mut String, reminder: &ReminderData) {
+        let component = self.component.name();
+        let remind_at = format_date_time(&reminder.remind_at);
+        let note = escape_text(&reminder.note);
+
+        write_line(output, &format!("BEGIN:{component}"));
+        write_line(output, &format!("UID:reminder-{}@hkb", reminder.id));
+        write_line(
+            output,
+            &format!("DTSTAMP:{}Z", format_date_time(&self.stamp)),
+        );
+        write_line(output, &format!("SUMMARY:{note}"));
+        write_line(output, &format!("DTSTART:{remind_at}"));
+
+        if self.component == IcsComponent::Todo {
+            write_line(output, &format!("DUE:{remind_at}"));
+        }
+
+        if let Some(recurrence) = &reminder.recurrence {
+            write_line(
+                output,
+                &format!("RRULE:{}", format_rrule(recurrence, reminder.occurrence)),
+            );
+        }
+
+        if !reminder.tags.is_empty() {
+            let tags: Vec<String> = reminder.tags.iter().map(|tag| escape_text(tag)).collect();
+
+            write_line(output, &format!("CATEGORIES:{}", tags.join(",")));
+        }
+
+        write_line(
+            output,
+            &format!("PRIORITY:{}", priority_value(reminder.priority)),
+        );
+
+        let status = match (reminder.status(), self.component) {
+            (ReminderStatus::Pending, IcsComponent::Todo) => "NEEDS-ACTION",
+            (ReminderStatus::Completed, IcsComponent::Todo) => "COMPLETED",
+            (ReminderStatus::Dismissed, _) => "CANCELLED",
+            (_, IcsComponent::Event) => "CONFIRMED",
+        };
+
+        write_line(output, &format!("STATUS:{status}"));
+
+        for alert in reminder.alerts.iter() {
+            write_line(output, "BEGIN:VALARM");
+            write_line(output, "ACTION:DISPLAY");
+            write_line(output, &format!("DESCRIPTION:{note}"));
+            write_line(
+                output,
+                &format!("TRIGGER:{}", format_duration_before(alert.minutes)),
+            );
+            write_line(output, "END:VALARM");
+        }
+
+        write_line(output, &format!("END:{component}"));
+    }
+}
@@ -0,0 +1,278 @@
+use hkb_date::date::SimpleDate;
+
+use super::content::{
+    parse_components, parse_date_time, parse_duration, split_text_list, unescape_text, Component,
+    Property,
+};
+use super::rrule::parse_rrule;
+use super::{IcsError, IcsResult, IcsWarning};
+use crate::dtos::reminders::{AlertOffset, CreateReminderData, ReminderPriority};
+
+/// Properties that mean nothing for a reminder, so they are left out without a warning
+const IGNORED_PROPERTIES: [&str; 11] = [
+    "UID",
+    "DTSTAMP",
+    "CREATED",
+    "LAST-MODIFIED",
+    "SEQUENCE",
+    "DTEND",
+    "DURATION",
+    "TRANSP",
+    "CLASS",
+    "COMPLETED",
+    "PERCENT-COMPLETE",
+];
+
+/// Reminders read from an iCalendar file, along with what could not be carried over
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IcsImport {
+    pub reminders: Vec<CreateReminderData>,
+    pub warnings: Vec<IcsWarning>,
+}
+
+fn priority_from_value(value: u8) -> Option<ReminderPriority> {
+    match value {
+        // 0 means the priority is not defined
+        0 | 5 => Some(ReminderPriority::Normal),
+        1..=2 => Some(ReminderPriority::Critical),
+        3..=4 => Some(ReminderPriority::High),
+        6..=9 => Some(ReminderPriority::Low),
+        _ => None,
+    }
+}
+
+fn read_date(property: &Property, warnings: &mut Vec<IcsWarning>) -> Option<SimpleDate> {
+    let date = parse_date_time(&property.value)?;
+
+    if let Some(timezone) = property.param("TZID") {
+        warnings.push(IcsWarning::new(
+            property.line,
+            format!("TZID={timezone} is not supported, the time is read as local time"),
+        ));
+    }
+
+    Some(date)
+}
+
+/// Triggers are taken relative to the date of the reminder, whichever date they are related to
+fn read_alert(alarm: &Component, remind_at: SimpleDate) -> Result<AlertOffset, String> {
+    let trigger = alarm
+        .property("TRIGGER")
+        .ok_or_else(|| "it has no TRIGGER".to_owned())?;
+    let invalid = || format!("TRIGGER={} is not valid", trigger.value);
+
+    let seconds_before = if trigger
+        .param("VALUE")
+        .is_some_and(|value| value.eq_ignore_ascii_case("DATE-TIME"))
+    {
+        let date = parse_date_time(&trigger.value).ok_or_else(invalid)?;
+
+        if date > remind_at {
+            -1
+        } else {
+            (remind_at - date).as_secs() as i64
+        }
+    } else {
+        -parse_duration(&trigger.value).ok_or_else(invalid)?
+    };
+
+    if seconds_before < 0 {
+        return Err("alerts after the reminder are not supported".to_owned());
+    }
+
+    Ok(AlertOffset::minutes_before((seconds_before / 60) as u32))
+}
+
+fn read_reminder(
+    component: &Component,
+    warnings: &mut Vec<IcsWarning>,
+) -> Option<CreateReminderData> {
+    let mut skip = |reason: String| {
+        warnings.push(IcsWarning::new(
+            component.line,
+            format!("{} skipped, {reason}", component.name),
+        ));
+
+        None
+    };
+
+    if let Some(status) = component.property("STATUS") {
+        let status = status.value.trim().to_uppercase();
+
+        if status == "COMPLETED" || status == "CANCELLED" {
+            return skip(format!("it is {}", status.to_lowercase()));
+        }
+    }
+
+    let Some(note) = component
+        .property("SUMMARY")
+        .or_else(|| component.property("DESCRIPTION"))
+        .map(|property| unescape_text(&property.value))
+        .filter(|note| !note.trim().is_empty())
+    else {
+        return skip("it has no summary".to_owned());
+    };
+
+    // tasks are reminded of when they are due
+    let Some(date) = component
+        .property("DUE")
+        .or_else(|| component.property("DTSTART"))
+    else {
+        return skip("it has no date".to_owned());
+    };
+
+    let mut date_warnings = vec![];
+    let Some(remind_at) = read_date(date, &mut date_warnings) else {
+        return skip(format!("{}={} is not valid", date.name, date.value));
+    };
+
+    warnings.append(&mut date_warnings);
+
+    let mut reminder = CreateReminderData {
+        note,
+        remind_at,
+        recurrence: None,
+        tags: vec![],
+        priority: ReminderPriority::Normal,
+        alerts: vec![],
+        checklist: vec![],
+    };
+
+    for property in component.properties.iter() {
+        let mut warn = |message: String| warnings.push(IcsWarning::new(property.line, message));
+
+        match property.name.as_str() {
+            "SUMMARY" | "DUE" | "DTSTART" | "STATUS" => {}
+            "DESCRIPTION" if component.property("SUMMARY").is_none() => {}
+            "RRULE" => match parse_rrule(&property.value) {
+                Ok(recurrence) => reminder.recurrence = Some(recurrence),
+                Err(reason) => warn(format!("RRULE dropped, {reason}")),
+            },
+            "CATEGORIES" => reminder.tags.extend(
+                split_text_list(&property.value)
+                    .into_iter()
+                    .map(|tag| tag.trim().to_owned())
+                    .filter(|tag| !tag.is_empty()),
+            ),
+            "PRIORITY" => match property
+                .value
+                .trim()
+                .parse()
+                .ok()
+                .and_then(priority_from_value)
+            {
+                Some(priority) => reminder.priority = priority,
+                None => warn(format!("PRIORITY={} is not valid", property.value)),
+            },
+            name if IGNORED_PROPERTIES.contains(&name) => {}
+            name => warn(format!("{name} is not supported")),
+        }
+    }
+
+    for alarm in component.components.iter() {
+        if alarm.name != "VALARM" {
+            warnings.push(IcsWarning::new(
+                alarm.line,
+                format!("{} is not supported", alarm.name),
+            ));
+
+            continue;
+        }
+
+        match read_alert(alarm, remind_at) {
+            Ok(alert) => reminder.alerts.push(alert),
+            Err(reason) => warnings.push(IcsWarning::new(
+                alarm.line,
+                format!("VALARM dropped, {reason}"),
+            )),
+        }
+    }
+
+    // the same order reminders keep their alerts in
+    reminder.alerts.sort_by(|a, b| b.cmp(a));
+    reminder.alerts.dedup();
+
+    Some(reminder)
+}
+
+/// Reads the tasks and events of an iCalendar file as reminders.
+/// Recurrence rules are carried over when hkb can follow them, anything left out is reported.
+pub fn parse_calendar(text: &str) -> IcsResult<IcsImport> {
+    let calendars: Vec<Component> = parse_components(text)?
+        .into_iter()
+        .filter(|component| component.name == "VCALENDAR")
+        .collect();
+
+    if calendars.is_empty() {
+        return Err(IcsError::MissingCalendar);
+    }
+
+    let mut import = IcsImport::default();
+
+    for component in calendars
+        .iter()
+        .flat_map(|calendar| calendar.components.iter())
+    {
+        match component.name.as_str() {
+            "VTODO" | "VEVENT" => {
+                if let Some(reminder) = read_reminder(component, &mut import.warnings) {
+                    import.reminders.push(reminder);
+                }
+            }
+            name => import.warnings.push(IcsWarning::new(
+                component.line,
+                format!("{name} is not supported"),
+            )),
+        }
+    }
+
+    Ok(import)
+}
+
+#[cfg(any(
+    feature = "sqlite-database",
+    feature = "mysql-database",
+    feature = "postgres-database"
+))]
+mod database {
+    use log::debug;
+    use std::path::Path;
+
+    use super::parse_calendar;
+    use crate::database::services::reminders::create_reminder;
+    use crate::database::DatabaseResult;
+    use crate::dtos::reminders::ReminderData;
+    use crate::ics::{IcsResult, IcsWarning};
+
+    /// Reminders created from an iCalendar file, along with what could not be carried over
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct IcsImportReport {
+        pub reminders: Vec<ReminderData>,
+        pub warnings: Vec<IcsWarning>,
+    }
+
+    pub fn import_file(path: impl AsRef<Path>) -> IcsResult<IcsImportReport> {
+        debug!(target: "CORE_ICS", "Importing calendar: {}", path.as_ref().display());
+
+        let calendar = parse_calendar(&std::fs::read_to_string(path)?)?;
+        let reminders = calendar
+            .reminders
+            .into_iter()
+            .map(create_reminder)
+            .collect::<DatabaseResult<Vec<ReminderData>>>()?;
+
+        debug!(target: "CORE_ICS", "Reminders imported: {}, warnings: {}", reminders.len(), calendar.warnings.len());
+
+        Ok(IcsImportReport {
+            reminders,
+            warnings: calendar.warnings,
+        })
+    }
+}
+
+#[cfg(any(
+    feature = "sqlite-database",
+    feature = "mysql-database",
+    feature = "postgres-database"
+))]
+pub use database::*;
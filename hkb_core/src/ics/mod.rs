@@ -0,0 +1,250 @@
+//! Moving reminders between hkb and calendars through iCalendar (RFC 5545) files.
+//!
+//! Reminders are exported as VTODO or VEVENT components with a VALARM for every alert.
+//! Dates are written as floating times, as reminders are kept in the local time.
+
+use std::fmt::Display;
+
+use thiserror::Error as ThisError;
+
+mod content;
+mod export;
+mod import;
+mod rrule;
+
+pub use export::*;
+pub use import::*;
+
+#[derive(ThisError, Debug)]
+pub enum IcsError {
+    #[error("Failed to read the calendar file")]
+    FailedToReadFile(#[from] std::io::Error),
+    #[error("Invalid content line {line}: {reason}")]
+    InvalidContentLine { line: usize, reason: String },
+    #[error("Component {name} on line {line} is not closed properly")]
+    UnbalancedComponent { line: usize, name: String },
+    #[error("No calendar found")]
+    MissingCalendar,
+    #[cfg(any(
+        feature = "sqlite-database",
+        feature = "mysql-database",
+        feature = "postgres-database"
+    ))]
+    #[error(transparent)]
+    FailedToCreateReminder(#[from] crate::database::DatabaseError),
+}
+
+pub type IcsResult<T> = Result<T, IcsError>;
+
+/// Something in the calendar that could not be carried over to a reminder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcsWarning {
+    pub line: usize,
+    pub message: String,
+}
+
+impl IcsWarning {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for IcsWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hkb_date::date::SimpleDate;
+    use hkb_date::recurrence::{Frequency, Recurrence, RecurrenceEnd, Weekday};
+
+    use super::*;
+    use crate::dtos::reminders::{
+        fakes, AlertOffset, CreateReminderData, ReminderData, ReminderPriority,
+    };
+
+    fn date(date: &str) -> SimpleDate {
+        SimpleDate::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn fixture_reminders() -> Vec<ReminderData> {
+        vec![
+            ReminderData {
+                id: 1,
+                note: "Dentist appointment, bring the x-rays; ask about the bill\nand the next check-up"
+                    .to_owned(),
+                remind_at: date("2024-04-05 08:30:00"),
+                recurrence: Some(
+                    Recurrence::new(Frequency::Weekly)
+                        .every(2)
+                        .on_weekdays(vec![Weekday::Monday, Weekday::Friday])
+                        .ending(RecurrenceEnd::After(5)),
+                ),
+                tags: vec!["health".to_owned(), "work, maybe".to_owned()],
+                priority: ReminderPriority::High,
+                alerts: vec![
+                    AlertOffset::minutes_before(24 * 60),
+                    AlertOffset::minutes_before(15),
+                    AlertOffset::AT_TIME,
+                ],
+                ..fakes::create_reminder()
+            },
+            ReminderData {
+                id: 2,
+                note: "Pay rent".to_owned(),
+                remind_at: date("2024-05-01 09:00:00"),
+                recurrence: Some(
+                    Recurrence::new(Frequency::Monthly)
+                        .ending(RecurrenceEnd::On(date("2024-12-31 23:59:59"))),
+                ),
+                priority: ReminderPriority::Low,
+                ..fakes::create_reminder()
+            },
+        ]
+    }
+
+    fn exporter() -> IcsExporter {
+        IcsExporter::new(IcsComponent::Todo).stamped_at(date("2024-04-01 10:00:00"))
+    }
+
+    fn created_from(reminder: &ReminderData) -> CreateReminderData {
+        CreateReminderData {
+            note: reminder.note.clone(),
+            remind_at: reminder.remind_at,
+            recurrence: reminder.recurrence.clone(),
+            tags: reminder.tags.clone(),
+            priority: reminder.priority,
+            alerts: reminder.alerts.clone(),
+            checklist: reminder.checklist.clone(),
+        }
+    }
+
+    #[test]
+    fn it_exports_reminders_as_the_fixture() {
+        let calendar = exporter().export(&fixture_reminders());
+
+        assert!(calendar
+            .split_terminator('\n')
+            .all(|line| line.ends_with('\r')));
+        assert_eq!(
+            include_str!("fixtures/reminders.ics").replace("\r\n", "\n"),
+            calendar.replace("\r\n", "\n")
+        );
+    }
+
+    #[test]
+    fn it_imports_the_exported_fixture_back() {
+        let import = parse_calendar(include_str!("fixtures/reminders.ics")).unwrap();
+        let expected: Vec<CreateReminderData> =
+            fixture_reminders().iter().map(created_from).collect();
+
+        assert_eq!(expected, import.reminders);
+        assert!(import.warnings.is_empty());
+    }
+
+    #[test]
+    fn it_round_trips_events() {
+        let reminders = fixture_reminders();
+        let calendar = IcsExporter::new(IcsComponent::Event).export(&reminders);
+        let import = parse_calendar(&calendar).unwrap();
+
+        assert_eq!(
+            reminders.iter().map(created_from).collect::<Vec<_>>(),
+            import.reminders
+        );
+        assert!(import.warnings.is_empty());
+    }
+
+    #[test]
+    fn it_imports_calendars_from_other_applications() {
+        let import = parse_calendar(include_str!("fixtures/calendar.ics")).unwrap();
+
+        assert_eq!(
+            vec![
+                CreateReminderData {
+                    note: "Team standup".to_owned(),
+                    remind_at: date("2024-04-08 09:30:00"),
+                    recurrence: Some(
+                        Recurrence::new(Frequency::Weekly)
+                            .on_weekdays(vec![
+                                Weekday::Monday,
+                                Weekday::Tuesday,
+                                Weekday::Wednesday,
+                                Weekday::Thursday,
+                                Weekday::Friday,
+                            ])
+                            .ending(RecurrenceEnd::On(date("2024-06-30 23:59:59"))),
+                    ),
+                    tags: vec![],
+                    priority: ReminderPriority::Normal,
+                    alerts: vec![AlertOffset::minutes_before(10)],
+                    checklist: vec![],
+                },
+                CreateReminderData {
+                    note: "File taxes".to_owned(),
+                    remind_at: date("2024-04-15 00:00:00"),
+                    recurrence: None,
+                    tags: vec!["Finance".to_owned(), "Home".to_owned()],
+                    priority: ReminderPriority::Critical,
+                    alerts: vec![
+                        AlertOffset::minutes_before(7 * 24 * 60),
+                        AlertOffset::minutes_before(24 * 60),
+                    ],
+                    checklist: vec![],
+                },
+                CreateReminderData {
+                    note: "Mom's birthday".to_owned(),
+                    remind_at: date("2024-05-12 00:00:00"),
+                    recurrence: None,
+                    tags: vec![],
+                    priority: ReminderPriority::Normal,
+                    alerts: vec![],
+                    checklist: vec![],
+                },
+            ],
+            import.reminders
+        );
+    }
+
+    #[test]
+    fn it_reports_what_it_can_not_import() {
+        let import = parse_calendar(include_str!("fixtures/calendar.ics")).unwrap();
+        let warnings: Vec<String> = import
+            .warnings
+            .iter()
+            .map(|warning| warning.to_string())
+            .collect();
+
+        assert_eq!(
+            vec![
+                "line 5: VTIMEZONE is not supported",
+                "line 16: TZID=Europe/Sofia is not supported, the time is read as local time",
+                "line 19: LOCATION is not supported",
+                "line 33: DESCRIPTION is not supported",
+                "line 45: VALARM dropped, alerts after the reminder are not supported",
+                "line 55: RRULE dropped, BYMONTH is not supported",
+                "line 57: VTODO skipped, it is completed",
+                "line 64: VEVENT skipped, it has no date",
+                "line 69: VJOURNAL is not supported",
+            ],
+            warnings
+        );
+    }
+
+    #[test]
+    fn it_requires_a_calendar() {
+        assert!(matches!(
+            parse_calendar("BEGIN:VTODO\nSUMMARY:Test\nEND:VTODO\n"),
+            Err(IcsError::MissingCalendar)
+        ));
+        assert!(matches!(
+            parse_calendar("BEGIN:VCALENDAR\nSUMMARY\nEND:VCALENDAR\n"),
+            Err(IcsError::InvalidContentLine { line: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,224 @@
+use hkb_date::date::SimpleDate;
+use hkb_date::recurrence::{Frequency, Recurrence, RecurrenceEnd, Weekday};
+
+use super::content::{format_date_time, parse_date_time};
+
+const WEEKDAYS: [(Weekday, &str); 7] = [
+    (Weekday::Monday, "MO"),
+    (Weekday::Tuesday, "TU"),
+    (Weekday::Wednesday, "WE"),
+    (Weekday::Thursday, "TH"),
+    (Weekday::Friday, "FR"),
+    (Weekday::Saturday, "SA"),
+    (Weekday::Sunday, "SU"),
+];
+
+fn frequency_name(frequency: Frequency) -> &'static str {
+    match frequency {
+        Frequency::Daily => "DAILY",
+        Frequency::Weekly => "WEEKLY",
+        Frequency::Monthly => "MONTHLY",
+        Frequency::Yearly => "YEARLY",
+    }
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    WEEKDAYS
+        .iter()
+        .find(|(day, _)| *day == weekday)
+        .map(|(_, name)| *name)
+        .unwrap_or_default()
+}
+
+/// Formats the recurrence as an RRULE value.
+/// `occurrence` is the position of the reminder in the series, so COUNT only covers what is left.
+pub(super) fn format_rrule(recurrence: &Recurrence, occurrence: u32) -> String {
+    let mut parts = vec![format!("FREQ={}", frequency_name(recurrence.frequency))];
+
+    if recurrence.interval > 1 {
+        parts.push(format!("INTERVAL={}", recurrence.interval));
+    }
+
+    if !recurrence.weekdays.is_empty() {
+        let mut weekdays = recurrence.weekdays.clone();
+        weekdays.sort();
+        weekdays.dedup();
+
+        let weekdays: Vec<&str> = weekdays.into_iter().map(weekday_name).collect();
+
+        parts.push(format!("BYDAY={}", weekdays.join(",")));
+    }
+
+    match recurrence.end {
+        RecurrenceEnd::Never => {}
+        RecurrenceEnd::On(date) => parts.push(format!("UNTIL={}", format_date_time(&date))),
+        RecurrenceEnd::After(count) => parts.push(format!(
+            "COUNT={}",
+            std::cmp::max(1, count.saturating_sub(occurrence.saturating_sub(1)))
+        )),
+    }
+
+    parts.join(";")
+}
+
+/// Parses an RRULE value, rules that hkb can not follow are rejected with the reason
+pub(super) fn parse_rrule(value: &str) -> Result<Recurrence, String> {
+    let mut frequency = None;
+    let mut interval = 1;
+    let mut weekdays = vec![];
+    let mut end = RecurrenceEnd::Never;
+
+    for part in value.trim().split(';').filter(|part| !part.is_empty()) {
+        let (name, value) = part
+            .split_once('=')
+            .ok_or_else(|| format!("{part} is not a valid rule part"))?;
+
+        match name.to_uppercase().as_str() {
+            "FREQ" => {
+                frequency = Some(match value.to_uppercase().as_str() {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    _ => return Err(format!("FREQ={value} is not supported")),
+                })
+            }
+            "INTERVAL" => {
+                interval = value
+                    .parse()
+                    .ok()
+                    .filter(|interval| *interval > 0)
+                    .ok_or_else(|| format!("INTERVAL={value} is not valid"))?
+            }
+            "COUNT" => {
+                end = RecurrenceEnd::After(
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|count| *count > 0)
+                        .ok_or_else(|| format!("COUNT={value} is not valid"))?,
+                )
+            }
+            "UNTIL" => {
+                let date: SimpleDate =
+                    parse_date_time(value).ok_or_else(|| format!("UNTIL={value} is not valid"))?;
+
+                // an end date without a time includes the whole day
+                end = RecurrenceEnd::On(if value.trim().len() == 8 {
+                    date.end_of_day()
+                        .map_err(|_| format!("UNTIL={value} is not valid"))?
+                } else {
+                    date
+                });
+            }
+            "BYDAY" => {
+                for day in value.split(',') {
+                    let weekday = WEEKDAYS
+                        .iter()
+                        .find(|(_, name)| name.eq_ignore_ascii_case(day.trim()))
+                        .map(|(weekday, _)| *weekday)
+                        .ok_or_else(|| format!("BYDAY={value} is not supported"))?;
+
+                    weekdays.push(weekday);
+                }
+            }
+            // only changes weekly rules with an interval, where monday is what hkb uses
+            "WKST" if value.eq_ignore_ascii_case("MO") => {}
+            _ => return Err(format!("{} is not supported", name.to_uppercase())),
+        }
+    }
+
+    let mut frequency = frequency.ok_or_else(|| "FREQ is missing".to_owned())?;
+
+    if !weekdays.is_empty() {
+        match frequency {
+            Frequency::Weekly => {}
+            // picking days out of every day is the same as repeating on them every week
+            Frequency::Daily if interval == 1 => frequency = Frequency::Weekly,
+            _ => {
+                return Err(format!(
+                    "BYDAY with FREQ={} is not supported",
+                    frequency_name(frequency)
+                ))
+            }
+        }
+    }
+
+    Ok(Recurrence::new(frequency)
+        .every(interval)
+        .on_weekdays(weekdays)
+        .ending(end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_can_format_rules() {
+        let recurrence = Recurrence::new(Frequency::Weekly)
+            .every(2)
+            .on_weekdays(vec![Weekday::Friday, Weekday::Monday])
+            .ending(RecurrenceEnd::After(5));
+
+        assert_eq!(
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;COUNT=5",
+            format_rrule(&recurrence, 1)
+        );
+        // the occurrences that already happened are not counted again
+        assert_eq!(
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;COUNT=2",
+            format_rrule(&recurrence, 4)
+        );
+
+        let date = SimpleDate::parse_from_str("2024-12-31 23:59:59", "%Y-%m-%d %H:%M:%S").unwrap();
+        let recurrence = Recurrence::new(Frequency::Daily).ending(RecurrenceEnd::On(date));
+
+        assert_eq!(
+            "FREQ=DAILY;UNTIL=20241231T235959",
+            format_rrule(&recurrence, 1)
+        );
+    }
+
+    #[test]
+    fn it_can_parse_rules() {
+        let date = SimpleDate::parse_from_str("2024-12-31 23:59:59", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        assert_eq!(
+            Ok(Recurrence::new(Frequency::Monthly)
+                .every(3)
+                .ending(RecurrenceEnd::After(4))),
+            parse_rrule("FREQ=MONTHLY;INTERVAL=3;COUNT=4")
+        );
+        assert_eq!(
+            Ok(Recurrence::new(Frequency::Yearly).ending(RecurrenceEnd::On(date))),
+            parse_rrule("freq=yearly;until=20241231")
+        );
+        assert_eq!(
+            Ok(Recurrence::new(Frequency::Weekly)
+                .on_weekdays(vec![Weekday::Monday, Weekday::Wednesday])),
+            parse_rrule("FREQ=DAILY;BYDAY=MO,WE;WKST=MO")
+        );
+    }
+
+    #[test]
+    fn it_rejects_rules_it_can_not_follow() {
+        assert_eq!(
+            Err("FREQ=HOURLY is not supported".to_owned()),
+            parse_rrule("FREQ=HOURLY")
+        );
+        assert_eq!(
+            Err("BYMONTHDAY is not supported".to_owned()),
+            parse_rrule("FREQ=MONTHLY;BYMONTHDAY=-1")
+        );
+        assert_eq!(
+            Err("BYDAY=1MO is not supported".to_owned()),
+            parse_rrule("FREQ=WEEKLY;BYDAY=1MO")
+        );
+        assert_eq!(
+            Err("BYDAY with FREQ=MONTHLY is not supported".to_owned()),
+            parse_rrule("FREQ=MONTHLY;BYDAY=MO")
+        );
+        assert_eq!(Err("FREQ is missing".to_owned()), parse_rrule("COUNT=2"));
+    }
+}
@@ -2,7 +2,12 @@ pub mod algorithms;
 pub mod data_structures;
 pub mod decoders;
 pub mod dtos;
+pub mod ics;
 pub mod logger;
 
-#[cfg(any(feature = "sqlite-database", feature = "mysql-database",))]
+#[cfg(any(
+    feature = "sqlite-database",
+    feature = "mysql-database",
+    feature = "postgres-database",
+))]
 pub mod database;
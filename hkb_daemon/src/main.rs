@@ -1,46 +1,51 @@
-use std::collections::HashMap;
-
-use diesel_migrations::{embed_migrations, EmbeddedMigrations};
-use hkb_core::database::init_database;
+use hkb_core::database::services::backup::{self, BackupFormat};
+use hkb_core::database::services::encryption::{is_database_encrypted, unlock_database};
 use hkb_core::database::services::reminders::*;
+use hkb_core::database::services::sync;
+use hkb_core::database::{
+    database_key, database_url, init_database, DatabaseError, DatabaseResult, CORE_MIGRATIONS,
+};
 use hkb_core::logger::{self, debug, error, info, AppenderType};
 use hkb_daemon_core::client::{Client, ClientError};
+use hkb_daemon_core::frame::Event;
 use hkb_daemon_core::server::Server;
 use hkb_date::date::SimpleDate;
-use notify_rust::{Notification, Timeout};
+use hkb_date::duration::{Duration, HumanizedDuration};
+use notification::EventSender;
+use std::path::PathBuf;
 use tokio::net::UnixStream;
+use tokio::sync::mpsc;
 
 mod audio;
+mod notification;
+
+const BACKUP_EVERY_SECS: u64 = 60 * 60 * 24;
+const BACKUPS_TO_KEEP: usize = 7;
+const DISMISS_OVERDUE_AFTER_DAYS: u32 = 1;
+const DEFAULT_PURGE_AFTER_DAYS: u32 = 30;
+const SYNC_EVERY_SECS: u64 = 60;
+
+/// How many days deleted reminders stay in the trash, `HKB_PURGE_AFTER_DAYS` overrides the default
+fn purge_after_days() -> u32 {
+    let Ok(days) = std::env::var("HKB_PURGE_AFTER_DAYS") else {
+        return DEFAULT_PURGE_AFTER_DAYS;
+    };
+
+    days.trim().parse().unwrap_or_else(|e| {
+        error!(target: "DAEMON", "Invalid HKB_PURGE_AFTER_DAYS {days}, using {DEFAULT_PURGE_AFTER_DAYS} days! {e}");
+
+        DEFAULT_PURGE_AFTER_DAYS
+    })
+}
+
+/// The directory the reminders are synced with other devices through, set by `HKB_SYNC_DIR`
+fn sync_directory() -> Option<PathBuf> {
+    std::env::var_os("HKB_SYNC_DIR")
+        .filter(|directory| !directory.is_empty())
+        .map(PathBuf::from)
+}
 
-const INTERVALS: [(
-    hkb_date::duration::Duration,
-    hkb_date::duration::Duration,
-    &str,
-); 4] = [
-    (
-        hkb_date::duration::Duration::Minute(0),
-        hkb_date::duration::Duration::Minute(1),
-        "1 minute",
-    ),
-    (
-        hkb_date::duration::Duration::Minute(0),
-        hkb_date::duration::Duration::Minute(5),
-        "5 minutes",
-    ),
-    (
-        hkb_date::duration::Duration::Minute(6),
-        hkb_date::duration::Duration::Minute(15),
-        "15 minutes",
-    ),
-    (
-        hkb_date::duration::Duration::Minute(16),
-        hkb_date::duration::Duration::Minute(30),
-        "30 minutes",
-    ),
-];
-const CORE_MIGRATIONS: EmbeddedMigrations = embed_migrations!("../hkb_core/migrations");
-
-async fn process_connection(stream: UnixStream) {
+async fn process_connection(stream: UnixStream, events: EventSender) {
     let mut client = Client::from_stream(stream);
     let mut alternate_interval = tokio::time::interval(std::time::Duration::from_millis(500));
 
@@ -57,6 +62,8 @@ async fn process_connection(stream: UnixStream) {
                 match result {
                     Ok(event) => {
                         debug!(target: "DAEMON", "Received an event: {event:?}");
+
+                        events.send(event).await.unwrap_or_default();
                     }
                     Err(ClientError::ConnectionClosed(e)) => {
                         debug!(target: "DAEMON", "Client disconnected: {e:?}");
@@ -69,109 +76,284 @@ async fn process_connection(stream: UnixStream) {
     }
 }
 
-async fn handle_reminding(already_reminded: &mut HashMap<String, Vec<i64>>) {
+async fn handle_reminding<R: ReminderRepository + Clone + Send + 'static>(
+    repository: &AsyncReminderRepository<R>,
+    events: &EventSender,
+) {
     debug!(target: "DAEMON", "Checking reminders to notify!");
 
-    let mut has_reminded = false;
+    let due_alerts = match repository.take_due_alerts(SimpleDate::local()).await {
+        Ok(due_alerts) => due_alerts,
+        Err(e) => {
+            error!(target: "DAEMON", "Failed to fetch due alerts! {}", e.to_string());
+
+            return;
+        }
+    };
+
+    debug!(target: "DAEMON", "Found {} reminders to notify!", due_alerts.len());
+
+    // the sound of the most important reminder is played once for all of them
+    let mut loudest_priority: Option<ReminderPriority> = None;
+
+    for DueAlertData { reminder, offset } in due_alerts {
+        debug!(target: "DAEMON", "Reminder at: {} - alert: {offset} - current time: {}", reminder.remind_at.to_string(), SimpleDate::local().to_string());
 
-    for (start, end, humanized_timeframe) in INTERVALS.iter() {
-        let start_date = SimpleDate::local().add_duration(start).unwrap();
-        let end_date = SimpleDate::local().add_duration(end).unwrap();
-        let reminded = already_reminded
-            .entry(end.to_string())
-            .or_insert_with(|| Vec::with_capacity(16));
-        let options = vec![
-            ReminderQueryOptions::RemindAtBetween {
-                start_date,
-                end_date,
-            },
-            ReminderQueryOptions::WithoutIds { ids: reminded },
-        ];
-        let reminders: Vec<ReminderData> = fetch_reminders(Some(options)).unwrap_or_default();
+        let duration = reminder.remind_at - SimpleDate::local();
+        let summary = if offset == AlertOffset::AT_TIME || duration.is_zero() {
+            "Your reminder is due".to_string()
+        } else {
+            format!("You have a reminder in: {}", duration.to_human_string())
+        };
+
+        notification::notify_reminder(repository, &reminder, summary.as_str(), events);
+
+        loudest_priority = std::cmp::max(loudest_priority, Some(reminder.priority));
+    }
+
+    if let Some(sound) = loudest_priority.and_then(notification::sound) {
+        // TOOD: play sounds from data directory
+        let _ = audio::play_audio(sound.to_string()).await;
+    }
+}
 
-        debug!(target: "DAEMON", "Found {} reminders to notify!", reminders.len());
+async fn handle_scheduling_recurring_reminders<R: ReminderRepository + Clone + Send + 'static>(
+    repository: &AsyncReminderRepository<R>,
+) {
+    debug!(target: "DAEMON", "Scheduling next occurrences of recurring reminders.");
 
-        if !has_reminded {
-            has_reminded = !reminders.is_empty();
+    match repository
+        .schedule_next_occurrences(SimpleDate::local())
+        .await
+    {
+        Ok(reminders) => {
+            debug!(target: "DAEMON", "Scheduled {} recurring reminders!", reminders.len());
         }
+        Err(e) => {
+            error!(target: "DAEMON", "Failed to schedule recurring reminders! {}", e.to_string());
+        }
+    }
+}
 
-        for reminder in reminders {
-            debug!(target: "DAEMON", "Reminder at: {} - current time: {}", reminder.remind_at.to_string(), SimpleDate::local().to_string());
+async fn handle_cleaning_reminders<R: ReminderRepository + Clone + Send + 'static>(
+    repository: &AsyncReminderRepository<R>,
+    purge_after_days: u32,
+) {
+    debug!(target: "DAEMON", "Checking if we should archive old reminders.");
 
-            Notification::new()
-                .summary(format!("You have a reminder in: {}", humanized_timeframe).as_str())
-                .body(reminder.note.as_str())
-                .auto_icon()
-                .timeout(Timeout::Milliseconds(3000))
-                .show()
-                .unwrap();
+    // overdue reminders are kept as dismissed, so we do not lose their history
+    let result = repository
+        .dismiss_overdue_reminders(
+            SimpleDate::local()
+                .sub_duration(Duration::Day(DISMISS_OVERDUE_AFTER_DAYS))
+                .unwrap(),
+        )
+        .await;
 
-            reminded.push(reminder.id);
+    match result {
+        Ok(dismissed) => {
+            debug!(target: "DAEMON", "Successfully archived {dismissed} old reminders!");
+        }
+        Err(e) => {
+            error!(target: "DAEMON", "Failed to archive old reminders! {}", e.to_string());
         }
     }
 
-    if has_reminded {
-        // TOOD: play sounds from data directory
-        let _ = audio::play_audio("notification.wav".to_string()).await;
+    let result = repository
+        .purge_deleted_reminders(
+            SimpleDate::local()
+                .sub_duration(Duration::Day(purge_after_days))
+                .unwrap(),
+        )
+        .await;
+
+    match result {
+        Ok(purged) => {
+            debug!(target: "DAEMON", "Purged {purged} reminders from the trash!");
+        }
+        Err(e) => {
+            error!(target: "DAEMON", "Failed to purge the trash! {}", e.to_string());
+        }
     }
 }
 
-async fn handle_cleaning_reminders() {
-    debug!(target: "DAEMON", "Checking if we should cleanup old reminders.");
+async fn handle_backing_up() {
+    let backup_directory = dirs::data_local_dir().unwrap().join("hkb/backups");
 
-    let result = delete_reminders(ReminderQueryOptions::RemindAtLe {
-        date: SimpleDate::local()
-            .sub_duration(hkb_date::duration::Duration::Day(1))
-            .unwrap(),
-    });
+    let result = tokio::task::spawn_blocking(move || {
+        let now = SimpleDate::local();
+
+        // the daemon is restarted often, so the last backup is looked up instead of counting
+        // from startup. Backups dated after now were made before the clock was set back,
+        // they do not count so backing up does not wait for the clock to catch up to them.
+        let is_due = backup::list_backups(&backup_directory)?
+            .iter()
+            .rev()
+            .find(|(date, _)| *date <= now)
+            .map_or(true, |(date, _)| {
+                (now - *date).as_secs() >= BACKUP_EVERY_SECS
+            });
+
+        if !is_due {
+            return Ok(None);
+        }
+
+        backup::rotate_backups(&backup_directory, BackupFormat::Json, BACKUPS_TO_KEEP).map(Some)
+    })
+    .await;
 
     match result {
-        Ok(_) => {
-            debug!(target: "DAEMON", "Successfully deleted old reminders!");
+        Ok(Ok(Some(path))) => {
+            info!(target: "DAEMON", "Backed up the database to {}", path.display());
+        }
+        Ok(Ok(None)) => {}
+        Ok(Err(e)) => {
+            error!(target: "DAEMON", "Failed to back up the database! {}", e.to_string());
+        }
+        Err(e) => {
+            error!(target: "DAEMON", "Backup did not finish! {}", e.to_string());
+        }
+    }
+}
+
+async fn handle_syncing(directory: PathBuf) {
+    debug!(target: "DAEMON", "Syncing reminders through {}", directory.display());
+
+    let result = tokio::task::spawn_blocking(move || sync::sync_directory(directory)).await;
+
+    match result {
+        Ok(Ok(summary)) => {
+            debug!(target: "DAEMON", "Synced reminders: {summary:?}");
+        }
+        Ok(Err(e)) => {
+            error!(target: "DAEMON", "Failed to sync reminders! {}", e.to_string());
         }
         Err(e) => {
-            error!(target: "DAEMON", "Failed to cleanup old reminders! {}", e.to_string());
+            error!(target: "DAEMON", "Syncing did not finish! {}", e.to_string());
+        }
+    }
+}
+
+fn handle_event(event: Event) {
+    // fired alerts are kept in the database and are tied to the date of the reminder,
+    // so snoozed, updated and deleted reminders do not need any bookkeeping here
+    match event {
+        Event::ReminderSnoozed(reminder) => {
+            debug!(target: "DAEMON", "Reminder {} snoozed until {}", reminder.id, reminder.remind_at);
+        }
+        Event::ReminderDeleted(id) => debug!(target: "DAEMON", "Reminder {id} deleted"),
+        Event::ReminderCreated(reminder) => {
+            debug!(target: "DAEMON", "Reminder {} created", reminder.id);
+        }
+        Event::ReminderUpdated(reminder) => {
+            debug!(target: "DAEMON", "Reminder {} updated", reminder.id);
         }
     }
 }
 
-async fn handle_reminders() {
-    let mut already_reminded: HashMap<String, Vec<i64>> = HashMap::new();
+async fn handle_reminders<R: ReminderRepository + Clone + Send + 'static>(
+    repository: AsyncReminderRepository<R>,
+    mut events_receiver: mpsc::Receiver<Event>,
+    events: EventSender,
+    purge_after_days: u32,
+    sync_directory: Option<PathBuf>,
+) {
     let mut cleanup_reminders_interval =
         tokio::time::interval(tokio::time::Duration::from_secs(60 * 5));
     let mut reminder_interval = tokio::time::interval(tokio::time::Duration::from_secs(10));
+    let mut backup_interval = tokio::time::interval(tokio::time::Duration::from_secs(60 * 60));
+    let mut sync_interval =
+        tokio::time::interval(tokio::time::Duration::from_secs(SYNC_EVERY_SECS));
 
     loop {
         tokio::select! {
             _ = reminder_interval.tick() => {
-                handle_reminding(&mut already_reminded).await;
+                handle_reminding(&repository, &events).await;
+            }
+            Some(event) = events_receiver.recv() => {
+                handle_event(event);
             }
             _ = cleanup_reminders_interval.tick() => {
-                handle_cleaning_reminders().await;
+                handle_scheduling_recurring_reminders(&repository).await;
+                handle_cleaning_reminders(&repository, purge_after_days).await;
+            }
+            _ = backup_interval.tick() => {
+                handle_backing_up().await;
+            }
+            _ = sync_interval.tick(), if sync_directory.is_some() => {
+                handle_syncing(sync_directory.clone().unwrap()).await;
             }
         }
     }
 }
 
+fn open_database() -> DatabaseResult<()> {
+    init_database(&database_url(), vec![CORE_MIGRATIONS])?;
+    unlock_database(database_key()?.as_ref())
+}
+
 #[tokio::main]
 async fn main() {
-    let database_file_path = dirs::data_local_dir().unwrap().join("hkb/db");
-    init_database(database_file_path.to_str().unwrap(), vec![CORE_MIGRATIONS]).unwrap();
-
     logger::init(Some(vec![AppenderType::FILE, AppenderType::STDOUT]));
 
+    if let Err(e) = open_database() {
+        match e {
+            DatabaseError::MissingDatabaseKey => {
+                error!(target: "DAEMON", "The database is encrypted, set HKB_DATABASE_KEY or HKB_DATABASE_KEYFILE to its key!")
+            }
+            DatabaseError::WrongDatabaseKey => {
+                error!(target: "DAEMON", "The key in HKB_DATABASE_KEY or HKB_DATABASE_KEYFILE does not open the database!")
+            }
+            e => error!(target: "DAEMON", "Failed to open the database! {e}"),
+        }
+
+        std::process::exit(1);
+    }
+
     let server = Server::bind();
 
     info!("Listening: {}", server.get_addr().to_str().unwrap());
 
+    let (events, events_receiver) = mpsc::channel::<Event>(32);
+    let reminders_events = events.clone();
+    let purge_after_days = purge_after_days();
+    let sync_directory = sync_directory().filter(|_| {
+        let encrypted = is_database_encrypted().unwrap_or(true);
+
+        if encrypted {
+            error!(target: "DAEMON", "Not syncing reminders! {}", sync::SyncError::EncryptedDatabase);
+        }
+
+        !encrypted
+    });
+
+    info!("Purging deleted reminders after {purge_after_days} days");
+
+    if let Some(directory) = &sync_directory {
+        info!("Syncing reminders through {}", directory.display());
+    }
+
     tokio::spawn(async move { audio::init().await });
-    tokio::spawn(async move { handle_reminders().await });
+    tokio::spawn(async move {
+        let repository = AsyncReminderRepository::new(DieselReminderRepository);
+
+        handle_reminders(
+            repository,
+            events_receiver,
+            reminders_events,
+            purge_after_days,
+            sync_directory,
+        )
+        .await
+    });
 
     loop {
         match server.accept().await {
             Ok((socket, _)) => {
-                tokio::spawn(async {
-                    process_connection(socket).await;
+                let events = events.clone();
+
+                tokio::spawn(async move {
+                    process_connection(socket, events).await;
                 });
             }
             Err(_) => error!("Failed to accept a connection ;("),
@@ -0,0 +1,143 @@
+use hkb_core::database::services::reminders::{
+    AsyncReminderRepository, ReminderData, ReminderPriority, ReminderRepository,
+};
+use hkb_daemon_core::frame::Event;
+use notify_rust::{Notification, Timeout};
+use tokio::sync::mpsc;
+
+pub type EventSender = mpsc::Sender<Event>;
+
+fn timeout(priority: ReminderPriority) -> Timeout {
+    match priority {
+        ReminderPriority::Low => Timeout::Milliseconds(2000),
+        ReminderPriority::Normal => Timeout::Milliseconds(3000),
+        ReminderPriority::High => Timeout::Milliseconds(10000),
+        // critical reminders stay until they are closed
+        ReminderPriority::Critical => Timeout::Never,
+    }
+}
+
+/// Sound to play for a reminder, relative to the sound directory
+pub fn sound(priority: ReminderPriority) -> Option<&'static str> {
+    match priority {
+        ReminderPriority::Low => None,
+        ReminderPriority::Normal | ReminderPriority::High | ReminderPriority::Critical => {
+            Some("notification.wav")
+        }
+    }
+}
+
+// Actions are only supported by freedesktop notification servers
+#[cfg(all(unix, not(target_os = "macos")))]
+mod actions {
+    use hkb_core::database::services::reminders::{
+        AsyncReminderRepository, ReminderData, ReminderPriority, ReminderRepository,
+    };
+    use hkb_core::logger::{debug, error};
+    use hkb_daemon_core::frame::Event;
+    use hkb_date::date::SimpleDate;
+    use hkb_date::duration::Duration;
+    use notify_rust::{Notification, Urgency};
+
+    use super::EventSender;
+
+    const SNOOZE_ACTION: &str = "snooze";
+    const SNOOZE_ACTION_LABEL: &str = "Snooze for 10 minutes";
+    const SNOOZE_DURATION: Duration = Duration::Minute(10);
+
+    fn snooze_reminder<R: ReminderRepository>(repository: &R, id: i64, events: &EventSender) {
+        let remind_at = SimpleDate::local().add_duration(SNOOZE_DURATION).unwrap();
+
+        match repository.snooze_reminder(id, remind_at) {
+            Ok(reminder) => {
+                debug!(target: "DAEMON_NOTIFICATION", "Snoozed reminder {id} until {remind_at}");
+
+                events
+                    .blocking_send(Event::ReminderSnoozed(reminder))
+                    .unwrap_or_default();
+            }
+            Err(e) => {
+                error!(target: "DAEMON_NOTIFICATION", "Failed to snooze reminder {id}! {}", e.to_string());
+            }
+        }
+    }
+
+    fn urgency(priority: ReminderPriority) -> Urgency {
+        match priority {
+            ReminderPriority::Low => Urgency::Low,
+            ReminderPriority::Normal | ReminderPriority::High => Urgency::Normal,
+            ReminderPriority::Critical => Urgency::Critical,
+        }
+    }
+
+    pub fn show<R: ReminderRepository + Clone + Send + 'static>(
+        repository: &AsyncReminderRepository<R>,
+        notification: &mut Notification,
+        reminder: &ReminderData,
+        events: &EventSender,
+    ) {
+        let reminder_id = reminder.id;
+        let repository = repository.clone();
+        let events = events.clone();
+        let handle = notification
+            .urgency(urgency(reminder.priority))
+            .action(SNOOZE_ACTION, SNOOZE_ACTION_LABEL)
+            .show();
+
+        match handle {
+            Ok(handle) => {
+                // waiting for an action blocks until the notification is closed
+                tokio::task::spawn_blocking(move || {
+                    handle.wait_for_action(|action| {
+                        if action == SNOOZE_ACTION {
+                            snooze_reminder(repository.blocking(), reminder_id, &events);
+                        }
+                    })
+                });
+            }
+            Err(e) => {
+                error!(target: "DAEMON_NOTIFICATION", "Failed to show notification! {}", e.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+mod actions {
+    use hkb_core::database::services::reminders::{
+        AsyncReminderRepository, ReminderData, ReminderRepository,
+    };
+    use hkb_core::logger::error;
+    use notify_rust::Notification;
+
+    use super::EventSender;
+
+    pub fn show<R: ReminderRepository + Clone + Send + 'static>(
+        _repository: &AsyncReminderRepository<R>,
+        notification: &mut Notification,
+        _reminder: &ReminderData,
+        _events: &EventSender,
+    ) {
+        if let Err(e) = notification.show() {
+            error!(target: "DAEMON_NOTIFICATION", "Failed to show notification! {}", e.to_string());
+        }
+    }
+}
+
+/// Snoozing from the notification goes through the repository
+pub fn notify_reminder<R: ReminderRepository + Clone + Send + 'static>(
+    repository: &AsyncReminderRepository<R>,
+    reminder: &ReminderData,
+    summary: &str,
+    events: &EventSender,
+) {
+    let mut notification = Notification::new();
+
+    notification
+        .summary(summary)
+        .body(reminder.note.as_str())
+        .auto_icon()
+        .timeout(timeout(reminder.priority));
+
+    actions::show(repository, &mut notification, reminder, events);
+}
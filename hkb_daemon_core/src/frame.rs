@@ -13,6 +13,8 @@ const FRAME_DATA_SIZE: usize = FRAME_SIZE - FRAME_METADATA_SIZE;
 pub enum Event {
     ReminderDeleted(i64),
     ReminderCreated(ReminderData),
+    ReminderSnoozed(ReminderData),
+    ReminderUpdated(ReminderData),
 }
 
 impl AsRef<Event> for Event {
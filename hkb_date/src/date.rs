@@ -1,5 +1,7 @@
 use crate::duration::*;
-use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, ParseError, Timelike, Utc};
+use chrono::{
+    Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, ParseError, TimeZone, Timelike, Utc,
+};
 use serde::{Deserialize, Serialize};
 use std::{fmt::Display, time::Duration as STDDuration};
 use thiserror::Error as ThisError;
@@ -17,9 +19,12 @@ pub enum DateError {
 
     #[error("Failed to set time")]
     FailedToSetTime,
+
+    #[error("{0} is out of the supported range")]
+    OutOfRange(NaiveDateTime),
 }
 
-#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum Timezone {
     UTC,
     Local,
@@ -29,7 +34,7 @@ pub type DateResult<T> = Result<T, DateError>;
 
 type DateUnit = u32;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct SimpleDate {
     date: NaiveDateTime,
     timezone: Timezone,
@@ -71,6 +76,43 @@ impl SimpleDate {
         })
     }
 
+    /// Reads the date as UTC and turns it into the local wall clock time
+    pub fn utc_to_local(self) -> Self {
+        Self {
+            date: Local.from_utc_datetime(&self.date).naive_local(),
+            timezone: Timezone::Local,
+        }
+    }
+
+    /// Reads the date as the local wall clock time and turns it into a UTC instant.
+    /// Times repeated when the clocks go back resolve to the earlier one,
+    /// times skipped when they go forward are moved forward with the clocks.
+    pub fn to_utc_instant(&self) -> DateResult<NaiveDateTime> {
+        Local
+            .from_local_datetime(&self.date)
+            .earliest()
+            .or_else(|| {
+                Local
+                    .from_local_datetime(&(self.date + chrono::Duration::hours(1)))
+                    .earliest()
+            })
+            .map(|date| date.naive_utc())
+            .ok_or(DateError::OutOfRange(self.date))
+    }
+
+    /// The local wall clock time of a UTC instant,
+    /// tagged the same way as parsed dates so the two compare equal
+    pub fn from_utc_instant(instant: NaiveDateTime) -> DateResult<Self> {
+        let date = instant
+            .checked_add_offset(Local.offset_from_utc_datetime(&instant))
+            .ok_or(DateError::OutOfRange(instant))?;
+
+        Ok(Self {
+            date,
+            timezone: Timezone::UTC,
+        })
+    }
+
     pub fn add_duration(mut self, duration: impl AsRef<Duration>) -> DateResult<Self> {
         self.date = self.date + duration.as_ref();
 
@@ -224,7 +266,7 @@ impl SimpleDate {
     #[cfg(feature = "chrono")]
     #[allow(clippy::wrong_self_convention)]
     pub fn to_chrono_date(&self) -> chrono::NaiveDateTime {
-        self.date.clone()
+        self.date
     }
 }
 
@@ -487,4 +529,30 @@ mod tests {
         let date = SimpleDate::parse_from_str("2024-04-14 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
         assert_eq!("2024-04-14", date.format("%Y-%m-%d"));
     }
+
+    #[test]
+    fn utc_can_be_turned_into_local_time() {
+        let date = SimpleDate::parse_from_str("2024-04-14 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let local = date.utc_to_local();
+        let expected = Local
+            .from_utc_datetime(&date.to_chrono_date())
+            .naive_local();
+
+        assert_eq!(Timezone::Local, local.get_timezone());
+        assert_eq!(expected, local.to_chrono_date());
+    }
+
+    #[test]
+    fn local_time_can_be_turned_into_a_utc_instant_and_back() {
+        let date = SimpleDate::parse_from_str("2024-04-14 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let instant = date.to_utc_instant().unwrap();
+        let expected = Local
+            .from_local_datetime(&date.to_chrono_date())
+            .earliest()
+            .unwrap()
+            .naive_utc();
+
+        assert_eq!(expected, instant);
+        assert_eq!(date, SimpleDate::from_utc_instant(instant).unwrap());
+    }
 }
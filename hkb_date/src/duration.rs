@@ -18,38 +18,56 @@ pub enum DurationError {
 
 // TODO: do we need negative values here?
 // For me a duration is forwards in time
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Duration {
+    Second(DateUnit),
     Minute(DateUnit),
     Hour(DateUnit),
     Day(DateUnit),
     Week(DateUnit),
     Month(DateUnit),
     Year(DateUnit),
+    /// Durations added one after the other, e.g. 1 hour and 30 minutes
+    Compound(Vec<Duration>),
 }
 
 impl Duration {
+    /// Takes the name of a unit or its abbreviation, e.g. `minute`, `min` or `m`
     pub fn from_string(duration: &str, value: DateUnit) -> Result<Self, DurationError> {
         let duration = match duration {
-            "minute" => Self::Minute(value),
-            "hour" => Self::Hour(value),
-            "day" => Self::Day(value),
-            "week" => Self::Week(value),
-            "month" => Self::Month(value),
-            "year" => Self::Year(value),
+            "second" | "sec" | "s" => Self::Second(value),
+            "minute" | "min" | "m" => Self::Minute(value),
+            "hour" | "hr" | "h" => Self::Hour(value),
+            "day" | "d" => Self::Day(value),
+            "week" | "wk" | "w" => Self::Week(value),
+            "month" | "mo" => Self::Month(value),
+            "year" | "yr" | "y" => Self::Year(value),
             _ => return Err(DurationError::InvalidDurationSpecified(duration.into())),
         };
 
         Ok(duration)
     }
 
-    pub fn get_value(&self) -> DateUnit {
-        *match self {
-            Duration::Minute(v) => v,
-            Duration::Hour(v) => v,
-            Duration::Day(v) => v,
-            Duration::Week(v) => v,
-            Duration::Month(v) => v,
-            Duration::Year(v) => v,
+    /// A single duration stays as it is, several become a compound one
+    pub fn compound(mut durations: Vec<Duration>) -> Self {
+        if durations.len() == 1 {
+            return durations.remove(0);
+        }
+
+        Self::Compound(durations)
+    }
+
+    /// A compound duration has no value, its parts are in different units
+    pub fn get_value(&self) -> Option<DateUnit> {
+        match self {
+            Duration::Second(v) => Some(*v),
+            Duration::Minute(v) => Some(*v),
+            Duration::Hour(v) => Some(*v),
+            Duration::Day(v) => Some(*v),
+            Duration::Week(v) => Some(*v),
+            Duration::Month(v) => Some(*v),
+            Duration::Year(v) => Some(*v),
+            Duration::Compound(_) => None,
         }
     }
 }
@@ -57,12 +75,18 @@ impl Duration {
 impl Display for Duration {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let value = match self {
+            Duration::Second(v) => format!("Second-{v}"),
             Duration::Minute(v) => format!("Minute-{v}"),
             Duration::Hour(v) => format!("Hour-{v}"),
             Duration::Day(v) => format!("Day-{v}"),
             Duration::Week(v) => format!("Week-{v}"),
             Duration::Month(v) => format!("Month-{v}"),
             Duration::Year(v) => format!("Year-{v}"),
+            Duration::Compound(durations) => durations
+                .iter()
+                .map(Duration::to_string)
+                .collect::<Vec<String>>()
+                .join("+"),
         };
 
         write!(f, "{}", value)
@@ -80,6 +104,7 @@ impl std::ops::Add<&Duration> for NaiveDateTime {
 
     fn add(self, rhs: &Duration) -> Self::Output {
         match rhs {
+            Duration::Second(v) => self + (TimeDelta::seconds(*v as i64)),
             Duration::Minute(v) => self + (TimeDelta::minutes(*v as i64)),
             Duration::Hour(v) => self + (TimeDelta::hours(*v as i64)),
             Duration::Day(v) => self + (TimeDelta::days(*v as i64)),
@@ -116,6 +141,9 @@ impl std::ops::Add<&Duration> for NaiveDateTime {
                 self.with_year(year as i32)
                     .unwrap_or_else(|| self.with_year(0).unwrap())
             }
+            Duration::Compound(durations) => durations
+                .iter()
+                .fold(self, |date, duration| date + duration),
         }
     }
 }
@@ -125,6 +153,7 @@ impl std::ops::Sub<&Duration> for NaiveDateTime {
 
     fn sub(self, rhs: &Duration) -> Self::Output {
         match rhs {
+            Duration::Second(v) => self - (TimeDelta::seconds(*v as i64)),
             Duration::Minute(v) => self - (TimeDelta::minutes(*v as i64)),
             Duration::Hour(v) => self - (TimeDelta::hours(*v as i64)),
             Duration::Day(v) => self - (TimeDelta::days(*v as i64)),
@@ -166,6 +195,9 @@ impl std::ops::Sub<&Duration> for NaiveDateTime {
                 self.with_year(year as i32)
                     .unwrap_or_else(|| self.with_year(0).unwrap())
             }
+            Duration::Compound(durations) => durations
+                .iter()
+                .fold(self, |date, duration| date - duration),
         }
     }
 }
@@ -259,4 +291,39 @@ mod tests {
         assert_eq!("59 minutes", STDDuration::new(60 * 59, 0).to_human_string());
         assert_eq!("", STDDuration::new(59, 0).to_human_string());
     }
+
+    #[test]
+    fn it_reads_units_and_their_abbreviations() {
+        assert_eq!(
+            Duration::Second(30),
+            Duration::from_string("s", 30).unwrap()
+        );
+        assert_eq!(
+            Duration::Minute(5),
+            Duration::from_string("min", 5).unwrap()
+        );
+        assert_eq!(Duration::Month(2), Duration::from_string("mo", 2).unwrap());
+        assert_eq!(Duration::Year(1), Duration::from_string("year", 1).unwrap());
+        assert!(Duration::from_string("fortnight", 1).is_err());
+    }
+
+    #[test]
+    fn it_adds_and_subtracts_the_parts_of_a_compound_duration() {
+        let date =
+            NaiveDateTime::parse_from_str("2024-04-14 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let duration = Duration::compound(vec![
+            Duration::Day(1),
+            Duration::Hour(2),
+            Duration::Second(30),
+        ]);
+
+        assert_eq!("2024-04-15 10:00:30", (date + &duration).to_string());
+        assert_eq!("2024-04-13 05:59:30", (date - &duration).to_string());
+        assert_eq!("Day-1+Hour-2+Second-30", duration.to_string());
+        assert_eq!(None, duration.get_value());
+        assert_eq!(
+            Duration::Minute(1),
+            Duration::compound(vec![Duration::Minute(1)])
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use chrono::Datelike;
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike, Weekday};
 use pest::iterators::Pair;
 use pest::Parser;
 use pest_derive::Parser;
@@ -6,6 +6,7 @@ use thiserror::Error as ThisError;
 
 use crate::date::{DateError, SimpleDate};
 use crate::duration::{Duration, DurationError};
+use crate::recurrence::{Frequency, Recurrence, RecurrenceRule, Weekday as RecurrenceWeekday};
 
 #[derive(ThisError, Debug)]
 pub enum DateParsingError {
@@ -51,42 +52,223 @@ fn ctoi(char: char) -> u8 {
     (char as u8) - 48
 }
 
+/// Days from the date to the next weekday with the name,
+/// a week when the date is on that weekday already
+fn days_until_next(date: &SimpleDate, weekday: &str) -> Option<u32> {
+    let weekday = weekday.parse::<chrono::Weekday>().ok()?;
+    let days_since_weekday = weekday.days_since(date.to_chrono_date().weekday());
+
+    if days_since_weekday == 0 {
+        return Some(7);
+    }
+
+    Some(days_since_weekday)
+}
+
+fn parse_cardinal(cardinal: &str) -> u32 {
+    match cardinal {
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9,
+        _ => 0,
+    }
+}
+
+/// The day of e.g. the "last" or the "2nd" weekday in the month
+fn nth_weekday_of_month(year: i32, month: u32, weekday: Weekday, position: &str) -> Option<u32> {
+    let date = match position {
+        "first" | "1st" => NaiveDate::from_weekday_of_month_opt(year, month, weekday, 1),
+        "second" | "2nd" => NaiveDate::from_weekday_of_month_opt(year, month, weekday, 2),
+        "third" | "3rd" => NaiveDate::from_weekday_of_month_opt(year, month, weekday, 3),
+        "fourth" | "4th" => NaiveDate::from_weekday_of_month_opt(year, month, weekday, 4),
+        _ => NaiveDate::from_weekday_of_month_opt(year, month, weekday, 5)
+            .or_else(|| NaiveDate::from_weekday_of_month_opt(year, month, weekday, 4)),
+    };
+
+    date.map(|date| date.day())
+}
+
+/// The weekdays of a `recurrence_weekdays`, a `weekday_group` or a `weekday_date`
+fn parse_weekdays(pair: Pair<Rule>) -> Vec<RecurrenceWeekday> {
+    match pair.as_rule() {
+        Rule::recurrence_weekdays => pair.into_inner().flat_map(parse_weekdays).collect(),
+        Rule::weekday_group if pair.as_str() == "weekend" => {
+            vec![RecurrenceWeekday::Saturday, RecurrenceWeekday::Sunday]
+        }
+        Rule::weekday_group => vec![
+            RecurrenceWeekday::Monday,
+            RecurrenceWeekday::Tuesday,
+            RecurrenceWeekday::Wednesday,
+            RecurrenceWeekday::Thursday,
+            RecurrenceWeekday::Friday,
+        ],
+        _ => vec![pair.as_str().parse::<Weekday>().unwrap().into()],
+    }
+}
+
 #[derive(Parser)]
 #[grammar = "../grammar/human_date.pest"]
 struct PestHumanDateParser;
 
+/// The times phrases like "tomorrow morning" or "tonight" are read as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayParts {
+    pub morning: NaiveTime,
+    pub afternoon: NaiveTime,
+    pub evening: NaiveTime,
+    pub night: NaiveTime,
+}
+
+impl Default for DayParts {
+    fn default() -> Self {
+        Self {
+            morning: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            afternoon: NaiveTime::from_hms_opt(15, 0, 0).unwrap(),
+            evening: NaiveTime::from_hms_opt(19, 0, 0).unwrap(),
+            night: NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
+        }
+    }
+}
+
+impl DayParts {
+    fn time_of(&self, day_part: &str) -> NaiveTime {
+        match day_part {
+            "morning" => self.morning,
+            "afternoon" => self.afternoon,
+            "evening" => self.evening,
+            _ => self.night,
+        }
+    }
+}
+
+/// How slashed dates like "5/3" are read, dotted dates like "5.3." always have the day first.
+/// A slashed date that is only valid the other way around is read that way, e.g. "13/5" as the 13th of May.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    #[default]
+    DayMonth,
+    MonthDay,
+}
+
 pub struct HumanDateParser {
     start_date: SimpleDate,
+    day_parts: DayParts,
+    date_order: DateOrder,
 }
 
 impl HumanDateParser {
     pub fn new(start_date: SimpleDate) -> Self {
-        Self { start_date }
+        Self {
+            start_date,
+            day_parts: DayParts::default(),
+            date_order: DateOrder::default(),
+        }
+    }
+
+    pub fn with_day_parts(mut self, day_parts: DayParts) -> Self {
+        self.day_parts = day_parts;
+
+        self
+    }
+
+    pub fn with_date_order(mut self, date_order: DateOrder) -> Self {
+        self.date_order = date_order;
+
+        self
     }
 }
 
 impl HumanDateParser {
-    fn get_date_based_on_possible_at_sentence(
+    /// The start date at the time of an `AT` sentence or a part of the day, when there is one
+    fn get_date_based_on_possible_time(
         &self,
-        possible_at_sentence: Option<Pair<Rule>>,
+        possible_time: Option<Pair<Rule>>,
     ) -> DateParsingResult {
-        let date = {
-            if let Some(at_sentence) = possible_at_sentence {
-                self.parse_at_sentence(at_sentence)?
-            } else {
-                self.start_date
+        match possible_time {
+            Some(day_part) if day_part.as_rule() == Rule::day_part => {
+                self.get_date_at_day_part(day_part)
             }
-        };
+            Some(at_sentence) => self.parse_at_sentence(at_sentence),
+            None => Ok(self.start_date),
+        }
+    }
+
+    fn get_date_at(&self, time: NaiveTime) -> DateParsingResult {
+        let mut date = self.start_date;
+
+        date.set_hms(time.hour(), time.minute(), 0)?;
 
         Ok(date)
     }
 
-    fn parse_in_sentence(&self, sentence: Pair<Rule>) -> DateParsingResult {
-        let mut inner = sentence.into_inner();
+    fn get_date_at_day_part(&self, day_part: Pair<Rule>) -> DateParsingResult {
+        self.get_date_at(self.day_parts.time_of(day_part.as_str()))
+    }
+
+    /// Midnight is the one at the end of the day, whether it is written as "midnight" or "12am"
+    fn set_midnight(date: &mut SimpleDate) -> Result<(), DateParsingError> {
+        date.set_hms(0, 0, 0)?;
+        *date = date.add_duration(Duration::Day(1))?;
+
+        Ok(())
+    }
+
+    /// Sets the time of a `clock` or a `named_time`.
+    /// A bare hour like "5" is read on the 24-hour clock, the way the hour of "5:00" is.
+    fn set_time(&self, date: &mut SimpleDate, time: Pair<Rule>) -> Result<(), DateParsingError> {
+        if time.as_rule() == Rule::named_time {
+            match time.as_str() {
+                "midnight" => Self::set_midnight(date)?,
+                _ => date.set_hms(12, 0, 0)?,
+            }
+
+            return Ok(());
+        }
+
+        let clock = time.as_str();
+        let mut inner = time.into_inner();
+        let mut hour = inner.next().unwrap().as_str().parse::<u32>().unwrap();
+        let mut minute = 0;
+
+        for pair in inner {
+            match pair.as_rule() {
+                Rule::time => minute = pair.as_str().parse::<u32>().unwrap(),
+                Rule::meridiem => {
+                    if !(1..=12).contains(&hour) {
+                        return Err(DateParsingError::FailedToParseInput(clock.to_owned()));
+                    }
+
+                    // 12am is midnight and 12pm is noon
+                    if hour == 12 && minute == 0 && pair.as_str().starts_with('a') {
+                        return Self::set_midnight(date);
+                    }
+
+                    hour %= 12;
+
+                    if pair.as_str().starts_with('p') {
+                        hour += 12;
+                    }
+                }
+                _ => return Err(DateParsingError::UnknownRuleEncountered()),
+            }
+        }
+
+        date.set_hms(hour, minute, 0)?;
+
+        Ok(())
+    }
+
+    fn parse_amount(&self, amount: Pair<Rule>) -> Result<Duration, DateParsingError> {
+        let mut inner = amount.into_inner();
         let mut pair = inner.next().unwrap();
         let mut duration_value: i64 = 0;
 
-        while !matches!(pair.as_rule(), Rule::duration) {
+        while !matches!(pair.as_rule(), Rule::duration | Rule::duration_letter) {
             let number = ctoi(pair.as_span().as_str().chars().next().unwrap());
 
             duration_value *= 10;
@@ -96,76 +278,209 @@ impl HumanDateParser {
         }
 
         let duration = pair.as_span().as_str();
-        let duration = Duration::from_string(duration, duration_value as u32)?;
-        let date = self.start_date.add_duration(duration)?;
 
-        Ok(date)
+        Ok(Duration::from_string(duration, duration_value as u32)?)
+    }
+
+    fn parse_in_sentence(&self, sentence: Pair<Rule>) -> DateParsingResult {
+        let mut durations = vec![];
+        let mut at_sentence = None;
+
+        for pair in sentence.into_inner() {
+            match pair.as_rule() {
+                Rule::amount => durations.push(self.parse_amount(pair)?),
+                _ => at_sentence = Some(pair),
+            }
+        }
+
+        let date = self.get_date_based_on_possible_time(at_sentence)?;
+
+        Ok(date.add_duration(Duration::compound(durations))?)
     }
 
     fn parse_in_alt_sentence(&self, sentence: Pair<Rule>) -> DateParsingResult {
         let mut inner = sentence.into_inner();
         let pair = inner.next().unwrap();
         let cardinal = pair.as_str();
-        let date = self.get_date_based_on_possible_at_sentence(inner.next())?;
-        let days_to_add = match cardinal {
-            "two" => 2,
-            "three" => 3,
-            "four" => 4,
-            "five" => 5,
-            "six" => 6,
-            "seven" => 7,
-            "eight" => 8,
-            "nine" => 9,
-            _ => 0,
-        };
+        let date = self.get_date_based_on_possible_time(inner.next())?;
+        let days_to_add = parse_cardinal(cardinal);
 
         Ok(date.add_duration(Duration::Day(days_to_add))?)
     }
 
-    fn parse_on_sentence(&self, sentence: Pair<Rule>) -> DateParsingResult {
+    fn parse_in_weeks_on_sentence(&self, sentence: Pair<Rule>) -> DateParsingResult {
         let mut inner = sentence.into_inner();
+        let weeks = match inner.next().unwrap().as_str() {
+            "a" => 1,
+            weeks if weeks.starts_with(|c: char| c.is_ascii_digit()) => weeks
+                .parse::<u32>()
+                .map_err(|_| DateParsingError::FailedToParseInput(weeks.to_owned()))?,
+            cardinal => parse_cardinal(cardinal),
+        };
+        let weekday = inner.next().unwrap().as_str().parse::<Weekday>().unwrap();
+        let date = self.get_date_based_on_possible_time(inner.next())?;
+        let date = date.add_duration(Duration::Week(weeks))?;
+        let current_weekday = self.start_date.to_chrono_date().weekday();
+
+        // weeks start on monday, so it can be earlier in the week than today
+        if weekday.num_days_from_monday() < current_weekday.num_days_from_monday() {
+            return Ok(date.sub_duration(Duration::Day(current_weekday.days_since(weekday)))?);
+        }
+
+        Ok(date.add_duration(Duration::Day(weekday.days_since(current_weekday)))?)
+    }
+
+    /// The day of a `day` like "3rd" or a `date_number` like "3"
+    fn parse_day(pair: Pair<Rule>) -> u32 {
+        let day = pair.as_str();
+        let day = match pair.as_rule() {
+            Rule::day => &day[0..day.len() - 2],
+            _ => day,
+        };
+
+        day.parse::<u32>().unwrap()
+    }
+
+    fn parse_month(pair: Pair<Rule>) -> u32 {
+        (MONTHS.iter().position(|&m| m == pair.as_str()).unwrap() + 1) as u32
+    }
+
+    /// Two digit years are in this century
+    fn parse_year(pair: Pair<Rule>) -> i32 {
+        let year = pair.as_str().parse::<i32>().unwrap();
+
+        if pair.as_str().len() == 2 {
+            2000 + year
+        } else {
+            year
+        }
+    }
+
+    /// The day and the month of a slashed date, see [`DateOrder`]
+    fn order_day_and_month(&self, first: u32, second: u32) -> (u32, u32) {
+        let (day, month) = match self.date_order {
+            DateOrder::DayMonth => (first, second),
+            DateOrder::MonthDay => (second, first),
+        };
+
+        if month > 12 && day <= 12 {
+            return (month, day);
+        }
+
+        (day, month)
+    }
+
+    /// Moves the date to a `calendar_date`, the time of day stays.
+    /// Dates without a year are the next ones from the start date on, today included.
+    fn set_calendar_date(
+        &self,
+        mut date: SimpleDate,
+        calendar_date: Pair<Rule>,
+    ) -> DateParsingResult {
+        let rule = calendar_date.as_rule();
+
+        if rule == Rule::weekday_date {
+            let days = days_until_next(&self.start_date, calendar_date.as_str())
+                .ok_or(DateParsingError::UnknownRuleEncountered())?;
+
+            return Ok(date.add_duration(Duration::Day(days))?);
+        }
+
+        let mut inner = calendar_date.into_inner();
         // We are unwrapping because we are sure we have these in the
         // data structure
-        let (day, month) = {
-            let day = inner.next().unwrap().as_str();
-            let day = day[0..day.len() - 2].parse::<u32>().unwrap();
-            let month = inner.next().unwrap().as_str();
-            let month = (MONTHS.iter().position(|&m| m == month).unwrap() + 1) as u32;
+        let (year, month, day) = match rule {
+            Rule::iso_date => {
+                let year = Self::parse_year(inner.next().unwrap());
+                let month = Self::parse_day(inner.next().unwrap());
+
+                (Some(year), month, Self::parse_day(inner.next().unwrap()))
+            }
+            Rule::dotted_date => {
+                let day = Self::parse_day(inner.next().unwrap());
+                let month = Self::parse_day(inner.next().unwrap());
+
+                (inner.next().map(Self::parse_year), month, day)
+            }
+            Rule::slashed_date => {
+                let first = Self::parse_day(inner.next().unwrap());
+                let second = Self::parse_day(inner.next().unwrap());
+                let (day, month) = self.order_day_and_month(first, second);
+
+                (inner.next().map(Self::parse_year), month, day)
+            }
+            Rule::nth_weekday_date => {
+                let position = inner.next().unwrap().as_str();
+                let weekday = inner.next().unwrap().as_str().parse::<Weekday>().unwrap();
+                let month = Self::parse_month(inner.next().unwrap());
+                let start_date = self.start_date.to_chrono_date().date();
+                let mut year = start_date.year();
+                let mut day = nth_weekday_of_month(year, month, weekday, position);
 
-            (day, month)
+                // the upcoming one, like the dates without a year
+                if day.and_then(|day| NaiveDate::from_ymd_opt(year, month, day)) < Some(start_date)
+                {
+                    year += 1;
+                    day = nth_weekday_of_month(year, month, weekday, position);
+                }
+
+                let day = day.ok_or(DateParsingError::UnknownRuleEncountered())?;
+
+                (Some(year), month, day)
+            }
+            Rule::ordinal_date => {
+                let day = Self::parse_day(inner.next().unwrap());
+
+                (None, Self::parse_month(inner.next().unwrap()), day)
+            }
+            Rule::month_first_date => {
+                let month = Self::parse_month(inner.next().unwrap());
+                let day = Self::parse_day(inner.next().unwrap());
+
+                (inner.next().map(Self::parse_year), month, day)
+            }
+            _ => return Err(DateParsingError::UnknownRuleEncountered()),
         };
-        let mut date = self.get_date_based_on_possible_at_sentence(inner.next())?;
-        let mut year = date.year();
+        let year = year.unwrap_or_else(|| {
+            let start_date = &self.start_date;
 
-        match (date.month(), date.day()) {
-            (m, _) if m > month => year += 1,
-            (m, d) if m == month && d > day => year += 1,
-            _ => {}
-        }
+            match (start_date.month(), start_date.day()) {
+                (m, _) if m > month => start_date.year() + 1,
+                (m, d) if m == month && d > day => start_date.year() + 1,
+                _ => start_date.year(),
+            }
+        });
+        // midnight moved the time to the next day, which carries over to the new date
+        let days_later = (date.to_chrono_date().date() - self.start_date.to_chrono_date().date())
+            .num_days()
+            .max(0) as u32;
 
         date.set_ymd(year, month, day)?;
 
-        Ok(date)
+        Ok(date.add_duration(Duration::Day(days_later))?)
     }
 
-    fn parse_at_sentence(&self, sentence: Pair<Rule>) -> DateParsingResult {
+    fn parse_on_sentence(&self, sentence: Pair<Rule>) -> DateParsingResult {
         let mut inner = sentence.into_inner();
-        let hour = inner.next().unwrap().as_str().parse::<u32>().unwrap();
-        let minute = inner.next().unwrap().as_str().parse::<u32>().unwrap();
-        let date = {
-            let mut on_date = {
-                if let Some(pair) = inner.next() {
-                    self.parse_on_sentence(pair)?
-                } else {
-                    self.start_date
-                }
-            };
+        let calendar_date = inner.next().unwrap();
+        let date = self.get_date_based_on_possible_time(inner.next())?;
 
-            on_date.set_hms(hour, minute, 0)?;
+        self.set_calendar_date(date, calendar_date)
+    }
 
-            on_date
+    fn parse_at_sentence(&self, sentence: Pair<Rule>) -> DateParsingResult {
+        let mut inner = sentence.into_inner();
+        let time = inner.next().unwrap();
+        let mut date = {
+            if let Some(pair) = inner.next() {
+                self.parse_on_sentence(pair)?
+            } else {
+                self.start_date
+            }
         };
 
+        self.set_time(&mut date, time)?;
+
         Ok(date)
     }
 
@@ -173,19 +488,11 @@ impl HumanDateParser {
         let mut inner = sentence.into_inner();
         let pair = inner.next().unwrap();
         let option = pair.as_str();
-        let start_date = self.get_date_based_on_possible_at_sentence(inner.next())?;
+        let start_date = self.get_date_based_on_possible_time(inner.next())?;
 
         match option {
             day if DAYS_OF_WEEK.contains(&day) => {
-                let weekday = day.parse::<chrono::Weekday>().unwrap();
-                let current_weekday = start_date.to_chrono_date().weekday();
-                let mut days_since_weekday = weekday.days_since(current_weekday);
-
-                // we set it for the next same weekday
-                // if the weekday we have specified is the same one as today
-                if days_since_weekday == 0 {
-                    days_since_weekday = 7;
-                }
+                let days_since_weekday = days_until_next(&start_date, day).unwrap();
 
                 Ok(start_date
                     .add_duration(Duration::Day(days_since_weekday))
@@ -201,11 +508,178 @@ impl HumanDateParser {
 
     fn parse_tomorrow_sentence(&self, sentence: Pair<Rule>) -> DateParsingResult {
         let mut inner = sentence.into_inner();
-        let start_date = self.get_date_based_on_possible_at_sentence(inner.next())?;
+        let start_date = self.get_date_based_on_possible_time(inner.next())?;
 
         Ok(start_date.add_duration(Duration::Day(1)).unwrap())
     }
 
+    fn parse_day_after_tomorrow_sentence(&self, sentence: Pair<Rule>) -> DateParsingResult {
+        let mut inner = sentence.into_inner();
+        let start_date = self.get_date_based_on_possible_time(inner.next())?;
+
+        Ok(start_date.add_duration(Duration::Day(2))?)
+    }
+
+    /// The first and the last day of the day, week, month or year the date is in
+    fn get_period_bounds(
+        date: SimpleDate,
+        period: &str,
+        next: bool,
+    ) -> Result<(SimpleDate, SimpleDate), DateParsingError> {
+        let mut first_day = date.start_of_day()?;
+        let length = match period {
+            "day" => Duration::Day(1),
+            "week" => {
+                let weekday = date.to_chrono_date().weekday();
+                first_day =
+                    first_day.sub_duration(Duration::Day(weekday.num_days_from_monday()))?;
+
+                Duration::Week(1)
+            }
+            "month" => {
+                first_day.set_day(1)?;
+
+                Duration::Month(1)
+            }
+            "year" => {
+                first_day.set_ymd(date.year(), 1, 1)?;
+
+                Duration::Year(1)
+            }
+            _ => return Err(DateParsingError::UnknownRuleEncountered()),
+        };
+
+        // moved from the first day, where adding a month can not overflow
+        if next {
+            first_day = first_day.add_duration(&length)?;
+        }
+
+        let last_day = first_day
+            .add_duration(&length)?
+            .sub_duration(Duration::Day(1))?;
+
+        Ok((first_day, last_day))
+    }
+
+    fn parse_boundary_sentence(&self, sentence: Pair<Rule>) -> DateParsingResult {
+        let mut inner = sentence.into_inner();
+        let boundary = inner.next().unwrap().as_str();
+        let mut pair = inner.next().unwrap();
+        let mut next = false;
+
+        if pair.as_rule() == Rule::period_offset {
+            next = pair.as_str() == "next";
+            pair = inner.next().unwrap();
+        }
+
+        let (first_day, last_day) = Self::get_period_bounds(self.start_date, pair.as_str(), next)?;
+        let mut date = match boundary {
+            "end" => last_day.end_of_day()?,
+            _ => first_day,
+        };
+
+        if let Some(time) = inner.next() {
+            self.set_time(&mut date, time)?;
+        }
+
+        Ok(date)
+    }
+
+    fn parse_tonight_sentence(&self) -> DateParsingResult {
+        self.get_date_at(self.day_parts.night)
+    }
+
+    fn parse_this_sentence(&self, sentence: Pair<Rule>) -> DateParsingResult {
+        self.get_date_at_day_part(sentence.into_inner().next().unwrap())
+    }
+
+    /// Parse a human recurrence phrase into a rule, the frequency defaults to weekly
+    /// for phrases with weekdays and to monthly for ones with a day of the month.
+    ///
+    /// Example
+    /// ```rust
+    /// use hkb_date::{HumanDateParser};
+    /// use hkb_date::date::*;
+    /// let date_parser = HumanDateParser::new(SimpleDate::local());
+    /// let rule = date_parser.parse_recurrence("Every other week on Tuesday").unwrap();
+    /// println!("{:?}", rule.occurrences(SimpleDate::local(), 3));
+    ///
+    /// ```
+    pub fn parse_recurrence(
+        &self,
+        input: impl AsRef<str>,
+    ) -> Result<RecurrenceRule, DateParsingError> {
+        let lowercased = input.as_ref().to_lowercase();
+        let failed_to_parse = || DateParsingError::FailedToParseInput(input.as_ref().into());
+        let mut result = PestHumanDateParser::parse(Rule::RECURRENCE, &lowercased)
+            .map_err(|_| failed_to_parse())?;
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut weekdays = vec![];
+        let mut day_of_month = None;
+        let mut time = None;
+
+        for pair in result.next().unwrap().into_inner() {
+            match pair.as_rule() {
+                Rule::recurrence_day_of_month => {
+                    frequency = Some(Frequency::Monthly);
+                    day_of_month = Some(Self::parse_day(pair.into_inner().next().unwrap()));
+                }
+                Rule::recurrence_interval => {
+                    interval = match pair.as_str() {
+                        "other" => 2,
+                        cardinal if cardinal.starts_with(|c: char| c.is_alphabetic()) => {
+                            parse_cardinal(cardinal)
+                        }
+                        number => number.parse::<u32>().map_err(|_| failed_to_parse())?,
+                    }
+                }
+                Rule::recurrence_unit => {
+                    frequency = match pair.into_inner().next().unwrap().as_str() {
+                        "day" => Some(Frequency::Daily),
+                        "week" => Some(Frequency::Weekly),
+                        "month" => Some(Frequency::Monthly),
+                        _ => Some(Frequency::Yearly),
+                    }
+                }
+                Rule::recurrence_weekdays | Rule::weekday_group => {
+                    weekdays.extend(parse_weekdays(pair))
+                }
+                Rule::day => day_of_month = Some(Self::parse_day(pair)),
+                Rule::day_part => time = Some(self.day_parts.time_of(pair.as_str())),
+                Rule::clock | Rule::named_time => {
+                    let mut date = self.start_date;
+
+                    self.set_time(&mut date, pair)?;
+                    time = Some(date.to_chrono_date().time());
+                }
+                _ => return Err(DateParsingError::UnknownRuleEncountered()),
+            }
+        }
+
+        let frequency = frequency.unwrap_or(Frequency::Weekly);
+
+        // e.g. "every day on monday" or "every week on the 1st"
+        if (!weekdays.is_empty() && frequency != Frequency::Weekly)
+            || (day_of_month.is_some() && frequency != Frequency::Monthly)
+        {
+            return Err(failed_to_parse());
+        }
+
+        weekdays.sort();
+        weekdays.dedup();
+
+        let recurrence = Recurrence::new(frequency)
+            .every(interval)
+            .on_weekdays(weekdays);
+
+        Ok(RecurrenceRule {
+            recurrence,
+            time,
+            day_of_month,
+        })
+    }
+
     /// Parse a human date string into a date
     ///
     /// Example
@@ -232,6 +706,12 @@ impl HumanDateParser {
             Rule::ON => self.parse_on_sentence(sentence),
             Rule::NEXT => self.parse_next_sentence(sentence),
             Rule::TOMORROW => self.parse_tomorrow_sentence(sentence),
+            Rule::DAY_AFTER_TOMORROW => self.parse_day_after_tomorrow_sentence(sentence),
+            Rule::IN_WEEKS_ON => self.parse_in_weeks_on_sentence(sentence),
+            Rule::BOUNDARY => self.parse_boundary_sentence(sentence),
+            Rule::TONIGHT => self.parse_tonight_sentence(),
+            Rule::THIS => self.parse_this_sentence(sentence),
+            Rule::DATE => self.parse_on_sentence(sentence),
             _ => Err(DateParsingError::UnknownRuleEncountered()),
         }
     }
@@ -269,6 +749,54 @@ mod tests {
         assert_date_parsing!("In 4 months", "2025-01-29T22:00:00Z", "2024-09-29 22:00:00");
     }
 
+    #[test]
+    fn it_can_parse_in_sentence_with_seconds() {
+        assert_date_parsing!("In 30 seconds", "2024-04-14T08:00:30Z");
+        assert_date_parsing!("In 1 second", "2024-04-14T08:00:01Z");
+        assert_date_parsing!("In 90 secs", "2024-04-14T08:01:30Z");
+        assert_date_parsing!("In 45s", "2024-04-14T08:00:45Z");
+    }
+
+    #[test]
+    fn it_can_parse_in_sentence_with_several_durations() {
+        assert_date_parsing!("In 1 hour and 30 minutes", "2024-04-14T09:30:00Z");
+        assert_date_parsing!("In 2h 15m", "2024-04-14T10:15:00Z");
+        assert_date_parsing!("In 2 days, 3 hours", "2024-04-16T11:00:00Z");
+        assert_date_parsing!("In 1 week, 2 days, and 5 minutes", "2024-04-23T08:05:00Z");
+        assert_date_parsing!("In 1 hr 30 mins 10 secs", "2024-04-14T09:30:10Z");
+        assert_date_parsing!("In 1 year 2 mo", "2025-06-14T08:00:00Z");
+        assert_date_parsing!("In 3 wks and 1d", "2024-05-06T08:00:00Z");
+        assert_date_parsing!("In 1h30m", "2024-04-14T09:30:00Z");
+        assert_date_parsing!("In 2 days at 5pm", "2024-04-16T17:00:00Z");
+        assert_date_parsing!("In 1 week and 2 days at noon", "2024-04-23T12:00:00Z");
+
+        // the parts are added in the order they are written
+        let start_date = "2024-11-30 22:00:00";
+        assert_date_parsing!("In 1 month and 1 day", "2024-12-31T22:00:00Z", start_date);
+        assert_date_parsing!("In 1 day and 1 month", "2025-01-01T22:00:00Z", start_date);
+    }
+
+    #[test]
+    fn it_does_not_parse_unknown_duration_abbreviations() {
+        let date_parser = HumanDateParser::new(SimpleDate::local());
+
+        assert!(date_parser.parse("In 2 mon").is_err());
+        assert!(date_parser.parse("In 5 x").is_err());
+        assert!(date_parser.parse("In 0 minutes").is_err());
+    }
+
+    #[test]
+    fn it_does_not_parse_sentences_with_unread_input() {
+        let date_parser = HumanDateParser::new(SimpleDate::local());
+
+        assert!(date_parser.parse("In 1h30x").is_err());
+        assert!(date_parser.parse("In 10 ms").is_err());
+        assert!(date_parser.parse("In 3 days and").is_err());
+        assert!(date_parser.parse("In 2 days at 5pm tomorrow").is_err());
+        assert!(date_parser.parse("Tomorrow at 5pm please").is_err());
+        assert!(date_parser.parse("At 5pm sharp").is_err());
+    }
+
     #[test]
     fn it_can_parse_in_alt_sentence() {
         assert_date_parsing!("In two days", "2024-04-16T08:00:00Z");
@@ -401,4 +929,361 @@ mod tests {
         assert_date_parsing!("Tomorrow at 15:35", "2024-04-15T15:35:00Z");
         assert_date_parsing!("Tomorrow at 23:59", "2024-04-15T23:59:00Z");
     }
+
+    #[test]
+    fn it_can_parse_12_hour_clocks_and_bare_hours() {
+        assert_date_parsing!("At 5pm", "2024-04-14T17:00:00Z");
+        assert_date_parsing!("At 5 PM", "2024-04-14T17:00:00Z");
+        assert_date_parsing!("At 5:30 p.m.", "2024-04-14T17:30:00Z");
+        assert_date_parsing!("At 9am", "2024-04-14T09:00:00Z");
+        assert_date_parsing!("At 12pm", "2024-04-14T12:00:00Z");
+        assert_date_parsing!("At 12:30am", "2024-04-14T00:30:00Z");
+        assert_date_parsing!("Tomorrow at 7pm", "2024-04-15T19:00:00Z");
+        assert_date_parsing!("At 6pm on the 5th of May", "2024-05-05T18:00:00Z");
+        assert_date_parsing!("On the 5th of May at 6 am", "2024-05-05T06:00:00Z");
+    }
+
+    #[test]
+    fn it_reads_bare_hours_on_the_24_hour_clock() {
+        assert_date_parsing!("At 5", "2024-04-14T05:00:00Z");
+        assert_date_parsing!("At 17", "2024-04-14T17:00:00Z");
+        // even when the hour has already passed today
+        assert_date_parsing!("At 5", "2024-04-14T05:00:00Z", "2024-04-14 15:00:00");
+        assert_date_parsing!("Tomorrow at 5", "2024-04-15T05:00:00Z");
+    }
+
+    #[test]
+    fn it_does_not_parse_impossible_12_hour_clocks() {
+        let date_parser = HumanDateParser::new(SimpleDate::local());
+
+        assert!(date_parser.parse("At 13pm").is_err());
+        assert!(date_parser.parse("At 0am").is_err());
+        assert!(date_parser.parse("At 25").is_err());
+    }
+
+    #[test]
+    fn it_can_parse_named_times() {
+        assert_date_parsing!("At noon", "2024-04-14T12:00:00Z");
+        assert_date_parsing!("At midday", "2024-04-14T12:00:00Z");
+        // midnight is the one at the end of the day
+        assert_date_parsing!("At midnight", "2024-04-15T00:00:00Z");
+        assert_date_parsing!("Tomorrow at midnight", "2024-04-16T00:00:00Z");
+        assert_date_parsing!("At 12am", "2024-04-15T00:00:00Z");
+        assert_date_parsing!("Tomorrow at 12 a.m.", "2024-04-16T00:00:00Z");
+        assert_date_parsing!("At noon on the 1st of May", "2024-05-01T12:00:00Z");
+    }
+
+    #[test]
+    fn it_can_parse_parts_of_the_day() {
+        assert_date_parsing!("Tonight", "2024-04-14T21:00:00Z");
+        assert_date_parsing!("This morning", "2024-04-14T09:00:00Z");
+        assert_date_parsing!("This afternoon", "2024-04-14T15:00:00Z");
+        assert_date_parsing!("This evening", "2024-04-14T19:00:00Z");
+        assert_date_parsing!("Tomorrow morning", "2024-04-15T09:00:00Z");
+        assert_date_parsing!("Tomorrow night", "2024-04-15T21:00:00Z");
+        assert_date_parsing!("Next Friday evening", "2024-04-19T19:00:00Z");
+    }
+
+    #[test]
+    fn it_can_configure_the_parts_of_the_day() {
+        let start_date =
+            SimpleDate::parse_from_str("2024-04-14 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let date_parser = HumanDateParser::new(start_date).with_day_parts(DayParts {
+            morning: NaiveTime::from_hms_opt(7, 30, 0).unwrap(),
+            night: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+            ..DayParts::default()
+        });
+
+        assert_eq!(
+            "2024-04-15T07:30:00Z",
+            date_parser.parse("Tomorrow morning").unwrap().to_string()
+        );
+        assert_eq!(
+            "2024-04-14T23:00:00Z",
+            date_parser.parse("Tonight").unwrap().to_string()
+        );
+        assert_eq!(
+            "2024-04-14T19:00:00Z",
+            date_parser.parse("This evening").unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn it_can_parse_iso_dates() {
+        assert_date_parsing!("2024-05-03", "2024-05-03T08:00:00Z");
+        assert_date_parsing!("2023-05-03", "2023-05-03T08:00:00Z");
+        assert_date_parsing!("On 2024-05-03 at 5pm", "2024-05-03T17:00:00Z");
+        assert_date_parsing!("2024-05-03 at midnight", "2024-05-04T00:00:00Z");
+    }
+
+    #[test]
+    fn it_can_parse_dotted_dates() {
+        assert_date_parsing!("03.05.2024", "2024-05-03T08:00:00Z");
+        assert_date_parsing!("3.5.25", "2025-05-03T08:00:00Z");
+        assert_date_parsing!("3.5.", "2024-05-03T08:00:00Z");
+        // dates without a year are the upcoming ones
+        assert_date_parsing!("On 13.4", "2025-04-13T08:00:00Z");
+        assert_date_parsing!("On 14.4", "2024-04-14T08:00:00Z");
+    }
+
+    #[test]
+    fn it_can_parse_slashed_dates_in_the_configured_order() {
+        let start_date =
+            SimpleDate::parse_from_str("2024-04-14 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let day_first = HumanDateParser::new(start_date);
+        let month_first = HumanDateParser::new(start_date).with_date_order(DateOrder::MonthDay);
+
+        assert_eq!(
+            "2024-05-03T08:00:00Z",
+            day_first.parse("3/5").unwrap().to_string()
+        );
+        assert_eq!(
+            "2024-05-03T08:00:00Z",
+            month_first.parse("5/3").unwrap().to_string()
+        );
+        assert_eq!(
+            "2025-03-05T08:00:00Z",
+            month_first.parse("3/5/2025").unwrap().to_string()
+        );
+        // only one of the orders gives a valid month
+        assert_eq!(
+            "2024-05-13T08:00:00Z",
+            day_first.parse("5/13").unwrap().to_string()
+        );
+        assert_eq!(
+            "2024-05-13T08:00:00Z",
+            month_first.parse("13/5").unwrap().to_string()
+        );
+        // dotted dates always have the day first
+        assert_eq!(
+            "2024-05-03T08:00:00Z",
+            month_first.parse("3.5.2024").unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn it_can_parse_month_first_dates() {
+        assert_date_parsing!("May 3", "2024-05-03T08:00:00Z");
+        assert_date_parsing!("On May 3rd", "2024-05-03T08:00:00Z");
+        assert_date_parsing!("May 3rd, 2025", "2025-05-03T08:00:00Z");
+        assert_date_parsing!("On March 30 at 9:15", "2025-03-30T09:15:00Z");
+    }
+
+    #[test]
+    fn it_can_parse_weekdays() {
+        assert_date_parsing!("On Friday", "2024-04-19T08:00:00Z");
+        assert_date_parsing!("Friday at 5pm", "2024-04-19T17:00:00Z");
+        assert_date_parsing!("Monday morning", "2024-04-15T09:00:00Z");
+        // today is a Sunday, so it is the one next week
+        assert_date_parsing!("On Sunday", "2024-04-21T08:00:00Z");
+    }
+
+    #[test]
+    fn it_does_not_parse_impossible_dates() {
+        let date_parser = HumanDateParser::new(SimpleDate::local());
+
+        assert!(date_parser.parse("31.02.2024").is_err());
+        assert!(date_parser.parse("2024-13-01").is_err());
+        assert!(date_parser.parse("13/13").is_err());
+        assert!(date_parser.parse("May 32").is_err());
+    }
+
+    #[test]
+    fn it_can_parse_day_after_tomorrow_sentence() {
+        assert_date_parsing!("Day after tomorrow", "2024-04-16T08:00:00Z");
+        assert_date_parsing!("The day after tomorrow at 5pm", "2024-04-16T17:00:00Z");
+        assert_date_parsing!("The day after tomorrow evening", "2024-04-16T19:00:00Z");
+    }
+
+    #[test]
+    fn it_can_parse_start_and_end_of_periods() {
+        assert_date_parsing!("End of the day", "2024-04-14T23:59:59Z");
+        // weeks start on monday
+        assert_date_parsing!("End of the week", "2024-04-14T23:59:59Z");
+        assert_date_parsing!("Start of the week", "2024-04-08T00:00:00Z");
+        assert_date_parsing!("Start of next week", "2024-04-15T00:00:00Z");
+        assert_date_parsing!(
+            "End of the week",
+            "2024-04-21T23:59:59Z",
+            "2024-04-17 08:00:00"
+        );
+        assert_date_parsing!("Start of next month", "2024-05-01T00:00:00Z");
+        assert_date_parsing!("The end of this month at 5pm", "2024-04-30T17:00:00Z");
+        assert_date_parsing!(
+            "Beginning of next month at 9",
+            "2024-02-01T09:00:00Z",
+            "2024-01-31 08:00:00"
+        );
+        assert_date_parsing!(
+            "End of next month",
+            "2024-02-29T23:59:59Z",
+            "2024-01-31 08:00:00"
+        );
+        assert_date_parsing!("End of the year", "2024-12-31T23:59:59Z");
+        assert_date_parsing!("Start of next year", "2025-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn it_can_parse_in_weeks_on_sentence() {
+        assert_date_parsing!(
+            "In two weeks on Monday",
+            "2024-04-29T08:00:00Z",
+            "2024-04-17 08:00:00"
+        );
+        assert_date_parsing!(
+            "In 2 weeks on Friday at 10",
+            "2024-05-03T10:00:00Z",
+            "2024-04-17 08:00:00"
+        );
+        assert_date_parsing!("In a week on Saturday", "2024-04-20T08:00:00Z");
+        // plain durations still work
+        assert_date_parsing!("In 2 weeks", "2024-04-28T08:00:00Z");
+    }
+
+    #[test]
+    fn it_can_parse_nth_weekday_of_a_month() {
+        assert_date_parsing!("The last Friday of June", "2024-06-28T08:00:00Z");
+        assert_date_parsing!("On the first Monday of May at 9", "2024-05-06T09:00:00Z");
+        assert_date_parsing!(
+            "2nd Tuesday of April",
+            "2024-04-09T08:00:00Z",
+            "2024-04-01 08:00:00"
+        );
+        // the upcoming one
+        assert_date_parsing!("The 2nd Tuesday of April", "2025-04-08T08:00:00Z");
+        assert_date_parsing!(
+            "The last Sunday of March",
+            "2024-03-31T08:00:00Z",
+            "2024-03-31 08:00:00"
+        );
+    }
+
+    macro_rules! assert_occurrences {
+        ($input:literal, [$($expected:literal),+]) => {
+            let date = SimpleDate::parse_from_str("2024-04-14 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+            let date_parser = HumanDateParser::new(date);
+            let expected: Vec<&str> = vec![$($expected),+];
+            let occurrences = date_parser
+                .parse_recurrence($input)
+                .expect("We should have been able to parse!")
+                .occurrences(date, expected.len());
+
+            assert_eq!(
+                expected,
+                occurrences.iter().map(|date| date.to_string()).collect::<Vec<String>>()
+            );
+        };
+    }
+
+    #[test]
+    fn it_can_parse_recurrences() {
+        let date_parser = HumanDateParser::new(SimpleDate::local());
+
+        assert_eq!(
+            RecurrenceRule::new(Recurrence::new(Frequency::Daily))
+                .at(NaiveTime::from_hms_opt(8, 30, 0).unwrap()),
+            date_parser.parse_recurrence("Every day at 8:30").unwrap()
+        );
+        assert_eq!(
+            RecurrenceRule::new(
+                Recurrence::new(Frequency::Weekly)
+                    .every(2)
+                    .on_weekdays(vec![RecurrenceWeekday::Tuesday])
+            ),
+            date_parser
+                .parse_recurrence("Every other week on Tuesday")
+                .unwrap()
+        );
+        assert_eq!(
+            RecurrenceRule::new(Recurrence::new(Frequency::Monthly)).on_day_of_month(1),
+            date_parser
+                .parse_recurrence("Every 1st of the month")
+                .unwrap()
+        );
+        assert_eq!(
+            RecurrenceRule::new(Recurrence::new(Frequency::Weekly).on_weekdays(vec![
+                RecurrenceWeekday::Monday,
+                RecurrenceWeekday::Tuesday,
+                RecurrenceWeekday::Wednesday,
+                RecurrenceWeekday::Thursday,
+                RecurrenceWeekday::Friday,
+            ]))
+            .at(NaiveTime::from_hms_opt(9, 0, 0).unwrap()),
+            date_parser.parse_recurrence("Weekdays at 9").unwrap()
+        );
+    }
+
+    #[test]
+    fn it_can_get_the_occurrences_of_parsed_recurrences() {
+        // the start date is a sunday at 8:00
+        assert_occurrences!(
+            "Every day at 8:30",
+            [
+                "2024-04-14T08:30:00Z",
+                "2024-04-15T08:30:00Z",
+                "2024-04-16T08:30:00Z"
+            ]
+        );
+        assert_occurrences!(
+            "Every day at 7am",
+            ["2024-04-15T07:00:00Z", "2024-04-16T07:00:00Z"]
+        );
+        assert_occurrences!(
+            "Every other week on Tuesday",
+            [
+                "2024-04-16T08:00:00Z",
+                "2024-04-30T08:00:00Z",
+                "2024-05-14T08:00:00Z"
+            ]
+        );
+        assert_occurrences!(
+            "Every 1st of the month",
+            ["2024-05-01T08:00:00Z", "2024-06-01T08:00:00Z"]
+        );
+        assert_occurrences!(
+            "Weekdays at 9",
+            [
+                "2024-04-15T09:00:00Z",
+                "2024-04-16T09:00:00Z",
+                "2024-04-17T09:00:00Z"
+            ]
+        );
+        assert_occurrences!(
+            "Every Monday and Friday evening",
+            [
+                "2024-04-15T19:00:00Z",
+                "2024-04-19T19:00:00Z",
+                "2024-04-22T19:00:00Z"
+            ]
+        );
+        assert_occurrences!(
+            "Every 3 months on the 31st at noon",
+            ["2024-05-31T12:00:00Z", "2024-08-31T12:00:00Z"]
+        );
+        assert_occurrences!(
+            "Every weekend",
+            [
+                "2024-04-14T08:00:00Z",
+                "2024-04-20T08:00:00Z",
+                "2024-04-21T08:00:00Z"
+            ]
+        );
+        assert_occurrences!(
+            "Every year",
+            ["2024-04-14T08:00:00Z", "2025-04-14T08:00:00Z"]
+        );
+    }
+
+    #[test]
+    fn it_does_not_parse_invalid_recurrences() {
+        let date_parser = HumanDateParser::new(SimpleDate::local());
+
+        assert!(date_parser.parse_recurrence("Every").is_err());
+        assert!(date_parser.parse_recurrence("Every day on Monday").is_err());
+        assert!(date_parser
+            .parse_recurrence("Every week on the 1st")
+            .is_err());
+        assert!(date_parser.parse_recurrence("Every 0 days").is_err());
+        assert!(date_parser.parse_recurrence("Tomorrow").is_err());
+    }
 }
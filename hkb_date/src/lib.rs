@@ -1,5 +1,6 @@
 pub mod date;
 pub mod duration;
 mod human_date_parsing;
+pub mod recurrence;
 
-pub use human_date_parsing::HumanDateParser;
+pub use human_date_parsing::{DateOrder, DayParts, HumanDateParser};
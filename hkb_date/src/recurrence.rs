@@ -0,0 +1,438 @@
+use chrono::{Datelike, NaiveDate, NaiveTime, TimeDelta, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::date::SimpleDate;
+
+const MONTHS_IN_A_YEAR: i32 = 12;
+// Safety net for rules that can never be satisfied again
+// e.g. the 31st of the month with an interval that only hits shorter months
+const MAX_LOOKUP_ITERATIONS: u32 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub fn days_from_monday(&self) -> u32 {
+        *self as u32
+    }
+}
+
+impl From<chrono::Weekday> for Weekday {
+    fn from(val: chrono::Weekday) -> Self {
+        match val {
+            chrono::Weekday::Mon => Weekday::Monday,
+            chrono::Weekday::Tue => Weekday::Tuesday,
+            chrono::Weekday::Wed => Weekday::Wednesday,
+            chrono::Weekday::Thu => Weekday::Thursday,
+            chrono::Weekday::Fri => Weekday::Friday,
+            chrono::Weekday::Sat => Weekday::Saturday,
+            chrono::Weekday::Sun => Weekday::Sunday,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecurrenceEnd {
+    Never,
+    On(SimpleDate),
+    After(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub weekdays: Vec<Weekday>,
+    pub end: RecurrenceEnd,
+}
+
+impl Recurrence {
+    pub fn new(frequency: Frequency) -> Self {
+        Self {
+            frequency,
+            interval: 1,
+            weekdays: vec![],
+            end: RecurrenceEnd::Never,
+        }
+    }
+
+    pub fn every(mut self, interval: u32) -> Self {
+        self.interval = interval;
+
+        self
+    }
+
+    pub fn on_weekdays(mut self, weekdays: Vec<Weekday>) -> Self {
+        self.weekdays = weekdays;
+
+        self
+    }
+
+    pub fn ending(mut self, end: RecurrenceEnd) -> Self {
+        self.end = end;
+
+        self
+    }
+}
+
+impl Recurrence {
+    fn interval(&self) -> u32 {
+        std::cmp::max(1, self.interval)
+    }
+
+    fn next_weekly_date(&self, date: NaiveDate) -> Option<NaiveDate> {
+        let interval = self.interval() as i64;
+
+        if self.weekdays.is_empty() {
+            return date.checked_add_signed(TimeDelta::weeks(interval));
+        }
+
+        let mut weekdays = self.weekdays.clone();
+        weekdays.sort();
+        weekdays.dedup();
+
+        let current_weekday = date.weekday().num_days_from_monday();
+
+        // first try to find a weekday later in the same week
+        if let Some(weekday) = weekdays
+            .iter()
+            .find(|weekday| weekday.days_from_monday() > current_weekday)
+        {
+            let days = (weekday.days_from_monday() - current_weekday) as i64;
+
+            return date.checked_add_signed(TimeDelta::days(days));
+        }
+
+        // otherwise jump to the first selected weekday in the next week of the interval
+        let start_of_week = date.checked_sub_signed(TimeDelta::days(current_weekday as i64))?;
+        let first_weekday = weekdays.first()?.days_from_monday() as i64;
+
+        start_of_week
+            .checked_add_signed(TimeDelta::weeks(interval))?
+            .checked_add_signed(TimeDelta::days(first_weekday))
+    }
+
+    fn next_monthly_date(&self, date: NaiveDate) -> Option<NaiveDate> {
+        let interval = self.interval() as i32;
+        let start_month = date.year() * MONTHS_IN_A_YEAR + date.month0() as i32;
+
+        // months that do not have the day (e.g. the 31st) are skipped
+        (1..=MAX_LOOKUP_ITERATIONS as i32).find_map(|i| {
+            let month = start_month + interval * i;
+            let year = month.div_euclid(MONTHS_IN_A_YEAR);
+            let month = month.rem_euclid(MONTHS_IN_A_YEAR) as u32 + 1;
+
+            NaiveDate::from_ymd_opt(year, month, date.day())
+        })
+    }
+
+    fn next_yearly_date(&self, date: NaiveDate) -> Option<NaiveDate> {
+        let interval = self.interval() as i32;
+
+        // years that do not have the day (e.g. the 29th of February) are skipped
+        (1..=MAX_LOOKUP_ITERATIONS as i32).find_map(|i| {
+            NaiveDate::from_ymd_opt(date.year() + interval * i, date.month(), date.day())
+        })
+    }
+
+    /// Get the occurrence that follows `date`.
+    /// `occurrence` is the position of `date` in the series, starting from 1.
+    /// Returns `None` when the series has ended.
+    ///
+    /// Example
+    /// ```rust
+    /// use hkb_date::date::SimpleDate;
+    /// use hkb_date::recurrence::{Frequency, Recurrence};
+    /// let date = SimpleDate::parse_from_str("2024-04-14 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+    /// let recurrence = Recurrence::new(Frequency::Daily).every(2);
+    /// let next = recurrence.next_occurrence(date, 1).unwrap();
+    ///
+    /// assert_eq!("2024-04-16T08:00:00Z", next.to_string());
+    /// ```
+    pub fn next_occurrence(&self, date: SimpleDate, occurrence: u32) -> Option<SimpleDate> {
+        if let RecurrenceEnd::After(count) = self.end {
+            if occurrence >= count {
+                return None;
+            }
+        }
+
+        let current = date.to_chrono_date().date();
+        let next = match self.frequency {
+            Frequency::Daily => current.checked_add_signed(TimeDelta::days(self.interval() as i64)),
+            Frequency::Weekly => self.next_weekly_date(current),
+            Frequency::Monthly => self.next_monthly_date(current),
+            Frequency::Yearly => self.next_yearly_date(current),
+        }?;
+
+        let mut next_date = date;
+        next_date
+            .set_ymd(next.year(), next.month(), next.day())
+            .ok()?;
+
+        if let RecurrenceEnd::On(end_date) = self.end {
+            if next_date > end_date {
+                return None;
+            }
+        }
+
+        Some(next_date)
+    }
+}
+
+/// A recurrence with the time of the day and the day of the month of its occurrences,
+/// e.g. the result of `HumanDateParser::parse_recurrence`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub recurrence: Recurrence,
+    pub time: Option<NaiveTime>,
+    pub day_of_month: Option<u32>,
+}
+
+impl RecurrenceRule {
+    pub fn new(recurrence: Recurrence) -> Self {
+        Self {
+            recurrence,
+            time: None,
+            day_of_month: None,
+        }
+    }
+
+    pub fn at(mut self, time: NaiveTime) -> Self {
+        self.time = Some(time);
+
+        self
+    }
+
+    pub fn on_day_of_month(mut self, day: u32) -> Self {
+        self.day_of_month = Some(day);
+
+        self
+    }
+}
+
+impl RecurrenceRule {
+    fn allows(&self, date: NaiveDate) -> bool {
+        match self.recurrence.frequency {
+            Frequency::Weekly => {
+                self.recurrence.weekdays.is_empty()
+                    || self.recurrence.weekdays.contains(&date.weekday().into())
+            }
+            Frequency::Monthly => self.day_of_month.map_or(true, |day| date.day() == day),
+            _ => true,
+        }
+    }
+
+    /// Get the first occurrence at or after `date`,
+    /// the rule's time of the day on the first day the rule allows.
+    pub fn first_occurrence(&self, date: SimpleDate) -> Option<SimpleDate> {
+        let mut first_date = date;
+
+        if let Some(time) = self.time {
+            first_date
+                .set_hms(time.hour(), time.minute(), time.second())
+                .ok()?;
+        }
+
+        let mut current = first_date.to_chrono_date().date();
+
+        // the time has already passed today
+        if first_date < date {
+            current = current.checked_add_signed(TimeDelta::days(1))?;
+        }
+
+        let first = (0..MAX_LOOKUP_ITERATIONS as i64)
+            .filter_map(|days| current.checked_add_signed(TimeDelta::days(days)))
+            .find(|day| self.allows(*day))?;
+
+        first_date
+            .set_ymd(first.year(), first.month(), first.day())
+            .ok()?;
+
+        Some(first_date)
+    }
+
+    /// Get the first `count` occurrences at or after `date`,
+    /// fewer when the recurrence ends before that.
+    ///
+    /// Example
+    /// ```rust
+    /// use hkb_date::date::SimpleDate;
+    /// use hkb_date::recurrence::{Frequency, Recurrence, RecurrenceRule};
+    /// let date = SimpleDate::parse_from_str("2024-04-14 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+    /// let rule = RecurrenceRule::new(Recurrence::new(Frequency::Monthly)).on_day_of_month(1);
+    /// let occurrences = rule.occurrences(date, 2);
+    ///
+    /// assert_eq!("2024-05-01T08:00:00Z", occurrences[0].to_string());
+    /// assert_eq!("2024-06-01T08:00:00Z", occurrences[1].to_string());
+    /// ```
+    pub fn occurrences(&self, date: SimpleDate, count: usize) -> Vec<SimpleDate> {
+        let first = self.first_occurrence(date).map(|first| (first, 1));
+
+        std::iter::successors(first, |(date, occurrence)| {
+            self.recurrence
+                .next_occurrence(*date, *occurrence)
+                .map(|next| (next, occurrence + 1))
+        })
+        .map(|(date, _)| date)
+        .take(count)
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! assert_next_occurrence {
+        ($recurrence:expr, $date:literal, $expected:literal) => {
+            assert_next_occurrence!($recurrence, $date, 1, $expected);
+        };
+        ($recurrence:expr, $date:literal, $occurrence:expr, $expected:literal) => {
+            let date = SimpleDate::parse_from_str($date, "%Y-%m-%d %H:%M:%S").unwrap();
+            let next = $recurrence
+                .next_occurrence(date, $occurrence)
+                .expect("We should have a next occurrence!");
+
+            assert_eq!($expected, next.to_string());
+        };
+    }
+
+    #[test]
+    fn it_can_get_next_daily_occurrence() {
+        let recurrence = Recurrence::new(Frequency::Daily);
+        assert_next_occurrence!(recurrence, "2024-04-14 08:00:00", "2024-04-15T08:00:00Z");
+        assert_next_occurrence!(recurrence, "2024-12-31 22:00:00", "2025-01-01T22:00:00Z");
+
+        let recurrence = Recurrence::new(Frequency::Daily).every(3);
+        assert_next_occurrence!(recurrence, "2024-04-14 08:00:00", "2024-04-17T08:00:00Z");
+    }
+
+    #[test]
+    fn it_can_get_next_weekly_occurrence() {
+        let recurrence = Recurrence::new(Frequency::Weekly);
+        assert_next_occurrence!(recurrence, "2024-04-14 08:00:00", "2024-04-21T08:00:00Z");
+
+        let recurrence = Recurrence::new(Frequency::Weekly).every(2);
+        assert_next_occurrence!(recurrence, "2024-04-14 08:00:00", "2024-04-28T08:00:00Z");
+    }
+
+    #[test]
+    fn it_can_get_next_weekly_occurrence_on_weekdays() {
+        let recurrence = Recurrence::new(Frequency::Weekly).on_weekdays(vec![
+            Weekday::Friday,
+            Weekday::Monday,
+            Weekday::Wednesday,
+        ]);
+
+        // 2024-04-15 is a monday
+        assert_next_occurrence!(recurrence, "2024-04-15 09:00:00", "2024-04-17T09:00:00Z");
+        assert_next_occurrence!(recurrence, "2024-04-17 09:00:00", "2024-04-19T09:00:00Z");
+        assert_next_occurrence!(recurrence, "2024-04-19 09:00:00", "2024-04-22T09:00:00Z");
+
+        let recurrence = Recurrence::new(Frequency::Weekly)
+            .every(2)
+            .on_weekdays(vec![Weekday::Tuesday, Weekday::Thursday]);
+
+        assert_next_occurrence!(recurrence, "2024-04-16 09:00:00", "2024-04-18T09:00:00Z");
+        assert_next_occurrence!(recurrence, "2024-04-18 09:00:00", "2024-04-30T09:00:00Z");
+    }
+
+    #[test]
+    fn it_can_get_next_monthly_occurrence() {
+        let recurrence = Recurrence::new(Frequency::Monthly);
+        assert_next_occurrence!(recurrence, "2024-04-14 08:00:00", "2024-05-14T08:00:00Z");
+        assert_next_occurrence!(recurrence, "2024-12-05 08:00:00", "2025-01-05T08:00:00Z");
+
+        // months without the 31st are skipped
+        assert_next_occurrence!(recurrence, "2024-01-31 08:00:00", "2024-03-31T08:00:00Z");
+
+        let recurrence = Recurrence::new(Frequency::Monthly).every(3);
+        assert_next_occurrence!(recurrence, "2024-11-14 08:00:00", "2025-02-14T08:00:00Z");
+    }
+
+    #[test]
+    fn it_can_get_next_yearly_occurrence() {
+        let recurrence = Recurrence::new(Frequency::Yearly);
+        assert_next_occurrence!(recurrence, "2024-04-14 08:00:00", "2025-04-14T08:00:00Z");
+
+        // only leap years have the 29th of february
+        assert_next_occurrence!(recurrence, "2024-02-29 08:00:00", "2028-02-29T08:00:00Z");
+    }
+
+    #[test]
+    fn it_stops_after_count_is_reached() {
+        let recurrence = Recurrence::new(Frequency::Daily).ending(RecurrenceEnd::After(3));
+        let date = SimpleDate::parse_from_str("2024-04-14 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        assert_next_occurrence!(recurrence, "2024-04-14 08:00:00", 2, "2024-04-15T08:00:00Z");
+        assert!(recurrence.next_occurrence(date, 3).is_none());
+    }
+
+    #[test]
+    fn it_stops_after_end_date() {
+        let end_date =
+            SimpleDate::parse_from_str("2024-04-16 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let recurrence = Recurrence::new(Frequency::Daily).ending(RecurrenceEnd::On(end_date));
+
+        assert_next_occurrence!(recurrence, "2024-04-15 08:00:00", "2024-04-16T08:00:00Z");
+        assert!(recurrence.next_occurrence(end_date, 1).is_none());
+    }
+
+    #[test]
+    fn it_can_get_the_occurrences_of_a_rule() {
+        let date = SimpleDate::parse_from_str("2024-04-14 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let rule = RecurrenceRule::new(
+            Recurrence::new(Frequency::Weekly)
+                .on_weekdays(vec![Weekday::Wednesday, Weekday::Saturday]),
+        )
+        .at(NaiveTime::from_hms_opt(18, 0, 0).unwrap());
+        let occurrences: Vec<String> = rule
+            .occurrences(date, 3)
+            .iter()
+            .map(|date| date.to_string())
+            .collect();
+
+        assert_eq!(
+            vec![
+                "2024-04-17T18:00:00Z",
+                "2024-04-20T18:00:00Z",
+                "2024-04-24T18:00:00Z"
+            ],
+            occurrences
+        );
+    }
+
+    #[test]
+    fn it_gets_the_occurrences_of_a_rule_until_the_end() {
+        let date = SimpleDate::parse_from_str("2024-04-14 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let rule =
+            RecurrenceRule::new(Recurrence::new(Frequency::Daily).ending(RecurrenceEnd::After(2)));
+
+        assert_eq!(2, rule.occurrences(date, 5).len());
+
+        // months without the 30th are skipped
+        let rule = RecurrenceRule::new(Recurrence::new(Frequency::Monthly)).on_day_of_month(30);
+        let date = SimpleDate::parse_from_str("2024-01-31 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        assert_eq!(
+            "2024-03-30T08:00:00Z",
+            rule.occurrences(date, 1)[0].to_string()
+        );
+    }
+}